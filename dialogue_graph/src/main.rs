@@ -71,7 +71,7 @@ fn count_whitespaces(line: &str) -> usize {
 fn index_from_whitespaces(line: &str) -> usize {
     let whitespaces = count_whitespaces(line);
     assert!(
-        whitespaces % 4 == 0,
+        whitespaces.is_multiple_of(4),
         "Whitespaces should always be 4 chars long, {}",
         line
     );
@@ -1,12 +1,21 @@
-use std::fs::{read_dir, read_to_string};
+use std::{
+    collections::HashSet,
+    fs::{read_dir, read_to_string},
+};
 
 use petgraph::{dot::Dot, Direction};
 use pretty_assertions::assert_eq;
 
-use crate::{construct_graph, try_read_yarn_contents, PATH_TO_DIALOGUES};
+use crate::{construct_graph, try_read_yarn_contents, ATTR_DELIMETER, PATH_TO_DIALOGUES};
 
 const PATH_TO_YARN: &str = "./DUMMY.yarn";
 const PATH_TO_DOT: &str = "./DUMMY.dot";
+// Nodes the game starts directly when NPCs talk as a group, nothing jumps to them.
+const GROUP_NODES: [&str; 3] = [
+    "EleonoreWithJotem",
+    "IsabelleWithJotem",
+    "IonasAndAntoniusWithJotem",
+];
 
 #[test]
 fn graph_construction() {
@@ -22,6 +31,7 @@ fn graph_construction() {
 
 #[test]
 fn validate_no_hanging_nodes() {
+    let mut group_nodes = HashSet::new();
     for entry in
         read_dir(format!("../{}", PATH_TO_DIALOGUES)).expect("Can't read entries in current dir")
     {
@@ -32,6 +42,15 @@ fn validate_no_hanging_nodes() {
 
         let graph = construct_graph(contents);
         for index in graph.node_indices().skip(1) {
+            let node = graph.node_weight(index).expect("Node should exist");
+            let title = node
+                .split(ATTR_DELIMETER)
+                .next()
+                .and_then(|label| label.strip_prefix("title: "));
+            if let Some(title) = title.filter(|title| GROUP_NODES.contains(title)) {
+                group_nodes.insert(title.to_string());
+                continue;
+            }
             assert!(
                 graph.edges_directed(index, Direction::Incoming).count() != 0,
                 "There is a node that doesn't have any incoming edges. This should only be the case for the very first node.\nfile: '{}', node: '{}'",
@@ -40,4 +59,12 @@ fn validate_no_hanging_nodes() {
             );
         }
     }
+
+    for node in GROUP_NODES {
+        assert!(
+            group_nodes.contains(node),
+            "The group node '{}' doesn't exist in any dialogue",
+            node
+        );
+    }
 }
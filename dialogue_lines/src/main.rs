@@ -1,4 +1,5 @@
 use std::{
+    cmp::Reverse,
     collections::HashMap,
     fs::{self, DirEntry},
     io::Error,
@@ -124,7 +125,7 @@ fn print_individual_npc_lines() {
     });
 
     let mut npc_lines_vec = npc_lines.into_iter().collect::<Vec<(String, usize)>>();
-    npc_lines_vec.sort_by(|x, y| y.1.cmp(&x.1));
+    npc_lines_vec.sort_by_key(|x| Reverse(x.1));
 
     for (key, value) in npc_lines_vec {
         let chars = key.chars().count();
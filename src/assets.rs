@@ -33,7 +33,15 @@ pub struct GameAssets {
     pub eleonore_texture: Handle<Image>,
     #[asset(texture_atlas(tile_size_x = 64, tile_size_y = 64, columns = 9, rows = 1))]
     pub eleonore_layout: Handle<TextureAtlasLayout>,
-    #[asset(paths("npc/eleonore.trickfilm#idle",), collection(typed))]
+    #[asset(
+        paths(
+            "npc/eleonore.trickfilm#idle",
+            "npc/eleonore.trickfilm#walk",
+            "npc/eleonore.trickfilm#sit",
+            "npc/eleonore.trickfilm#fish",
+        ),
+        collection(typed)
+    )]
     pub eleonore_animations: Vec<Handle<AnimationClip2D>>,
     #[asset(path = "npc/eleonore_shadow.png")]
     pub eleonore_shadow_texture: Handle<Image>,
@@ -44,28 +52,60 @@ pub struct GameAssets {
     pub jotem_texture: Handle<Image>,
     #[asset(texture_atlas(tile_size_x = 64, tile_size_y = 64, columns = 6, rows = 1))]
     pub jotem_layout: Handle<TextureAtlasLayout>,
-    #[asset(paths("npc/jotem.trickfilm#idle",), collection(typed))]
+    #[asset(
+        paths(
+            "npc/jotem.trickfilm#idle",
+            "npc/jotem.trickfilm#walk",
+            "npc/jotem.trickfilm#sit",
+            "npc/jotem.trickfilm#fish",
+        ),
+        collection(typed)
+    )]
     pub jotem_animations: Vec<Handle<AnimationClip2D>>,
 
     #[asset(path = "npc/isabelle.png")]
     pub isabelle_texture: Handle<Image>,
     #[asset(texture_atlas(tile_size_x = 48, tile_size_y = 64, columns = 7, rows = 1))]
     pub isabelle_layout: Handle<TextureAtlasLayout>,
-    #[asset(paths("npc/isabelle.trickfilm#idle",), collection(typed))]
+    #[asset(
+        paths(
+            "npc/isabelle.trickfilm#idle",
+            "npc/isabelle.trickfilm#walk",
+            "npc/isabelle.trickfilm#sit",
+            "npc/isabelle.trickfilm#fish",
+        ),
+        collection(typed)
+    )]
     pub isabelle_animations: Vec<Handle<AnimationClip2D>>,
 
     #[asset(path = "npc/antonius.png")]
     pub antonius_texture: Handle<Image>,
     #[asset(texture_atlas(tile_size_x = 80, tile_size_y = 80, columns = 5, rows = 1))]
     pub antonius_layout: Handle<TextureAtlasLayout>,
-    #[asset(paths("npc/antonius.trickfilm#idle",), collection(typed))]
+    #[asset(
+        paths(
+            "npc/antonius.trickfilm#idle",
+            "npc/antonius.trickfilm#walk",
+            "npc/antonius.trickfilm#sit",
+            "npc/antonius.trickfilm#fish",
+        ),
+        collection(typed)
+    )]
     pub antonius_animations: Vec<Handle<AnimationClip2D>>,
 
     #[asset(path = "npc/ionas.png")]
     pub ionas_texture: Handle<Image>,
     #[asset(texture_atlas(tile_size_x = 80, tile_size_y = 80, columns = 5, rows = 1))]
     pub ionas_layout: Handle<TextureAtlasLayout>,
-    #[asset(paths("npc/ionas.trickfilm#idle",), collection(typed))]
+    #[asset(
+        paths(
+            "npc/ionas.trickfilm#idle",
+            "npc/ionas.trickfilm#walk",
+            "npc/ionas.trickfilm#sit",
+            "npc/ionas.trickfilm#fish",
+        ),
+        collection(typed)
+    )]
     pub ionas_animations: Vec<Handle<AnimationClip2D>>,
 
     // --- MAP ---
@@ -1,9 +1,12 @@
-use rand::{thread_rng, Rng};
+use rand::Rng;
 
 use bevy::{prelude::*, utils::HashSet};
 use bevy_kira_audio::prelude::{AudioSource, *};
 
-use crate::GameState;
+use crate::{
+    utils::{RngStream, SeededRng},
+    GameState,
+};
 
 use super::{spacial::SpacialSound, GameAudio};
 
@@ -44,10 +47,11 @@ fn play_sounds(
     mut commands: Commands,
     audio: Res<Audio>,
     game_audio: Res<GameAudio>,
+    seeded_rng: Res<SeededRng>,
     mut repeating_sounds: ResMut<RepeatingSounds>,
     mut ev_play_sound: EventReader<PlaySound>,
 ) {
-    let mut rng = thread_rng();
+    let mut rng = seeded_rng.lock(RngStream::Sounds);
     let mut added_sounds: HashSet<Handle<AudioSource>> = HashSet::new();
 
     for ev in ev_play_sound.read() {
@@ -24,6 +24,7 @@ use bevy_yarnspinner::prelude::*;
 
 const BACKGROUND_COLOR: Color = Color::BLACK;
 const DEFAULT_WINDOW_WIDTH: f32 = 1280.0;
+const DEFAULT_WINDOW_HEIGHT: f32 = DEFAULT_WINDOW_WIDTH * 9.0 / 16.0;
 
 #[derive(States, Clone, Eq, PartialEq, Debug, Hash, Default)]
 pub enum GameState {
@@ -47,12 +48,15 @@ fn main() {
                     primary_window: Some(Window {
                         present_mode: PresentMode::Fifo,
                         mode: WindowMode::Windowed,
-                        resizable: false,
-                        fit_canvas_to_parent: false,
+                        // The viewport keeps any window size in shape, see `world::viewport`.
+                        resizable: true,
+                        // On the web the canvas follows the browser window,
+                        // which may be a phone in portrait mode.
+                        fit_canvas_to_parent: cfg!(target_arch = "wasm32"),
                         canvas: Some("#game-canvas".to_string()),
                         resolution: WindowResolution::new(
                             DEFAULT_WINDOW_WIDTH,
-                            DEFAULT_WINDOW_WIDTH * 9.0 / 16.0,
+                            DEFAULT_WINDOW_HEIGHT,
                         ),
                         ..default()
                     }),
@@ -71,6 +75,10 @@ fn main() {
                 YarnFileSource::file("dialogue/jotem.yarn"),
                 YarnFileSource::file("dialogue/isabelle.yarn"),
                 YarnFileSource::file("dialogue/ionas-and-antonius.yarn"),
+                YarnFileSource::file("dialogue/world/points-of-interest.yarn"),
+                YarnFileSource::file("dialogue/world/barks.yarn"),
+                YarnFileSource::file("dialogue/world/flora.yarn"),
+                YarnFileSource::file("dialogue/world/companions.yarn"),
             ])
             .with_development_file_generation(DevelopmentFileGeneration::None),
             TweeningPlugin,
@@ -0,0 +1,235 @@
+use std::str::FromStr;
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_yarnspinner::prelude::*;
+use rand::seq::SliceRandom;
+
+use crate::{
+    player::{Player, PlayerState, NPC_PROXIMITY_DISTANCE},
+    utils::{RngStream, SeededRng},
+    world::camera::YSortChild,
+    GameAssets, GameState,
+};
+
+use super::{companion::Companion, Npc, NpcDialogue};
+
+// Barks start a bit before the player is close enough to talk.
+const BARK_DISTANCE: f32 = 2.0 * NPC_PROXIMITY_DISTANCE;
+const BARK_COOLDOWN: f32 = 25.0;
+const BUBBLE_DURATION: f32 = 4.0;
+const BUBBLE_FONT_SIZE: f32 = 40.0;
+const BUBBLE_SCALE: f32 = 0.2;
+// Right above the dialogue start hint.
+const BUBBLE_OFFSET: Vec3 = Vec3::new(0.0, 60.0, 0.0);
+
+const BARKS_NODE_SUFFIX: &str = "Barks";
+pub const GREETING_TAG: &str = "greeting";
+pub const MENTIONED_TAG: &str = "mentioned";
+pub const TALKED_TAG: &str = "talked";
+
+/// A short line of text above a character's head.
+/// It doesn't block anything and disappears on its own.
+#[derive(Component)]
+pub struct SpeechBubble {
+    timer: Timer,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum BarkCondition {
+    /// The player never heard of the NPC.
+    Greeting,
+    /// Another NPC mentioned this NPC, but the player didn't talk to them yet.
+    Mentioned,
+    /// The player already talked to the NPC.
+    Talked,
+    Any,
+}
+
+impl BarkCondition {
+    fn from_tags(tags: &[String]) -> Self {
+        for tag in tags.iter().map(|t| t.trim_start_matches('#')) {
+            match tag {
+                GREETING_TAG => return BarkCondition::Greeting,
+                MENTIONED_TAG => return BarkCondition::Mentioned,
+                TALKED_TAG => return BarkCondition::Talked,
+                _ => {}
+            }
+        }
+        BarkCondition::Any
+    }
+
+    fn of_npc(npc: &Npc) -> Self {
+        if npc.was_talked_to {
+            BarkCondition::Talked
+        } else if !npc.was_mentioned_by.is_empty() {
+            BarkCondition::Mentioned
+        } else {
+            BarkCondition::Greeting
+        }
+    }
+}
+
+struct Bark {
+    text: String,
+    condition: BarkCondition,
+}
+
+/// All the barks of all NPCs, taken from the `<Npc>Barks` yarn nodes.
+/// These nodes never get run, we only use their lines.
+#[derive(Resource, Default)]
+struct Barks(HashMap<NpcDialogue, Vec<Bark>>);
+
+#[derive(Component)]
+struct BarkCooldown(Timer);
+
+pub fn spawn_speech_bubble(
+    commands: &mut Commands,
+    assets: &Res<GameAssets>,
+    speaker: Entity,
+    line: &str,
+) {
+    let text_style = TextStyle {
+        font: assets.pixel_font.clone(),
+        font_size: BUBBLE_FONT_SIZE,
+        color: Color::WHITE,
+    };
+
+    let bubble = commands
+        .spawn((
+            SpeechBubble {
+                timer: Timer::from_seconds(BUBBLE_DURATION, TimerMode::Once),
+            },
+            YSortChild(100.0),
+            Text2dBundle {
+                text: Text::from_section(line, text_style),
+                transform: Transform::from_translation(BUBBLE_OFFSET)
+                    .with_scale(Vec3::splat(BUBBLE_SCALE)),
+                ..default()
+            },
+        ))
+        .id();
+    commands.entity(speaker).add_child(bubble);
+}
+
+fn collect_barks(mut commands: Commands, project: Res<YarnProject>) {
+    let mut barks: HashMap<NpcDialogue, Vec<Bark>> = HashMap::new();
+    for info in project.compilation().string_table.values() {
+        let dialogue = match info
+            .node_name
+            .strip_suffix(BARKS_NODE_SUFFIX)
+            .map(NpcDialogue::from_str)
+        {
+            Some(Ok(r)) => r,
+            _ => continue,
+        };
+
+        // Lines are written as `<name>: <text>`, we only show the text.
+        let text = match info.text.split_once(": ") {
+            Some((_, text)) => text,
+            None => &info.text,
+        };
+        barks.entry(dialogue).or_default().push(Bark {
+            text: text.trim().to_string(),
+            condition: BarkCondition::from_tags(&info.metadata),
+        });
+    }
+    commands.insert_resource(Barks(barks));
+}
+
+fn bark(
+    mut commands: Commands,
+    time: Res<Time>,
+    assets: Res<GameAssets>,
+    barks: Res<Barks>,
+    seeded_rng: Res<SeededRng>,
+    q_player: Query<(&Transform, &Player)>,
+    mut q_npcs: Query<
+        (Entity, &Transform, &Npc, Option<&mut BarkCooldown>),
+        (Without<Player>, Without<Companion>),
+    >,
+    q_bubbles: Query<&Parent, With<SpeechBubble>>,
+) {
+    let (player_transform, player) = match q_player.get_single() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    let player_pos = player_transform.translation.truncate();
+
+    for (entity, transform, npc, cooldown) in &mut q_npcs {
+        if let Some(mut cooldown) = cooldown {
+            cooldown.0.tick(time.delta());
+            if !cooldown.0.finished() {
+                continue;
+            }
+        }
+
+        if player.state == PlayerState::Talking {
+            continue;
+        }
+        if transform
+            .translation
+            .truncate()
+            .distance_squared(player_pos)
+            > BARK_DISTANCE.powi(2)
+        {
+            continue;
+        }
+        if q_bubbles.iter().any(|parent| parent.get() == entity) {
+            continue;
+        }
+
+        let condition = BarkCondition::of_npc(npc);
+        let candidates: Vec<&Bark> = barks
+            .0
+            .get(&npc.dialogue)
+            .into_iter()
+            .flatten()
+            .filter(|b| b.condition == condition || b.condition == BarkCondition::Any)
+            .collect();
+        let mut rng = seeded_rng.lock(RngStream::Barks);
+        let Some(bark) = candidates.choose(&mut *rng) else {
+            continue;
+        };
+
+        spawn_speech_bubble(&mut commands, &assets, entity, &bark.text);
+        commands
+            .entity(entity)
+            .insert(BarkCooldown(Timer::from_seconds(
+                BARK_COOLDOWN,
+                TimerMode::Once,
+            )));
+    }
+}
+
+fn despawn_speech_bubbles(
+    mut commands: Commands,
+    time: Res<Time>,
+    q_player: Query<&Player>,
+    mut q_bubbles: Query<(Entity, &mut SpeechBubble)>,
+) {
+    let talking = q_player
+        .get_single()
+        .is_ok_and(|player| player.state == PlayerState::Talking);
+
+    for (entity, mut bubble) in &mut q_bubbles {
+        bubble.timer.tick(time.delta());
+        if bubble.timer.finished() || talking {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+pub struct NpcBarksPlugin;
+
+impl Plugin for NpcBarksPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Barks>()
+            .add_systems(Update, collect_barks.run_if(resource_added::<YarnProject>))
+            .add_systems(
+                Update,
+                (bark, despawn_speech_bubbles)
+                    .chain()
+                    .run_if(in_state(GameState::Gaming)),
+            );
+    }
+}
@@ -0,0 +1,379 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy_trickfilm::prelude::*;
+use bevy_yarnspinner::events::DialogueCompleteEvent;
+use rand::{Rng, SeedableRng};
+
+use crate::{
+    player::chat::{PlayerStartedChat, PlayerStoppedChat},
+    utils::{RngStream, SeededRng},
+    world::map::{generation::BitMap, navigation::NavGrid, TILE_SIZE},
+    GameAssets, GameRng, GameState,
+};
+
+use super::{Npc, NpcDialogue, Speaker};
+
+const WALK_SPEED: f32 = 40.0;
+const IDLE_DURATION: (f32, f32) = (3.0, 7.0);
+const SIT_DURATION: (f32, f32) = (15.0, 30.0);
+// If there is no path right now (e.g. the chunk isn't loaded), try again after this.
+const RETRY_DURATION: f32 = 2.0;
+const MAX_ROUTE_STOPS: usize = 3;
+
+/// What an NPC does when the player isn't talking to it.
+#[derive(Component, Clone, Copy, Debug)]
+pub enum NpcBehavior {
+    /// Stand at the hotspot.
+    Idle,
+    /// Walk to random spots within `radius` of the hotspot.
+    Wander { radius: f32 },
+    /// Walk between the graph vertices within `radius` of the hotspot.
+    Route { radius: f32 },
+    /// Sit next to the closest water within `radius` for a while.
+    Sit { radius: f32 },
+    /// Fish at the closest water within `radius` for a while.
+    Fish { radius: f32 },
+}
+
+impl NpcBehavior {
+    pub fn for_npc(dialogue: NpcDialogue) -> Self {
+        match dialogue {
+            NpcDialogue::Eleonore => NpcBehavior::Sit {
+                radius: 12.0 * TILE_SIZE,
+            },
+            NpcDialogue::Jotem => NpcBehavior::Wander {
+                radius: 5.0 * TILE_SIZE,
+            },
+            NpcDialogue::Isabelle => NpcBehavior::Route {
+                radius: 40.0 * TILE_SIZE,
+            },
+            NpcDialogue::IonasAndAntonius => NpcBehavior::Fish {
+                radius: 12.0 * TILE_SIZE,
+            },
+            NpcDialogue::Ionas | NpcDialogue::Antonius => NpcBehavior::Idle,
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum NpcState {
+    #[default]
+    Idling,
+    Walking,
+    Sitting,
+    Fishing,
+    Talking,
+}
+
+#[derive(Component)]
+pub struct NpcActivity {
+    pub state: NpcState,
+    home: Vec2,
+    /// Route stops or the spot next to the water.
+    stops: Vec<Vec2>,
+    next_stop: usize,
+    path: VecDeque<Vec2>,
+    /// The state once the NPC arrived at the end of the path.
+    arrival_state: NpcState,
+    speed: f32,
+    timer: Timer,
+    /// Each NPC draws from its own stream,
+    /// so that the NPCs don't change each other's behavior.
+    rng: GameRng,
+}
+
+impl NpcActivity {
+    fn new(home: Vec2, stops: Vec<Vec2>, mut rng: GameRng) -> Self {
+        Self {
+            state: NpcState::Idling,
+            home,
+            stops,
+            next_stop: 0,
+            path: VecDeque::new(),
+            arrival_state: NpcState::Idling,
+            speed: WALK_SPEED,
+            timer: random_timer(&mut rng, IDLE_DURATION),
+            rng,
+        }
+    }
+
+    /// Walk along the given path and idle once the end is reached.
+    pub fn walk(&mut self, path: Vec<Vec2>, speed: f32) {
+        self.path = path.into();
+        self.arrival_state = NpcState::Idling;
+        self.speed = speed;
+        self.state = NpcState::Walking;
+    }
+
+    pub fn rest(&mut self, state: NpcState) {
+        self.state = state;
+        self.path.clear();
+        self.timer = match state {
+            NpcState::Sitting | NpcState::Fishing => random_timer(&mut self.rng, SIT_DURATION),
+            _ => random_timer(&mut self.rng, IDLE_DURATION),
+        };
+    }
+
+    /// Where to go next and what to do there.
+    fn next_target(&mut self, behavior: NpcBehavior, pos: Vec2) -> Option<(Vec2, NpcState)> {
+        match behavior {
+            NpcBehavior::Idle => None,
+            NpcBehavior::Wander { radius } => {
+                let offset = Vec2::from_angle(self.rng.gen_range(0.0..std::f32::consts::TAU))
+                    * self.rng.gen_range(0.0..radius);
+                Some((self.home + offset, NpcState::Idling))
+            }
+            NpcBehavior::Route { .. } => {
+                if self.stops.is_empty() {
+                    return None;
+                }
+                let stop = self.stops[self.next_stop];
+                self.next_stop = (self.next_stop + 1) % self.stops.len();
+                Some((stop, NpcState::Idling))
+            }
+            NpcBehavior::Sit { .. } | NpcBehavior::Fish { .. } => {
+                let spot = *self.stops.first()?;
+                if pos.distance_squared(spot) < TILE_SIZE.powi(2) {
+                    Some((self.home, NpcState::Idling))
+                } else if matches!(behavior, NpcBehavior::Sit { .. }) {
+                    Some((spot, NpcState::Sitting))
+                } else {
+                    Some((spot, NpcState::Fishing))
+                }
+            }
+        }
+    }
+}
+
+fn random_timer(rng: &mut GameRng, range: (f32, f32)) -> Timer {
+    Timer::from_seconds(rng.gen_range(range.0..range.1), TimerMode::Once)
+}
+
+/// The graph vertices closest to the hotspot, followed by the hotspot itself.
+fn route_stops(bitmap: &BitMap, home: Vec2, radius: f32) -> Vec<Vec2> {
+    let mut vertices: Vec<Vec2> = bitmap
+        .vertices()
+        .iter()
+        .copied()
+        .filter(|v| *v != home && v.distance_squared(home) <= radius.powi(2))
+        .collect();
+    vertices.sort_by(|a, b| {
+        a.distance_squared(home)
+            .partial_cmp(&b.distance_squared(home))
+            .unwrap()
+    });
+    vertices.truncate(MAX_ROUTE_STOPS);
+    vertices.push(home);
+    vertices
+}
+
+/// The closest walkable tile that lies right next to water.
+fn water_spot(bitmap: &mut BitMap, home: Vec2, radius: f32) -> Option<Vec2> {
+    let center = NavGrid::world_to_cell(home);
+    let r = (radius / TILE_SIZE) as i32;
+
+    let mut best: Option<IVec2> = None;
+    for x in -r..=r {
+        for y in -r..=r {
+            let cell = center + IVec2::new(x, y);
+            if best.is_some_and(|b| (b - center).length_squared() <= x * x + y * y) {
+                continue;
+            }
+            if !bitmap.get_walkable_flag(cell) {
+                continue;
+            }
+            let next_to_water = [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y]
+                .iter()
+                .any(|d| !bitmap.get_walkable_flag(cell + *d) && bitmap.is_water(cell + *d));
+            if next_to_water {
+                best = Some(cell);
+            }
+        }
+    }
+    best.map(NavGrid::cell_to_world)
+}
+
+fn init_behaviors(
+    mut commands: Commands,
+    mut bitmap: ResMut<BitMap>,
+    seeded_rng: Res<SeededRng>,
+    q_npcs: Query<(Entity, &Transform, &Npc), Added<Npc>>,
+) {
+    for (entity, transform, npc) in &q_npcs {
+        let home = transform.translation.truncate();
+        let behavior = NpcBehavior::for_npc(npc.dialogue);
+        let stops = match behavior {
+            NpcBehavior::Idle | NpcBehavior::Wander { .. } => Vec::new(),
+            NpcBehavior::Route { radius } => route_stops(&bitmap, home, radius),
+            NpcBehavior::Sit { radius } | NpcBehavior::Fish { radius } => {
+                water_spot(&mut bitmap, home, radius).into_iter().collect()
+            }
+        };
+
+        let rng = GameRng::seed_from_u64(seeded_rng.lock(RngStream::Behaviors).gen());
+        commands
+            .entity(entity)
+            .insert((behavior, NpcActivity::new(home, stops, rng)));
+    }
+}
+
+fn update_activities(
+    time: Res<Time>,
+    nav_grid: Res<NavGrid>,
+    mut q_npcs: Query<(&Transform, &NpcBehavior, &mut NpcActivity)>,
+) {
+    for (transform, behavior, mut activity) in &mut q_npcs {
+        if matches!(activity.state, NpcState::Walking | NpcState::Talking) {
+            continue;
+        }
+
+        activity.timer.tick(time.delta());
+        if !activity.timer.finished() {
+            continue;
+        }
+
+        let pos = transform.translation.truncate();
+        let path = activity
+            .next_target(*behavior, pos)
+            .and_then(|(target, state)| Some((nav_grid.find_path(pos, target)?, state)));
+        match path {
+            Some((path, state)) => {
+                activity.walk(path, WALK_SPEED);
+                activity.arrival_state = state;
+            }
+            None => {
+                activity.timer = Timer::from_seconds(RETRY_DURATION, TimerMode::Once);
+            }
+        }
+    }
+}
+
+pub fn move_npcs(
+    time: Res<Time>,
+    mut q_npcs: Query<(&mut Transform, &mut NpcActivity, Option<&mut Sprite>)>,
+) {
+    for (mut transform, mut activity, sprite) in &mut q_npcs {
+        if activity.state != NpcState::Walking {
+            continue;
+        }
+
+        let pos = transform.translation.truncate();
+        let next = match activity.path.front() {
+            Some(r) => *r,
+            None => {
+                let state = activity.arrival_state;
+                activity.rest(state);
+                continue;
+            }
+        };
+
+        let step = activity.speed * time.delta_seconds();
+        let delta = next - pos;
+        if delta.length() <= step {
+            transform.translation = next.extend(transform.translation.z);
+            activity.path.pop_front();
+        } else {
+            let direction = delta.normalize();
+            transform.translation += (direction * step).extend(0.0);
+            if let Some(mut sprite) = sprite {
+                sprite.flip_x = direction.x < 0.0;
+            }
+        }
+    }
+}
+
+fn start_talking(
+    mut q_npcs: Query<(&Npc, &mut NpcActivity)>,
+    mut ev_player_started_chat: EventReader<PlayerStartedChat>,
+) {
+    for ev in ev_player_started_chat.read() {
+        for (npc, mut activity) in &mut q_npcs {
+            if npc.dialogue == ev.dialogue {
+                activity.rest(NpcState::Talking);
+            }
+        }
+    }
+}
+
+fn stop_talking(mut q_npcs: Query<&mut NpcActivity>) {
+    for mut activity in &mut q_npcs {
+        if activity.state == NpcState::Talking {
+            activity.rest(NpcState::Idling);
+        }
+    }
+}
+
+/// Stop all the NPCs where they are, e.g. during the ending.
+fn pause_behaviors(mut q_npcs: Query<&mut NpcActivity>) {
+    for mut activity in &mut q_npcs {
+        activity.rest(NpcState::Idling);
+    }
+}
+
+/// The idle, walk, sit and fish clips, in that order.
+fn npc_animations(assets: &GameAssets, dialogue: NpcDialogue) -> &[Handle<AnimationClip2D>] {
+    match dialogue {
+        NpcDialogue::Eleonore => &assets.eleonore_animations,
+        NpcDialogue::Jotem => &assets.jotem_animations,
+        NpcDialogue::Isabelle => &assets.isabelle_animations,
+        NpcDialogue::Ionas => &assets.ionas_animations,
+        NpcDialogue::Antonius => &assets.antonius_animations,
+        // They only have their own sprites, see `Speaker`.
+        NpcDialogue::IonasAndAntonius => &[],
+    }
+}
+
+/// Ionas and Antonius animate separately and Eleonore's shadow
+/// follows her, so we go through all the animated descendants.
+fn update_animations(
+    assets: Res<GameAssets>,
+    q_npcs: Query<(Entity, &Npc, &NpcActivity), Changed<NpcActivity>>,
+    q_children: Query<&Children>,
+    mut q_animators: Query<(&mut AnimationPlayer2D, Option<&Speaker>)>,
+) {
+    for (entity, npc, activity) in &q_npcs {
+        let index = match activity.state {
+            NpcState::Idling | NpcState::Talking => 0,
+            NpcState::Walking => 1,
+            NpcState::Sitting => 2,
+            NpcState::Fishing => 3,
+        };
+
+        for e in std::iter::once(entity).chain(q_children.iter_descendants(entity)) {
+            let Ok((mut animator, speaker)) = q_animators.get_mut(e) else {
+                continue;
+            };
+            let dialogue = speaker.map_or(npc.dialogue, |speaker| speaker.0);
+            if let Some(clip) = npc_animations(&assets, dialogue).get(index) {
+                animator.play(clip.clone()).repeat();
+            }
+        }
+    }
+}
+
+pub struct NpcBehaviorPlugin;
+
+impl Plugin for NpcBehaviorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                init_behaviors,
+                start_talking,
+                stop_talking.run_if(
+                    on_event::<PlayerStoppedChat>().or_else(on_event::<DialogueCompleteEvent>()),
+                ),
+                update_activities,
+                move_npcs,
+                update_animations,
+            )
+                .chain()
+                .run_if(in_state(GameState::Gaming)),
+        )
+        .add_systems(
+            OnEnter(GameState::Ending),
+            (pause_behaviors, update_animations).chain(),
+        );
+    }
+}
@@ -0,0 +1,341 @@
+use std::str::FromStr;
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_rapier2d::prelude::*;
+use bevy_yarnspinner::prelude::*;
+use rand::seq::SliceRandom;
+
+use crate::{
+    player::{Player, PlayerState},
+    utils::{RngStream, SeededRng},
+    world::map::{generation::BitMap, navigation::NavGrid},
+    GameAssets, GameState,
+};
+
+use super::{
+    barks::{spawn_speech_bubble, SpeechBubble},
+    behavior::{move_npcs, NpcActivity, NpcBehavior, NpcState},
+    Npc, NpcDialogue,
+};
+
+// The companion tries to stay this far away from the player.
+const SPACING: f32 = 32.0;
+// Only start walking again once the player is this far away,
+// otherwise the companion would jitter behind the player.
+const FOLLOW_DISTANCE: f32 = 56.0;
+// Too far behind (e.g. the player ran off), catch up by running.
+const CATCH_UP_DISTANCE: f32 = 120.0;
+// Lost the player completely, just appear behind them.
+const TELEPORT_DISTANCE: f32 = 400.0;
+const WALK_SPEED: f32 = 100.0;
+const RUN_SPEED: f32 = 150.0;
+const REPATH_INTERVAL: f32 = 0.4;
+
+const CONTEXT_DISTANCE: f32 = 80.0;
+const WATER_CONTEXT_RADIUS: i32 = 2;
+const LINE_COOLDOWN: f32 = 20.0;
+const WATER_LINE_COOLDOWN: f32 = 90.0;
+
+const LINES_NODE_SUFFIX: &str = "Companion";
+pub const WATER_TAG: &str = "water";
+pub const NPC_TAG: &str = "npc";
+
+/// An NPC joined the player, see the `join_party` yarn command.
+#[derive(Event)]
+pub struct NpcJoinedParty {
+    pub dialogue: NpcDialogue,
+}
+
+/// An NPC that follows the player around.
+#[derive(Component)]
+pub struct Companion {
+    repath_timer: Timer,
+    line_cooldown: Timer,
+    water_line_cooldown: Timer,
+    /// The NPCs this companion already said something about.
+    commented_on: Vec<NpcDialogue>,
+}
+
+impl Default for Companion {
+    fn default() -> Self {
+        let mut water_line_cooldown = Timer::from_seconds(WATER_LINE_COOLDOWN, TimerMode::Once);
+        // The first water line can be said right away.
+        water_line_cooldown.tick(water_line_cooldown.duration());
+        Self {
+            repath_timer: Timer::from_seconds(REPATH_INTERVAL, TimerMode::Repeating),
+            line_cooldown: Timer::from_seconds(LINE_COOLDOWN, TimerMode::Once),
+            water_line_cooldown,
+            commented_on: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum LineContext {
+    Npc(NpcDialogue),
+    Water,
+}
+
+/// When a companion line can be said, taken from the line's tag.
+#[derive(Clone, Copy, PartialEq)]
+enum LineCondition {
+    Water,
+    /// Close to any NPC.
+    AnyNpc,
+    /// Close to this NPC, preferred over `AnyNpc`.
+    Npc(NpcDialogue),
+}
+
+impl LineCondition {
+    fn from_tags(tags: &[String]) -> Option<Self> {
+        tags.iter()
+            .map(|t| t.trim_start_matches('#'))
+            .find_map(|tag| match tag {
+                WATER_TAG => Some(LineCondition::Water),
+                NPC_TAG => Some(LineCondition::AnyNpc),
+                _ => NpcDialogue::from_str(tag).ok().map(LineCondition::Npc),
+            })
+    }
+}
+
+struct ContextLine {
+    text: String,
+    condition: LineCondition,
+}
+
+/// The lines of all companions, taken from the `<Npc>Companion` yarn nodes.
+/// Like the barks, these nodes never get run.
+#[derive(Resource, Default)]
+struct CompanionLines(HashMap<NpcDialogue, Vec<ContextLine>>);
+
+/// The lines that fit the context best.
+fn context_lines(lines: &[ContextLine], context: LineContext) -> Vec<&ContextLine> {
+    let matching = |condition: LineCondition| {
+        lines
+            .iter()
+            .filter(|line| line.condition == condition)
+            .collect::<Vec<&ContextLine>>()
+    };
+
+    match context {
+        LineContext::Water => matching(LineCondition::Water),
+        LineContext::Npc(other) => {
+            let specific = matching(LineCondition::Npc(other));
+            if specific.is_empty() {
+                matching(LineCondition::AnyNpc)
+            } else {
+                specific
+            }
+        }
+    }
+}
+
+fn collect_companion_lines(mut commands: Commands, project: Res<YarnProject>) {
+    let mut lines: HashMap<NpcDialogue, Vec<ContextLine>> = HashMap::new();
+    for info in project.compilation().string_table.values() {
+        let dialogue = match info
+            .node_name
+            .strip_suffix(LINES_NODE_SUFFIX)
+            .map(NpcDialogue::from_str)
+        {
+            Some(Ok(r)) => r,
+            _ => continue,
+        };
+        let Some(condition) = LineCondition::from_tags(&info.metadata) else {
+            error!("companion line without a context tag, {}", info.text);
+            continue;
+        };
+
+        // Lines are written as `<name>: <text>`, we only show the text.
+        let text = match info.text.split_once(": ") {
+            Some((_, text)) => text,
+            None => &info.text,
+        };
+        lines.entry(dialogue).or_default().push(ContextLine {
+            text: text.trim().to_string(),
+            condition,
+        });
+    }
+    commands.insert_resource(CompanionLines(lines));
+}
+
+fn join_party(
+    mut commands: Commands,
+    q_npcs: Query<(Entity, &Npc)>,
+    q_children: Query<&Children>,
+    q_colliders: Query<(), With<Collider>>,
+    mut ev_npc_joined_party: EventReader<NpcJoinedParty>,
+) {
+    for ev in ev_npc_joined_party.read() {
+        for (entity, npc) in &q_npcs {
+            if npc.dialogue != ev.dialogue {
+                continue;
+            }
+
+            // The companion walks right behind the player, so it shouldn't block them.
+            for child in q_children.iter_descendants(entity) {
+                if q_colliders.contains(child) {
+                    commands.entity(child).insert(ColliderDisabled);
+                }
+            }
+            commands
+                .entity(entity)
+                .remove::<NpcBehavior>()
+                .insert(Companion::default());
+        }
+    }
+}
+
+fn follow_player(
+    time: Res<Time>,
+    nav_grid: Res<NavGrid>,
+    q_player: Query<(&Transform, &Player)>,
+    mut q_companions: Query<(&mut Transform, &mut Companion, &mut NpcActivity), Without<Player>>,
+) {
+    let (player_transform, player) = match q_player.get_single() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    let player_pos = player_transform.translation.truncate();
+
+    for (mut transform, mut companion, mut activity) in &mut q_companions {
+        if activity.state == NpcState::Talking {
+            continue;
+        }
+
+        // Wait while the player talks to someone else.
+        if player.state == PlayerState::Talking {
+            if activity.state == NpcState::Walking {
+                activity.rest(NpcState::Idling);
+            }
+            continue;
+        }
+
+        let pos = transform.translation.truncate();
+        let distance = pos.distance(player_pos);
+
+        if distance > TELEPORT_DISTANCE {
+            let behind = player_pos - player.current_direction.normalize_or_zero() * SPACING;
+            transform.translation = behind.extend(transform.translation.z);
+            activity.rest(NpcState::Idling);
+            continue;
+        }
+
+        if distance < SPACING {
+            if activity.state == NpcState::Walking {
+                activity.rest(NpcState::Idling);
+            }
+            continue;
+        }
+
+        companion.repath_timer.tick(time.delta());
+        let walking = activity.state == NpcState::Walking;
+        if distance < FOLLOW_DISTANCE && !walking {
+            continue;
+        }
+        if walking && !companion.repath_timer.just_finished() {
+            continue;
+        }
+
+        // Stay on our side of the player instead of walking around them.
+        let target = player_pos + (pos - player_pos).normalize_or_zero() * SPACING;
+        let speed = if distance > CATCH_UP_DISTANCE {
+            RUN_SPEED
+        } else {
+            WALK_SPEED
+        };
+        if let Some(path) = nav_grid.find_path(pos, target) {
+            activity.walk(path, speed);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn say_context_lines(
+    mut commands: Commands,
+    time: Res<Time>,
+    assets: Res<GameAssets>,
+    lines: Res<CompanionLines>,
+    seeded_rng: Res<SeededRng>,
+    mut bitmap: ResMut<BitMap>,
+    q_player: Query<&Player>,
+    mut q_companions: Query<(Entity, &Transform, &Npc, &mut Companion)>,
+    q_npcs: Query<(&Transform, &Npc), Without<Companion>>,
+    q_bubbles: Query<(), With<SpeechBubble>>,
+) {
+    let player = match q_player.get_single() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    for (entity, transform, npc, mut companion) in &mut q_companions {
+        companion.line_cooldown.tick(time.delta());
+        companion.water_line_cooldown.tick(time.delta());
+
+        if player.state == PlayerState::Talking || !q_bubbles.is_empty() {
+            continue;
+        }
+        if !companion.line_cooldown.finished() {
+            continue;
+        }
+
+        let pos = transform.translation.truncate();
+        let other_npc = q_npcs
+            .iter()
+            .find(|(other_transform, other)| {
+                !companion.commented_on.contains(&other.dialogue)
+                    && other_transform.translation.truncate().distance_squared(pos)
+                        <= CONTEXT_DISTANCE.powi(2)
+            })
+            .map(|(_, other)| other.dialogue);
+
+        let context = if let Some(other) = other_npc {
+            companion.commented_on.push(other);
+            LineContext::Npc(other)
+        } else if companion.water_line_cooldown.finished() && {
+            let cell = NavGrid::world_to_cell(pos);
+            (-WATER_CONTEXT_RADIUS..=WATER_CONTEXT_RADIUS).any(|x| {
+                (-WATER_CONTEXT_RADIUS..=WATER_CONTEXT_RADIUS)
+                    .any(|y| bitmap.is_water(cell + IVec2::new(x, y)))
+            })
+        } {
+            companion.water_line_cooldown.reset();
+            LineContext::Water
+        } else {
+            continue;
+        };
+
+        let candidates = lines
+            .0
+            .get(&npc.dialogue)
+            .map(|lines| context_lines(lines, context))
+            .unwrap_or_default();
+        let mut rng = seeded_rng.lock(RngStream::CompanionLines);
+        let Some(line) = candidates.choose(&mut *rng) else {
+            continue;
+        };
+
+        companion.line_cooldown.reset();
+        spawn_speech_bubble(&mut commands, &assets, entity, &line.text);
+    }
+}
+
+pub struct CompanionPlugin;
+
+impl Plugin for CompanionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<NpcJoinedParty>()
+            .init_resource::<CompanionLines>()
+            .add_systems(
+                Update,
+                collect_companion_lines.run_if(resource_added::<YarnProject>),
+            )
+            .add_systems(
+                Update,
+                (join_party, follow_player, say_context_lines)
+                    .chain()
+                    .before(move_npcs)
+                    .run_if(in_state(GameState::Gaming)),
+            );
+    }
+}
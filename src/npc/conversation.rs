@@ -0,0 +1,266 @@
+use std::str::FromStr;
+
+use bevy::prelude::*;
+use bevy_yarnspinner::{events::DialogueCompleteEvent, prelude::*};
+
+use crate::{
+    player::{
+        chat::{PlayerStartedChat, PlayerStoppedChat},
+        Player, PlayerState, NPC_PROXIMITY_DISTANCE,
+    },
+    ui::dialogue::{
+        runner::{RunnerFlags, UpdateTargetNpcs},
+        Typewriter,
+    },
+    GameState,
+};
+
+use super::{
+    behavior::{NpcActivity, NpcState},
+    companion::Companion,
+    Npc, NpcDialogue, Speaker,
+};
+
+// NPCs this close to the one the player talks to can join in with a group node.
+// Companions always can, they follow the player around.
+const GROUP_DISTANCE: f32 = 1.5 * NPC_PROXIMITY_DISTANCE;
+// How far the camera moves from the player towards the current speaker.
+const FOCUS_WEIGHT: f32 = 0.5;
+const FOCUS_SPEED: f32 = 4.0;
+const SPEAKER_COLOR: Color = Color::srgb(1.0, 0.92, 0.75);
+
+#[derive(Clone, Copy)]
+pub struct Participant {
+    pub speaker: NpcDialogue,
+    /// The NPC the speaker belongs to, Ionas and Antonius share one.
+    pub npc: NpcDialogue,
+    pub entity: Entity,
+}
+
+/// All the characters taking part in the current conversation.
+/// Another NPC only joins in when there is a group node for the two of them,
+/// see `group_node`.
+#[derive(Resource, Default)]
+pub struct Conversation {
+    /// Sorted from left to right, the way they stand in the world.
+    pub participants: Vec<Participant>,
+    /// The group node the dialogue runner starts with instead of the NPC's own node.
+    pub node: Option<String>,
+    /// `None` while the player (or no one in particular) is speaking.
+    pub speaker: Option<NpcDialogue>,
+    /// Where the camera should look at, `None` outside of conversations.
+    pub focus: Option<Vec2>,
+}
+
+impl Conversation {
+    fn participant(&self, speaker: NpcDialogue) -> Option<&Participant> {
+        self.participants.iter().find(|p| p.speaker == speaker)
+    }
+}
+
+/// The node where `other` joins the conversation with `dialogue`, e.g. `EleonoreWithJotem`.
+/// It has lines from both of them and jumps to the NPC's own node at the end.
+pub fn group_node(dialogue: NpcDialogue, other: NpcDialogue) -> String {
+    format!("{}With{}", dialogue, other)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn start_conversation(
+    mut conversation: ResMut<Conversation>,
+    project: Option<Res<YarnProject>>,
+    q_npcs: Query<(Entity, &GlobalTransform, &Npc, Has<Companion>)>,
+    q_speakers: Query<(Entity, &GlobalTransform, &Speaker, Option<&Parent>)>,
+    q_runner_flags: Query<&RunnerFlags>,
+    mut q_activities: Query<&mut NpcActivity>,
+    mut ev_player_started_chat: EventReader<PlayerStartedChat>,
+) {
+    for ev in ev_player_started_chat.read() {
+        let (npc_entity, center) = match q_npcs
+            .iter()
+            .find(|(_, _, npc, _)| npc.dialogue == ev.dialogue)
+        {
+            Some((entity, transform, _, _)) => (entity, transform.translation().truncate()),
+            None => continue,
+        };
+
+        let node_exists = |node: &str| {
+            project
+                .as_ref()
+                .and_then(|project| project.compilation().program.as_ref())
+                .is_some_and(|program| program.nodes.contains_key(node))
+        };
+        // A cached runner continues where the player left off instead.
+        let cached = q_runner_flags
+            .iter()
+            .any(|flags| flags.dialogue == Some(ev.dialogue));
+        let group = if ev.node.is_some() || cached {
+            None
+        } else {
+            q_npcs
+                .iter()
+                .filter(|(_, transform, npc, is_companion)| {
+                    npc.dialogue != ev.dialogue
+                        && (*is_companion
+                            || transform.translation().truncate().distance_squared(center)
+                                <= GROUP_DISTANCE.powi(2))
+                })
+                .map(|(entity, _, npc, _)| (entity, group_node(ev.dialogue, npc.dialogue)))
+                .find(|(_, node)| node_exists(node))
+        };
+
+        let mut participants: Vec<(f32, Participant)> = Vec::new();
+        for (entity, transform, speaker, parent) in &q_speakers {
+            // Ionas and Antonius are children of their shared NPC.
+            let owner = if q_npcs.contains(entity) {
+                entity
+            } else {
+                match parent {
+                    Some(r) => r.get(),
+                    None => continue,
+                }
+            };
+            if owner != npc_entity && group.as_ref().map(|(entity, _)| *entity) != Some(owner) {
+                continue;
+            }
+            let npc = match q_npcs.get(owner) {
+                Ok((_, _, npc, _)) => npc.dialogue,
+                Err(_) => continue,
+            };
+
+            participants.push((
+                transform.translation().x,
+                Participant {
+                    speaker: speaker.0,
+                    npc,
+                    entity,
+                },
+            ));
+
+            if let Ok(mut activity) = q_activities.get_mut(owner) {
+                if activity.state != NpcState::Talking {
+                    activity.rest(NpcState::Talking);
+                }
+            }
+        }
+        participants.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+        conversation.participants = participants.into_iter().map(|(_, p)| p).collect();
+        conversation.node = group.map(|(_, node)| node);
+        conversation.speaker = None;
+        conversation.focus = None;
+    }
+}
+
+/// Whoever speaks in a group node was talked to as well,
+/// the NPC the player clicked on is already marked when its runner spawns.
+fn update_speaker(
+    typewriter: Res<Typewriter>,
+    mut conversation: ResMut<Conversation>,
+    mut q_npcs: Query<&mut Npc>,
+    mut ev_update_target_npcs: EventWriter<UpdateTargetNpcs>,
+) {
+    if conversation.participants.is_empty() {
+        return;
+    }
+
+    let name = typewriter.character_name.clone().unwrap_or_default();
+    let participant = NpcDialogue::from_str(name.trim_start_matches('_'))
+        .ok()
+        .and_then(|speaker| conversation.participant(speaker))
+        .copied();
+    let speaker = participant.map(|p| p.speaker);
+    if conversation.speaker == speaker {
+        return;
+    }
+    conversation.speaker = speaker;
+
+    let Some(participant) = participant else {
+        return;
+    };
+    for mut npc in &mut q_npcs {
+        if npc.dialogue == participant.npc && !npc.was_talked_to {
+            npc.was_talked_to = true;
+            ev_update_target_npcs.send(UpdateTargetNpcs);
+        }
+    }
+}
+
+fn update_focus(
+    time: Res<Time>,
+    mut conversation: ResMut<Conversation>,
+    q_player: Query<(&Transform, &Player)>,
+    q_transforms: Query<&GlobalTransform>,
+) {
+    if conversation.participants.is_empty() {
+        return;
+    }
+
+    let (player_transform, player) = match q_player.get_single() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    if player.state != PlayerState::Talking {
+        return;
+    }
+
+    let player_pos = player_transform.translation.truncate();
+    let target = conversation
+        .speaker
+        .and_then(|speaker| conversation.participant(speaker))
+        .and_then(|p| q_transforms.get(p.entity).ok())
+        .map(|transform| player_pos.lerp(transform.translation().truncate(), FOCUS_WEIGHT))
+        .unwrap_or(player_pos);
+
+    let focus = conversation.focus.unwrap_or(player_pos);
+    let t = 1.0 - (-FOCUS_SPEED * time.delta_seconds()).exp();
+    conversation.focus = Some(focus.lerp(target, t));
+}
+
+fn highlight_speaker(conversation: Res<Conversation>, mut q_sprites: Query<&mut Sprite>) {
+    for participant in &conversation.participants {
+        let color = if conversation.speaker == Some(participant.speaker) {
+            SPEAKER_COLOR
+        } else {
+            Color::WHITE
+        };
+
+        if let Ok(mut sprite) = q_sprites.get_mut(participant.entity) {
+            if sprite.color != color {
+                sprite.color = color;
+            }
+        }
+    }
+}
+
+fn end_conversation(mut conversation: ResMut<Conversation>, mut q_sprites: Query<&mut Sprite>) {
+    for participant in &conversation.participants {
+        if let Ok(mut sprite) = q_sprites.get_mut(participant.entity) {
+            sprite.color = Color::WHITE;
+        }
+    }
+    *conversation = Conversation::default();
+}
+
+pub struct ConversationPlugin;
+
+impl Plugin for ConversationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Conversation>()
+            .add_systems(
+                Update,
+                (
+                    start_conversation,
+                    update_speaker,
+                    update_focus,
+                    highlight_speaker,
+                    end_conversation.run_if(
+                        on_event::<PlayerStoppedChat>()
+                            .or_else(on_event::<DialogueCompleteEvent>()),
+                    ),
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Gaming)),
+            )
+            .add_systems(OnExit(GameState::Gaming), end_conversation);
+    }
+}
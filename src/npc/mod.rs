@@ -1,12 +1,19 @@
+pub mod barks;
+pub mod behavior;
+pub mod companion;
+pub mod conversation;
+
 mod spawn;
 
-use strum_macros::{Display, EnumString};
+use strum_macros::{Display, EnumIter, EnumString};
 
 use bevy::prelude::*;
 
 use crate::player::Player;
 
-#[derive(Clone, Copy, Display, PartialEq, EnumString)]
+use behavior::{NpcActivity, NpcState};
+
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Hash, EnumString, EnumIter)]
 pub enum NpcDialogue {
     Eleonore,
     Jotem,
@@ -20,8 +27,14 @@ pub struct NpcPlugin;
 
 impl Plugin for NpcPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(spawn::NpcSpawnPlugin)
-            .add_systems(Update, (face_player,));
+        app.add_plugins((
+            spawn::NpcSpawnPlugin,
+            behavior::NpcBehaviorPlugin,
+            barks::NpcBarksPlugin,
+            companion::CompanionPlugin,
+            conversation::ConversationPlugin,
+        ))
+        .add_systems(Update, (face_player,));
     }
 }
 
@@ -45,6 +58,12 @@ pub struct Npc {
     pub was_mentioned_by: Vec<NpcDialogue>,
 }
 
+/// A character that can speak in a conversation.
+/// Usually this sits right on the `Npc`, but Ionas and Antonius
+/// share a single `Npc` and each of them has their own `Speaker`.
+#[derive(Component)]
+pub struct Speaker(pub NpcDialogue);
+
 impl Npc {
     fn new(dialogue: NpcDialogue) -> Self {
         Self {
@@ -57,14 +76,22 @@ impl Npc {
 
 fn face_player(
     q_player: Query<&Transform, With<Player>>,
-    mut q_npcs: Query<(&Transform, &mut Sprite), (With<Npc>, Without<Player>)>,
+    mut q_npcs: Query<
+        (&Transform, &mut Sprite, Option<&NpcActivity>),
+        (With<Npc>, Without<Player>),
+    >,
 ) {
     let player = match q_player.get_single() {
         Ok(r) => r,
         Err(_) => return,
     };
 
-    for (transform, mut sprite) in &mut q_npcs {
+    for (transform, mut sprite, activity) in &mut q_npcs {
+        // Walking NPCs look where they are going.
+        if activity.is_some_and(|a| a.state == NpcState::Walking) {
+            continue;
+        }
+
         let flip = player.translation.x < transform.translation.x;
         sprite.flip_x = flip;
     }
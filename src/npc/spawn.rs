@@ -5,12 +5,18 @@ use bevy_trickfilm::prelude::*;
 use crate::{
     world::{
         camera::{YSort, YSortChild},
-        map::generation::BitMap,
+        map::{
+            generation::{BitMap, NPC_HOTSPOTS},
+            reserved_areas::ReservedAreas,
+        },
     },
     GameAssets, GameState,
 };
 
-use super::{Npc, NpcDialogue};
+use super::{Npc, NpcDialogue, Speaker};
+
+// Nothing grows this close to the NPCs' hotspots.
+const RESERVED_RADIUS: f32 = 64.0;
 
 fn spawn_eleonore(commands: &mut Commands, assets: &Res<GameAssets>, pos: Vec2) {
     let transform = Transform::from_translation(pos.extend(0.0));
@@ -54,6 +60,7 @@ fn spawn_eleonore(commands: &mut Commands, assets: &Res<GameAssets>, pos: Vec2)
     commands
         .spawn((
             Npc::new(NpcDialogue::Eleonore),
+            Speaker(NpcDialogue::Eleonore),
             YSort(16.0),
             animator,
             SpriteBundle {
@@ -88,6 +95,7 @@ fn spawn_jotem(commands: &mut Commands, assets: &Res<GameAssets>, pos: Vec2) {
     commands
         .spawn((
             Npc::new(NpcDialogue::Jotem),
+            Speaker(NpcDialogue::Jotem),
             YSort(0.0),
             animator,
             SpriteBundle {
@@ -124,6 +132,7 @@ fn spawn_isabelle(commands: &mut Commands, assets: &Res<GameAssets>, pos: Vec2)
     commands
         .spawn((
             Npc::new(NpcDialogue::Isabelle),
+            Speaker(NpcDialogue::Isabelle),
             YSort(0.0),
             animator,
             SpriteBundle {
@@ -159,6 +168,7 @@ fn spawn_antonius(commands: &mut Commands, assets: &Res<GameAssets>, pos: Vec2)
 
     commands
         .spawn((
+            Speaker(NpcDialogue::Antonius),
             animator,
             SpriteBundle {
                 texture: assets.antonius_texture.clone(),
@@ -192,6 +202,7 @@ fn spawn_ionas(commands: &mut Commands, assets: &Res<GameAssets>, pos: Vec2) ->
 
     commands
         .spawn((
+            Speaker(NpcDialogue::Ionas),
             animator,
             SpriteBundle {
                 texture: assets.ionas_texture.clone(),
@@ -228,8 +239,13 @@ fn spawn_antonius_and_ionas(commands: &mut Commands, assets: &Res<GameAssets>, p
         .push_children(&[antonius, ionas]);
 }
 
-fn spawn_npcs(mut commands: Commands, bitmap: Res<BitMap>, assets: Res<GameAssets>) {
-    let hotspots = bitmap.get_furthest_hotspots(4);
+fn spawn_npcs(
+    mut commands: Commands,
+    bitmap: Res<BitMap>,
+    assets: Res<GameAssets>,
+    mut reserved_areas: ResMut<ReservedAreas>,
+) {
+    let hotspots = bitmap.get_furthest_hotspots(NPC_HOTSPOTS);
     let npcs = [
         spawn_eleonore,
         spawn_jotem,
@@ -238,6 +254,7 @@ fn spawn_npcs(mut commands: Commands, bitmap: Res<BitMap>, assets: Res<GameAsset
     ];
 
     for i in 0..hotspots.len() {
+        reserved_areas.reserve_circle(hotspots[i], RESERVED_RADIUS);
         npcs[i](&mut commands, &assets, hotspots[i]);
     }
 }
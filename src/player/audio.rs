@@ -6,6 +6,7 @@ use noisy_bevy::simplex_noise_2d_seeded;
 
 use crate::{
     audio::{GameAudio, PlaySound},
+    world::map::generation::BitMap,
     GameAssets, GameState,
 };
 
@@ -16,6 +17,8 @@ const TIME_BETWEEN_STEPS_WALKING: f32 = 0.5;
 const TIME_BETWEEN_STEPS_RUNNING: f32 = 0.4;
 const WALK_VOLUME: f64 = 1.5;
 const RUN_VOLUME: f64 = 2.0;
+// Bridges are made of wooden planks, the lower pitch makes the steps sound hollow.
+const BRIDGE_PLAYBACK_RATE: f64 = 0.7;
 
 const BIRD_MAX_VOLUME: f64 = 1.0;
 const BIRD_MIN_VOLUME: f32 = 0.1;
@@ -77,12 +80,13 @@ fn tick_steps_timers(time: Res<Time>, mut steps_timer: ResMut<StepsTimer>) {
 
 fn play_step_sounds(
     assets: Res<GameAssets>,
+    mut bitmap: ResMut<BitMap>,
     mut steps_timer: ResMut<StepsTimer>,
-    q_player: Query<&Player>,
+    q_player: Query<(&Transform, &Player)>,
     mut ev_play_sound: EventWriter<PlaySound>,
 ) {
-    let player_state = match q_player.get_single() {
-        Ok(p) => p.state,
+    let (player_pos, player_state) = match q_player.get_single() {
+        Ok((transform, player)) => (transform.translation.truncate(), player.state),
         Err(_) => return,
     };
 
@@ -90,12 +94,19 @@ fn play_step_sounds(
         return;
     }
 
+    let playback_rate = if bitmap.is_on_bridge(player_pos) {
+        BRIDGE_PLAYBACK_RATE
+    } else {
+        1.0
+    };
+
     let sound = match player_state {
         PlayerState::Walking => {
             steps_timer.set_duration(Duration::from_secs_f32(TIME_BETWEEN_STEPS_WALKING));
             Some(PlaySound {
                 clip: assets.player_footstep.clone(),
                 volume: WALK_VOLUME,
+                playback_rate,
                 rand_speed_intensity: RAND_SPEED_INTENSITY,
                 ..default()
             })
@@ -105,6 +116,7 @@ fn play_step_sounds(
             Some(PlaySound {
                 clip: assets.player_footstep.clone(),
                 volume: RUN_VOLUME,
+                playback_rate,
                 rand_speed_intensity: RAND_SPEED_INTENSITY,
                 ..default()
             })
@@ -7,24 +7,38 @@ use crate::{
     GameState,
 };
 
-use super::{input::PlayerInput, Player, PlayerState, NPC_PROXIMITY_DISTANCE};
+use super::{
+    input::PlayerInput,
+    interaction::{update_nearest_target, InteractTarget, NearestTarget},
+    Player, PlayerState,
+};
 
 #[derive(Event)]
 pub struct PlayerStartedChat {
     pub dialogue: NpcDialogue,
     pub direction: Vec2,
+    /// Restart the dialogue from this node instead of continuing it.
+    pub node: Option<String>,
+}
+/// The player inspects something in the world, e.g. a point of interest.
+#[derive(Event)]
+pub struct PlayerStartedInspection {
+    pub node: &'static str,
+    pub direction: Vec2,
 }
 #[derive(Event)]
 pub struct PlayerStoppedChat;
 
 fn start_chat(
     player_input: Res<PlayerInput>,
+    nearest_target: Res<NearestTarget>,
     mut q_player: Query<(&Transform, &mut Player)>,
-    q_npcs: Query<(&Transform, &Npc), Without<Player>>,
+    q_npcs: Query<&Npc, Without<Player>>,
     q_dialogue_runners: Query<&RunnerFlags, With<DialogueRunner>>,
     mut ev_player_started_chat: EventWriter<PlayerStartedChat>,
 ) {
-    if !player_input.dialogue {
+    // Clicks only start a chat when they are on the NPC, see `click_move`.
+    if !player_input.dialogue || player_input.click {
         return;
     }
     for flags in &q_dialogue_runners {
@@ -33,26 +47,25 @@ fn start_chat(
         }
     }
 
+    // The closest NPC or object, see `interaction`.
+    let Some(InteractTarget::Npc(entity)) = nearest_target.target else {
+        return;
+    };
     let (player_transform, mut player) = match q_player.get_single_mut() {
         Ok(r) => r,
         Err(_) => return,
     };
+    let npc = match q_npcs.get(entity) {
+        Ok(r) => r,
+        Err(_) => return,
+    };
 
-    for (npc_transform, npc) in &q_npcs {
-        if player_transform
-            .translation
-            .xy()
-            .distance_squared(npc_transform.translation.xy())
-            <= NPC_PROXIMITY_DISTANCE.powi(2)
-        {
-            player.state = PlayerState::Talking;
-            ev_player_started_chat.send(PlayerStartedChat {
-                dialogue: npc.dialogue,
-                direction: npc_transform.translation.xy() - player_transform.translation.xy(),
-            });
-            break;
-        }
-    }
+    player.state = PlayerState::Talking;
+    ev_player_started_chat.send(PlayerStartedChat {
+        dialogue: npc.dialogue,
+        direction: nearest_target.pos - player_transform.translation.xy(),
+        node: None,
+    });
 }
 
 fn stop_chat(
@@ -81,10 +94,12 @@ pub struct PlayerChatPlugin;
 impl Plugin for PlayerChatPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<PlayerStartedChat>()
+            .add_event::<PlayerStartedInspection>()
             .add_event::<PlayerStoppedChat>()
             .add_systems(
                 Update,
-                (start_chat, stop_chat).run_if(in_state(GameState::Gaming)),
+                (start_chat.after(update_nearest_target), stop_chat)
+                    .run_if(in_state(GameState::Gaming)),
             );
     }
 }
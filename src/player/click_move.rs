@@ -0,0 +1,340 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_tweening::{lens::*, *};
+use bevy_yarnspinner::prelude::*;
+
+use crate::{
+    npc::Npc,
+    ui::dialogue::runner::RunnerFlags,
+    world::{camera::YSort, map::navigation::NavGrid},
+    GameAssets, GameState,
+};
+
+use super::{
+    chat::PlayerStartedChat,
+    input::{MouseWorldCoords, PlayerInput},
+    movement::player_movement,
+    Player, PlayerState, NPC_PROXIMITY_DISTANCE,
+};
+
+// Half size of the area around an NPC that counts as hovering it.
+const NPC_HOVER_SIZE: Vec2 = Vec2::new(20.0, 28.0);
+const HOVER_COLOR: Color = Color::srgb(1.0, 0.85, 0.6);
+// Distance at which a waypoint counts as reached.
+const WAYPOINT_RADIUS: f32 = 4.0;
+// If the player doesn't get closer to the next waypoint
+// for this long, we assume the player is stuck and give up.
+const STUCK_TIMEOUT: f32 = 1.0;
+
+const MARKER_SIZE: f32 = 6.0;
+const MARKER_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.8);
+const MARKER_PULSE_DURATION: f32 = 0.6;
+
+#[derive(Resource, Default)]
+struct HoveredNpc(Option<Entity>);
+
+#[derive(Resource, Default)]
+struct ClickPath {
+    waypoints: VecDeque<Vec2>,
+    /// Start chatting with this NPC once the player is close enough.
+    npc: Option<Entity>,
+    closest_distance: f32,
+    stuck_timer: Timer,
+}
+
+impl ClickPath {
+    fn clear(&mut self) {
+        self.waypoints.clear();
+        self.npc = None;
+    }
+
+    fn set(&mut self, waypoints: Vec<Vec2>, npc: Option<Entity>) {
+        self.waypoints = waypoints.into();
+        self.npc = npc;
+        self.closest_distance = f32::MAX;
+        self.stuck_timer = Timer::from_seconds(STUCK_TIMEOUT, TimerMode::Once);
+    }
+}
+
+#[derive(Component)]
+struct PathMarker;
+
+fn is_hovered(mouse_pos: Vec2, npc_pos: Vec2) -> bool {
+    let d = (mouse_pos - npc_pos).abs();
+    d.x <= NPC_HOVER_SIZE.x && d.y <= NPC_HOVER_SIZE.y
+}
+
+fn update_hovered_npc(
+    mouse_coords: Res<MouseWorldCoords>,
+    mut hovered_npc: ResMut<HoveredNpc>,
+    q_npcs: Query<(Entity, &GlobalTransform), With<Npc>>,
+) {
+    hovered_npc.0 = q_npcs
+        .iter()
+        .find(|(_, transform)| is_hovered(mouse_coords.0, transform.translation().truncate()))
+        .map(|(entity, _)| entity);
+}
+
+/// Ionas and Antonius share a single `Npc` without a sprite, so we tint the children in that case.
+fn tint_npc(
+    npc: Entity,
+    color: Color,
+    q_npcs: &Query<Option<&Children>, With<Npc>>,
+    q_sprites: &mut Query<&mut Sprite>,
+) {
+    if let Ok(mut sprite) = q_sprites.get_mut(npc) {
+        sprite.color = color;
+        return;
+    }
+
+    let Ok(children) = q_npcs.get(npc) else {
+        return;
+    };
+    for child in children.into_iter().flatten() {
+        if let Ok(mut sprite) = q_sprites.get_mut(*child) {
+            sprite.color = color;
+        }
+    }
+}
+
+/// Tint the hovered NPC and restore the one that was hovered before,
+/// the sprites of all the other NPCs are left alone.
+/// During chats the tint belongs to the speaker, see `conversation`.
+fn highlight_hovered_npc(
+    hovered_npc: Res<HoveredNpc>,
+    q_player: Query<&Player>,
+    q_npcs: Query<Option<&Children>, With<Npc>>,
+    mut q_sprites: Query<&mut Sprite>,
+    mut highlighted: Local<Option<Entity>>,
+) {
+    let talking = q_player
+        .get_single()
+        .is_ok_and(|player| player.state == PlayerState::Talking);
+    let hovered = if talking { None } else { hovered_npc.0 };
+    if *highlighted == hovered {
+        return;
+    }
+
+    if let Some(npc) = *highlighted {
+        tint_npc(npc, Color::WHITE, &q_npcs, &mut q_sprites);
+    }
+    if let Some(npc) = hovered {
+        tint_npc(npc, HOVER_COLOR, &q_npcs, &mut q_sprites);
+    }
+    *highlighted = hovered;
+}
+
+fn spawn_marker(commands: &mut Commands, assets: &Res<GameAssets>, pos: Vec2) {
+    let tween = Tween::new(
+        EaseFunction::SineInOut,
+        Duration::from_secs_f32(MARKER_PULSE_DURATION),
+        TransformScaleLens {
+            start: Vec3::ONE,
+            end: Vec3::splat(1.5),
+        },
+    )
+    .with_repeat_count(RepeatCount::Infinite)
+    .with_repeat_strategy(RepeatStrategy::MirroredRepeat);
+
+    commands.spawn((
+        PathMarker,
+        // The marker lies on the ground.
+        YSort(-200.0),
+        Animator::new(tween),
+        SpriteBundle {
+            texture: assets.white_pixel.clone(),
+            sprite: Sprite {
+                color: MARKER_COLOR,
+                custom_size: Some(Vec2::splat(MARKER_SIZE)),
+                ..default()
+            },
+            transform: Transform::from_translation(pos.extend(0.0))
+                .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_4)),
+            ..default()
+        },
+    ));
+}
+
+fn start_chat_with(
+    player: &mut Player,
+    player_pos: Vec2,
+    npc_pos: Vec2,
+    npc: &Npc,
+    ev_player_started_chat: &mut EventWriter<PlayerStartedChat>,
+) {
+    player.state = PlayerState::Talking;
+    ev_player_started_chat.send(PlayerStartedChat {
+        dialogue: npc.dialogue,
+        direction: npc_pos - player_pos,
+        node: None,
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn handle_click(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    player_input: Res<PlayerInput>,
+    mouse_coords: Res<MouseWorldCoords>,
+    nav_grid: Res<NavGrid>,
+    hovered_npc: Res<HoveredNpc>,
+    mut click_path: ResMut<ClickPath>,
+    mut q_player: Query<(&Transform, &mut Player)>,
+    q_npcs: Query<(&GlobalTransform, &Npc)>,
+    q_dialogue_runners: Query<&RunnerFlags, With<DialogueRunner>>,
+    q_markers: Query<Entity, With<PathMarker>>,
+    q_interactions: Query<&Interaction>,
+    mut ev_player_started_chat: EventWriter<PlayerStartedChat>,
+) {
+    if !player_input.click {
+        return;
+    }
+    if q_dialogue_runners.iter().any(|flags| flags.active) {
+        return;
+    }
+    // The click was meant for the UI, e.g. the minimap or the journal.
+    if q_interactions
+        .iter()
+        .any(|interaction| *interaction != Interaction::None)
+    {
+        return;
+    }
+
+    let (player_transform, mut player) = match q_player.get_single_mut() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    if player.state == PlayerState::Talking {
+        return;
+    }
+
+    for entity in &q_markers {
+        commands.entity(entity).despawn_recursive();
+    }
+    click_path.clear();
+
+    let player_pos = player_transform.translation.truncate();
+    let (goal, npc) = match hovered_npc
+        .0
+        .and_then(|entity| q_npcs.get(entity).ok().map(|r| (entity, r)))
+    {
+        Some((entity, (npc_transform, npc))) => {
+            let npc_pos = npc_transform.translation().truncate();
+            if player_pos.distance_squared(npc_pos) <= NPC_PROXIMITY_DISTANCE.powi(2) {
+                start_chat_with(
+                    &mut player,
+                    player_pos,
+                    npc_pos,
+                    npc,
+                    &mut ev_player_started_chat,
+                );
+                return;
+            }
+            (npc_pos, Some(entity))
+        }
+        None => (mouse_coords.0, None),
+    };
+
+    let Some(waypoints) = nav_grid.find_path(player_pos, goal) else {
+        return;
+    };
+    if npc.is_none() {
+        if let Some(target) = waypoints.last() {
+            spawn_marker(&mut commands, &assets, *target);
+        }
+    }
+    click_path.set(waypoints, npc);
+}
+
+fn follow_click_path(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut player_input: ResMut<PlayerInput>,
+    mut click_path: ResMut<ClickPath>,
+    mut q_player: Query<(&Transform, &mut Player)>,
+    q_npcs: Query<(&GlobalTransform, &Npc)>,
+    q_markers: Query<Entity, With<PathMarker>>,
+    mut ev_player_started_chat: EventWriter<PlayerStartedChat>,
+) {
+    if click_path.waypoints.is_empty() && click_path.npc.is_none() {
+        return;
+    }
+
+    let (player_transform, mut player) = match q_player.get_single_mut() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    let player_pos = player_transform.translation.truncate();
+
+    let mut done =
+        player.state == PlayerState::Talking || player_input.move_direction != Vec2::ZERO;
+
+    if let Some((npc_transform, npc)) = click_path.npc.and_then(|e| q_npcs.get(e).ok()) {
+        let npc_pos = npc_transform.translation().truncate();
+        if !done && player_pos.distance_squared(npc_pos) <= NPC_PROXIMITY_DISTANCE.powi(2) {
+            start_chat_with(
+                &mut player,
+                player_pos,
+                npc_pos,
+                npc,
+                &mut ev_player_started_chat,
+            );
+            done = true;
+        }
+    }
+
+    while let Some(next) = click_path.waypoints.front() {
+        if player_pos.distance_squared(*next) > WAYPOINT_RADIUS.powi(2) {
+            break;
+        }
+        click_path.waypoints.pop_front();
+        click_path.closest_distance = f32::MAX;
+    }
+
+    match click_path.waypoints.front().copied() {
+        Some(next) if !done => {
+            let distance = player_pos.distance(next);
+            if distance < click_path.closest_distance {
+                click_path.closest_distance = distance;
+                click_path.stuck_timer.reset();
+            }
+            click_path.stuck_timer.tick(time.delta());
+            if click_path.stuck_timer.finished() {
+                done = true;
+            } else {
+                player_input.move_direction = (next - player_pos).normalize_or_zero();
+            }
+        }
+        _ => done = true,
+    }
+
+    if done {
+        click_path.clear();
+        for entity in &q_markers {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+pub struct PlayerClickMovePlugin;
+
+impl Plugin for PlayerClickMovePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HoveredNpc>()
+            .init_resource::<ClickPath>()
+            .add_systems(
+                Update,
+                (
+                    update_hovered_npc,
+                    highlight_hovered_npc,
+                    handle_click,
+                    follow_click_path,
+                )
+                    .chain()
+                    .before(player_movement)
+                    .run_if(in_state(GameState::Gaming)),
+            );
+    }
+}
@@ -6,10 +6,20 @@ use bevy::window::{PrimaryWindow, Window};
 use crate::world::MainCamera;
 use crate::GameState;
 
+/// Systems that fill `PlayerInput` from the keyboard and mouse.
+/// Other input sources (like touch) should run after this.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PlayerInputSystemSet;
+
+/// Runs after all the input sources, for things that replace `PlayerInput` wholesale
+/// (e.g. replays). Systems that consume the input for themselves should run after this.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PlayerInputOverrideSystemSet;
+
 #[derive(Resource, Default)]
 pub struct MouseWorldCoords(pub Vec2);
 
-#[derive(Resource, Default)]
+#[derive(Resource, Default, Clone, Debug, PartialEq)]
 pub struct PlayerInput {
     pub move_direction: Vec2,
     pub scroll: f32,
@@ -18,9 +28,19 @@ pub struct PlayerInput {
 
     pub dialogue: bool,
     pub dialogue_direction: i8,
+    /// Left mouse button or a tap on the touch screen, used for click-to-move.
+    /// Note that clicking also counts as `dialogue`.
+    pub click: bool,
 
     pub toggle_fullscreen: bool,
     pub toggle_debug: bool,
+    pub toggle_journal: bool,
+    pub toggle_inventory: bool,
+    pub toggle_minimap: bool,
+    pub toggle_npc_indicators: bool,
+    pub toggle_photo_mode: bool,
+    pub cycle_photo_filter: bool,
+    pub cycle_viewport_mode: bool,
 }
 
 fn reset_player_input(mut player_input: ResMut<PlayerInput>) {
@@ -41,9 +61,15 @@ pub fn fetch_mouse_world_coords(
         Err(_) => return,
     };
 
+    // The viewport may not cover the whole window, see `ViewportMode`.
+    let viewport_origin = camera
+        .logical_viewport_rect()
+        .map(|rect| rect.min)
+        .unwrap_or_default();
+
     if let Some(world_position) = window
         .cursor_position()
-        .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor))
+        .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor - viewport_origin))
         .map(|ray| ray.origin.truncate())
     {
         mouse_coords.0 = world_position;
@@ -150,10 +176,45 @@ fn input_dialogue(
         || mouse_buttons.just_pressed(MouseButton::Left);
 }
 
+fn input_click(
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    mut player_input: ResMut<PlayerInput>,
+) {
+    player_input.click = mouse_buttons.just_pressed(MouseButton::Left);
+}
+
 fn toggle_fullscreen(keys: Res<ButtonInput<KeyCode>>, mut player_input: ResMut<PlayerInput>) {
     player_input.toggle_fullscreen = keys.just_pressed(KeyCode::KeyB);
 }
 
+fn cycle_viewport_mode(keys: Res<ButtonInput<KeyCode>>, mut player_input: ResMut<PlayerInput>) {
+    player_input.cycle_viewport_mode = keys.just_pressed(KeyCode::KeyV);
+}
+
+fn toggle_journal(keys: Res<ButtonInput<KeyCode>>, mut player_input: ResMut<PlayerInput>) {
+    player_input.toggle_journal = keys.just_pressed(KeyCode::Tab);
+}
+
+fn toggle_inventory(keys: Res<ButtonInput<KeyCode>>, mut player_input: ResMut<PlayerInput>) {
+    player_input.toggle_inventory = keys.just_pressed(KeyCode::KeyI);
+}
+
+fn toggle_minimap(keys: Res<ButtonInput<KeyCode>>, mut player_input: ResMut<PlayerInput>) {
+    player_input.toggle_minimap = keys.just_pressed(KeyCode::KeyM);
+}
+
+fn toggle_npc_indicators(keys: Res<ButtonInput<KeyCode>>, mut player_input: ResMut<PlayerInput>) {
+    player_input.toggle_npc_indicators = keys.just_pressed(KeyCode::KeyN);
+}
+
+fn toggle_photo_mode(keys: Res<ButtonInput<KeyCode>>, mut player_input: ResMut<PlayerInput>) {
+    player_input.toggle_photo_mode = keys.just_pressed(KeyCode::KeyP);
+}
+
+fn cycle_photo_filter(keys: Res<ButtonInput<KeyCode>>, mut player_input: ResMut<PlayerInput>) {
+    player_input.cycle_photo_filter = keys.just_pressed(KeyCode::KeyC);
+}
+
 fn toggle_debug(keys: Res<ButtonInput<KeyCode>>, mut player_input: ResMut<PlayerInput>) {
     player_input.toggle_debug = keys.just_pressed(KeyCode::F3);
 }
@@ -172,12 +233,25 @@ impl Plugin for InputPlugin {
                 input_running,
                 input_escape,
                 input_dialogue,
+                input_click,
                 toggle_fullscreen,
                 toggle_debug,
+                toggle_journal,
+                toggle_inventory,
+                toggle_minimap,
+                toggle_npc_indicators,
+                toggle_photo_mode,
+                cycle_photo_filter,
+                cycle_viewport_mode,
             )
                 .run_if(not(in_state(GameState::AssetLoading)))
+                .in_set(PlayerInputSystemSet)
                 .after(InputSystem),
         )
+        .configure_sets(
+            PreUpdate,
+            PlayerInputOverrideSystemSet.after(PlayerInputSystemSet),
+        )
         .init_resource::<PlayerInput>()
         .init_resource::<MouseWorldCoords>()
         .add_systems(PreUpdate, reset_player_input.before(InputSystem));
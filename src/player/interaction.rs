@@ -0,0 +1,266 @@
+use bevy::prelude::*;
+use bevy_yarnspinner::prelude::*;
+
+use crate::{
+    npc::{companion::Companion, Npc},
+    ui::dialogue::runner::RunnerFlags,
+    GameState,
+};
+
+use super::{
+    chat::PlayerStartedInspection, input::PlayerInput, inventory::Inventory, Player, PlayerState,
+    NPC_PROXIMITY_DISTANCE,
+};
+
+// How far in front of the seat the player sits.
+const SEAT_OFFSET: Vec2 = Vec2::new(0.0, 6.0);
+
+/// What happens when the player interacts with an `Interactable`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InteractAction {
+    /// Run the yarn node, e.g. to inspect the object.
+    RunNode(&'static str),
+    /// Remove the object from the world and put the item (see `inventory::ITEMS`) into the inventory.
+    /// The yarn node (if any) is run as well.
+    PickUp {
+        item: &'static str,
+        node: Option<&'static str>,
+    },
+    /// Flip `Interactable::toggled`, other systems react to the new state.
+    Toggle { toggled_prompt: &'static str },
+}
+
+/// Something in the world the player can interact with, with the same key as talking to NPCs.
+#[derive(Component)]
+pub struct Interactable {
+    pub radius: f32,
+    pub prompt: &'static str,
+    pub action: InteractAction,
+    pub toggled: bool,
+}
+
+impl Interactable {
+    pub fn new(radius: f32, prompt: &'static str, action: InteractAction) -> Self {
+        Self {
+            radius,
+            prompt,
+            action,
+            toggled: false,
+        }
+    }
+
+    pub fn current_prompt(&self) -> &'static str {
+        match self.action {
+            InteractAction::Toggle { toggled_prompt } if self.toggled => toggled_prompt,
+            _ => self.prompt,
+        }
+    }
+}
+
+/// The player sits on this while it's toggled.
+/// Needs an `Interactable` with `InteractAction::Toggle`.
+#[derive(Component)]
+pub struct Seat;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InteractTarget {
+    Npc(Entity),
+    Object(Entity),
+}
+
+/// The closest thing the player can talk to or interact with, updated every frame.
+/// Companions are only chosen when there is nothing else around,
+/// they are always standing right next to the player.
+#[derive(Resource, Default)]
+pub struct NearestTarget {
+    pub target: Option<InteractTarget>,
+    pub pos: Vec2,
+}
+
+/// Sent for every interaction with an `Interactable`.
+#[derive(Event)]
+pub struct Interacted {
+    pub entity: Entity,
+}
+
+pub(super) fn update_nearest_target(
+    mut nearest_target: ResMut<NearestTarget>,
+    q_player: Query<(&Transform, &Player)>,
+    q_npcs: Query<(Entity, &GlobalTransform, Has<Companion>), With<Npc>>,
+    q_interactables: Query<(Entity, &GlobalTransform, &Interactable)>,
+) {
+    let (player_transform, player) = match q_player.get_single() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    if player.state == PlayerState::Talking {
+        if nearest_target.target.is_some() {
+            nearest_target.target = None;
+        }
+        return;
+    }
+
+    let player_pos = player_transform.translation.xy();
+    let npcs = q_npcs.iter().map(|(entity, transform, is_companion)| {
+        (
+            InteractTarget::Npc(entity),
+            transform.translation().xy(),
+            NPC_PROXIMITY_DISTANCE,
+            is_companion,
+        )
+    });
+    let objects = q_interactables
+        .iter()
+        .map(|(entity, transform, interactable)| {
+            (
+                InteractTarget::Object(entity),
+                transform.translation().xy(),
+                interactable.radius,
+                false,
+            )
+        });
+
+    let closest = npcs
+        .chain(objects)
+        .filter(|(_, pos, radius, _)| player_pos.distance_squared(*pos) <= radius.powi(2))
+        .min_by(|(_, a, _, a_companion), (_, b, _, b_companion)| {
+            a_companion.cmp(b_companion).then(
+                player_pos
+                    .distance_squared(*a)
+                    .total_cmp(&player_pos.distance_squared(*b)),
+            )
+        });
+
+    let (target, pos) = match closest {
+        Some((target, pos, _, _)) => (Some(target), pos),
+        None => (None, Vec2::ZERO),
+    };
+    if nearest_target.target != target || nearest_target.pos != pos {
+        nearest_target.target = target;
+        nearest_target.pos = pos;
+    }
+}
+
+fn interact(
+    mut commands: Commands,
+    player_input: Res<PlayerInput>,
+    nearest_target: Res<NearestTarget>,
+    mut inventory: ResMut<Inventory>,
+    mut q_player: Query<(&Transform, &mut Player)>,
+    mut q_interactables: Query<&mut Interactable>,
+    q_dialogue_runners: Query<&RunnerFlags, With<DialogueRunner>>,
+    mut ev_interacted: EventWriter<Interacted>,
+    mut ev_player_started_inspection: EventWriter<PlayerStartedInspection>,
+) {
+    // Same as for chats, clicks are handled in `click_move`.
+    if !player_input.dialogue || player_input.click {
+        return;
+    }
+    if q_dialogue_runners.iter().any(|flags| flags.active) {
+        return;
+    }
+    let Some(InteractTarget::Object(entity)) = nearest_target.target else {
+        return;
+    };
+
+    let (player_transform, mut player) = match q_player.get_single_mut() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    let mut interactable = match q_interactables.get_mut(entity) {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    let node = match interactable.action {
+        InteractAction::RunNode(node) => Some(node),
+        InteractAction::PickUp { item, node } => {
+            if inventory.add(item).is_none() {
+                error!("trying to pick up an item that doesn't exist, '{}'", item);
+            }
+            commands.entity(entity).despawn_recursive();
+            node
+        }
+        InteractAction::Toggle { .. } => {
+            interactable.toggled = !interactable.toggled;
+            None
+        }
+    };
+
+    if let Some(node) = node {
+        player.state = PlayerState::Talking;
+        ev_player_started_inspection.send(PlayerStartedInspection {
+            node,
+            direction: nearest_target.pos - player_transform.translation.xy(),
+        });
+    }
+    ev_interacted.send(Interacted { entity });
+}
+
+fn sit_down(
+    mut q_player: Query<(&mut Transform, &mut Player)>,
+    q_seats: Query<(&GlobalTransform, &Interactable), With<Seat>>,
+    mut ev_interacted: EventReader<Interacted>,
+) {
+    let (mut player_transform, mut player) = match q_player.get_single_mut() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    for ev in ev_interacted.read() {
+        let Ok((seat_transform, interactable)) = q_seats.get(ev.entity) else {
+            continue;
+        };
+
+        if interactable.toggled {
+            let pos = seat_transform.translation().xy() + SEAT_OFFSET;
+            player_transform.translation = pos.extend(player_transform.translation.z);
+            player.current_direction = Vec2::NEG_Y;
+            player.state = PlayerState::Sitting;
+        } else {
+            player.state = PlayerState::Idling;
+        }
+    }
+}
+
+/// Moving gets the player back up, and other interactions (e.g. a chat)
+/// may also end the sitting. The seats need to reflect that.
+fn stand_up(
+    player_input: Res<PlayerInput>,
+    mut q_player: Query<&mut Player>,
+    mut q_seats: Query<&mut Interactable, With<Seat>>,
+) {
+    let mut player = match q_player.get_single_mut() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    if player.state == PlayerState::Sitting
+        && (player_input.move_direction != Vec2::ZERO || player_input.click)
+    {
+        player.state = PlayerState::Idling;
+    }
+
+    if player.state != PlayerState::Sitting {
+        for mut interactable in &mut q_seats {
+            if interactable.toggled {
+                interactable.toggled = false;
+            }
+        }
+    }
+}
+
+pub struct PlayerInteractionPlugin;
+
+impl Plugin for PlayerInteractionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NearestTarget>()
+            .add_event::<Interacted>()
+            .add_systems(
+                Update,
+                (update_nearest_target, interact, sit_down, stand_up)
+                    .chain()
+                    .run_if(in_state(GameState::Gaming)),
+            );
+    }
+}
@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod test;
+
+use std::sync::{Arc, PoisonError, RwLock};
+
+use bevy::prelude::*;
+
+/// Something Pai can carry around.
+/// The `id` is what the yarn files use, e.g. `<<give_item bread>>` or `has_item("stone")`.
+#[derive(Debug, PartialEq)]
+pub struct ItemData {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+pub const ITEMS: [ItemData; 2] = [
+    ItemData {
+        id: "stone",
+        name: "Smooth stone",
+        description: "Picked up by the path. It fits nicely in the hand.",
+    },
+    ItemData {
+        id: "bread",
+        name: "Loaf of bread",
+        description: "Isabelle baked it this morning, it's still a little warm.",
+    },
+];
+
+pub fn item_data(id: &str) -> Option<&'static ItemData> {
+    ITEMS.iter().find(|item| item.id == id)
+}
+
+/// The items Pai is carrying, in the order they were received.
+/// The contents are shared with the yarn function `has_item()`,
+/// yarn functions can't access the ECS so they hold a clone of this.
+#[derive(Resource, Clone, Default)]
+pub struct Inventory(Arc<RwLock<Vec<(&'static ItemData, u32)>>>);
+
+impl Inventory {
+    /// Returns `None` if there is no item with the given id.
+    pub fn add(&mut self, id: &str) -> Option<&'static ItemData> {
+        let item = item_data(id)?;
+        let mut items = self.0.write().unwrap_or_else(PoisonError::into_inner);
+        match items.iter_mut().find(|(data, _)| data.id == id) {
+            Some((_, count)) => *count += 1,
+            None => items.push((item, 1)),
+        }
+        Some(item)
+    }
+
+    /// Returns `false` if the item isn't in the inventory.
+    pub fn remove(&mut self, id: &str) -> bool {
+        let mut items = self.0.write().unwrap_or_else(PoisonError::into_inner);
+        let Some(index) = items.iter().position(|(data, _)| data.id == id) else {
+            return false;
+        };
+
+        items[index].1 -= 1;
+        if items[index].1 == 0 {
+            items.remove(index);
+        }
+        true
+    }
+
+    pub fn has(&self, id: &str) -> bool {
+        self.count(id) > 0
+    }
+
+    pub fn count(&self, id: &str) -> u32 {
+        self.0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .find(|(data, _)| data.id == id)
+            .map_or(0, |(_, count)| *count)
+    }
+
+    pub fn items(&self) -> Vec<(&'static ItemData, u32)> {
+        self.0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+pub struct InventoryPlugin;
+
+impl Plugin for InventoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Inventory>();
+    }
+}
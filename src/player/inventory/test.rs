@@ -0,0 +1,48 @@
+use bevy::utils::HashSet;
+
+use super::*;
+
+#[test]
+fn item_ids_are_unique() {
+    let mut ids = HashSet::new();
+    for item in &ITEMS {
+        assert!(ids.insert(item.id), "item id '{}' is not unique", item.id);
+        assert!(
+            !item.id.contains(char::is_whitespace),
+            "item id '{}' can't be used in yarn commands",
+            item.id
+        );
+    }
+}
+
+#[test]
+fn add_and_remove_items() {
+    let mut inventory = Inventory::default();
+    assert!(!inventory.has("stone"));
+
+    assert_eq!(inventory.add("stone"), item_data("stone"));
+    assert_eq!(inventory.add("stone"), item_data("stone"));
+    assert_eq!(inventory.count("stone"), 2);
+
+    assert!(inventory.remove("stone"));
+    assert!(inventory.has("stone"));
+    assert!(inventory.remove("stone"));
+    assert!(!inventory.has("stone"));
+    assert!(!inventory.remove("stone"));
+    assert!(inventory.items().is_empty());
+}
+
+#[test]
+fn unknown_items_are_rejected() {
+    let mut inventory = Inventory::default();
+    assert_eq!(inventory.add("not_an_item"), None);
+    assert!(inventory.items().is_empty());
+}
+
+#[test]
+fn clones_share_the_contents() {
+    let mut inventory = Inventory::default();
+    let shared = inventory.clone();
+    inventory.add("bread");
+    assert!(shared.has("bread"));
+}
@@ -1,8 +1,11 @@
 pub mod chat;
 pub mod input;
+pub mod interaction;
+pub mod inventory;
 pub mod state;
 
 mod audio;
+mod click_move;
 mod collision;
 mod movement;
 mod spawn;
@@ -29,8 +32,11 @@ impl Plugin for PlayerPlugin {
             state::PlayerStatePlugin,
             audio::PlayerAudioPlugin,
             chat::PlayerChatPlugin,
+            interaction::PlayerInteractionPlugin,
+            inventory::InventoryPlugin,
             spawn::PlayerSpawnPlugin,
             movement::PlayerMovementPlugin,
+            click_move::PlayerClickMovePlugin,
         ));
     }
 }
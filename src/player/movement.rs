@@ -4,11 +4,11 @@ use bevy_rapier2d::prelude::*;
 use crate::utils::DebugActive;
 use crate::GameState;
 
-use super::chat::PlayerStartedChat;
+use super::chat::{PlayerStartedChat, PlayerStartedInspection};
 use super::input::PlayerInput;
 use super::{Player, PlayerState, RUN_SPEED, WALK_SPEED};
 
-fn player_movement(
+pub fn player_movement(
     player_input: Res<PlayerInput>,
     debug_active: Res<DebugActive>,
     mut q_player: Query<(&mut Velocity, &mut Player)>,
@@ -19,7 +19,10 @@ fn player_movement(
     };
 
     let direction = player_input.move_direction;
-    if player.state == PlayerState::Talking || direction == Vec2::ZERO {
+    if player.state == PlayerState::Talking
+        || player.state == PlayerState::Sitting
+        || direction == Vec2::ZERO
+    {
         velocity.linvel = Vec2::ZERO;
         return;
     };
@@ -42,14 +45,19 @@ fn player_movement(
 fn face_npc(
     mut q_player: Query<&mut Player>,
     mut ev_player_started_chat: EventReader<PlayerStartedChat>,
+    mut ev_player_started_inspection: EventReader<PlayerStartedInspection>,
 ) {
-    for ev in ev_player_started_chat.read() {
+    let directions = ev_player_started_chat
+        .read()
+        .map(|ev| ev.direction)
+        .chain(ev_player_started_inspection.read().map(|ev| ev.direction));
+    for direction in directions {
         let mut player = match q_player.get_single_mut() {
             Ok(p) => p,
             Err(_) => return,
         };
 
-        player.current_direction = ev.direction;
+        player.current_direction = direction;
     }
 }
 
@@ -19,6 +19,8 @@ pub enum PlayerState {
     Walking,
     Running,
     Talking,
+    /// On a `Seat`, see `interaction`.
+    Sitting,
 }
 
 fn switch_to_idling_from_talking(mut q_player: Query<&mut Player>) {
@@ -38,7 +40,7 @@ fn switch_player_move_state(
         Err(_) => return,
     };
 
-    if player.state == PlayerState::Talking {
+    if player.state == PlayerState::Talking || player.state == PlayerState::Sitting {
         return;
     }
 
@@ -93,6 +95,8 @@ fn update_animation(
         PlayerState::Walking => (assets.player_animations[4 + direction_index].clone(), true),
         PlayerState::Running => (assets.player_animations[8 + direction_index].clone(), true),
         PlayerState::Talking => (assets.player_animations[direction_index].clone(), true),
+        // There is no sitting animation, so we just idle on the seat.
+        PlayerState::Sitting => (assets.player_animations[direction_index].clone(), true),
     };
 
     if repeat {
@@ -24,7 +24,10 @@ fn spawn_bar(mut commands: Commands, assets: Res<GameAssets>) {
         color: Color::WHITE,
     };
     let text_bundle = TextBundle::from_sections([TextSection::new("", text_style)]);
-    let text = commands.spawn((BarText, text_bundle)).id();
+    // Blocks click-to-move while the bar is shown.
+    let text = commands
+        .spawn((BarText, Interaction::default(), text_bundle))
+        .id();
 
     commands
         .spawn((
@@ -4,13 +4,18 @@ use bevy::prelude::*;
 use bevy_yarnspinner::prelude::*;
 
 use crate::{
-    npc::{Npc, NpcDialogue},
-    player::{chat::PlayerStoppedChat, Player, PlayerState},
-    world::ending::EndingTriggered,
+    npc::{companion::NpcJoinedParty, Npc, NpcDialogue},
+    player::{chat::PlayerStoppedChat, inventory::Inventory, Player, PlayerState},
+    ui::journal::{Journal, JournalUpdated},
+    world::{camera_director::DirectCamera, ending::EndingTriggered},
 };
 
 use super::runner::RunnerFlags;
 
+// The name the yarn files use for the player.
+const PLAYER_NAME: &str = "Pai";
+const CAMERA_RESET_DURATION: f32 = 1.0;
+
 pub fn stop_chat_command(
     In(_): In<()>,
     q_player: Query<&Player>,
@@ -89,3 +94,174 @@ pub fn trigger_ending_command(
     };
     ev_ending_triggered.send(EndingTriggered { dialogue });
 }
+
+pub fn join_party_command(
+    In(npc_name): In<&str>,
+    mut ev_npc_joined_party: EventWriter<NpcJoinedParty>,
+) {
+    let dialogue = match NpcDialogue::from_str(npc_name.trim_start_matches('_')) {
+        Ok(r) => r,
+        Err(err) => {
+            error!("Not a valid npc name! {}", err);
+            return;
+        }
+    };
+    ev_npc_joined_party.send(NpcJoinedParty { dialogue });
+}
+
+/// The lead is completed right away if the player already talked to the target NPC.
+pub fn add_lead_command(
+    In((source_npc, target_npc)): In<(&str, &str)>,
+    mut journal: ResMut<Journal>,
+    q_npcs: Query<&Npc>,
+    mut ev_journal_updated: EventWriter<JournalUpdated>,
+) {
+    let source_npc = match NpcDialogue::from_str(source_npc.trim_start_matches('_')) {
+        Ok(r) => r,
+        Err(err) => {
+            error!("Not a valid npc name! {}", err);
+            return;
+        }
+    };
+
+    let target_npc = match NpcDialogue::from_str(target_npc.trim_start_matches('_')) {
+        Ok(r) => r,
+        Err(err) => {
+            error!("Not a valid npc name! {}", err);
+            return;
+        }
+    };
+
+    if let Some(text) = journal.add_lead(source_npc, target_npc) {
+        ev_journal_updated.send(JournalUpdated(text));
+    }
+
+    if q_npcs
+        .iter()
+        .any(|npc| npc.dialogue == target_npc && npc.was_talked_to)
+    {
+        for text in journal.complete_leads(target_npc) {
+            ev_journal_updated.send(JournalUpdated(format!("Done! {text}")));
+        }
+    }
+}
+
+pub fn complete_lead_command(
+    In(npc_name): In<&str>,
+    mut journal: ResMut<Journal>,
+    mut ev_journal_updated: EventWriter<JournalUpdated>,
+) {
+    let dialogue = match NpcDialogue::from_str(npc_name.trim_start_matches('_')) {
+        Ok(r) => r,
+        Err(err) => {
+            error!("Not a valid npc name! {}", err);
+            return;
+        }
+    };
+
+    for text in journal.complete_leads(dialogue) {
+        ev_journal_updated.send(JournalUpdated(format!("Done! {text}")));
+    }
+}
+
+pub fn give_item_command(In(item): In<&str>, mut inventory: ResMut<Inventory>) {
+    if inventory.add(item).is_none() {
+        error!("Not a valid item! {}", item);
+    }
+}
+
+/// Hand an item over to the NPC, this sets `$gifted_<item>` in the NPC's dialogue.
+/// The variable needs to be declared in the yarn file.
+pub fn gift_item_command(
+    In((npc_name, item)): In<(&str, &str)>,
+    mut inventory: ResMut<Inventory>,
+    mut q_dialogue_runners: Query<(&mut DialogueRunner, &RunnerFlags)>,
+) {
+    let dialogue = match NpcDialogue::from_str(npc_name.trim_start_matches('_')) {
+        Ok(r) => r,
+        Err(err) => {
+            error!("Not a valid npc name! {}", err);
+            return;
+        }
+    };
+
+    if !inventory.remove(item) {
+        error!(
+            "Trying to gift {} to {}, but the item is not in the inventory!",
+            item, dialogue
+        );
+        return;
+    }
+
+    for (mut runner, flags) in &mut q_dialogue_runners {
+        if flags.dialogue != Some(dialogue) {
+            continue;
+        }
+
+        let variable_storage = runner.variable_storage_mut();
+        let variable = format!("$gifted_{item}");
+        if !variable_storage.contains(&variable) {
+            error!("Npc {}, does not contain var {}", dialogue, variable);
+            continue;
+        }
+        if let Err(err) = variable_storage.set(variable, (true).into()) {
+            error!("{}, {}", dialogue, err);
+        }
+    }
+}
+
+/// `<<camera_focus Eleonore 1.5>>`, the NPC can also be `Pai`.
+/// An optional third argument sets the zoom.
+pub fn camera_focus_command(
+    In((name, duration, zoom)): In<(&str, f32, Option<f32>)>,
+    q_player: Query<Entity, With<Player>>,
+    q_npcs: Query<(Entity, &Npc)>,
+    mut ev_direct_camera: EventWriter<DirectCamera>,
+) {
+    let name = name.trim_start_matches('_');
+    let entity = if name == PLAYER_NAME {
+        q_player.get_single().ok()
+    } else {
+        let dialogue = match NpcDialogue::from_str(name) {
+            Ok(r) => r,
+            Err(err) => {
+                error!("Not a valid npc name! {}", err);
+                return;
+            }
+        };
+        q_npcs
+            .iter()
+            .find(|(_, npc)| npc.dialogue == dialogue)
+            .map(|(entity, _)| entity)
+    };
+
+    let Some(entity) = entity else {
+        error!("Can't focus the camera on {}, not spawned", name);
+        return;
+    };
+    ev_direct_camera.send(DirectCamera::Focus {
+        entity,
+        zoom,
+        duration,
+    });
+}
+
+pub fn camera_pan_command(
+    In((x, y, duration)): In<(f32, f32, f32)>,
+    mut ev_direct_camera: EventWriter<DirectCamera>,
+) {
+    ev_direct_camera.send(DirectCamera::Pan {
+        pos: Vec2::new(x, y),
+        zoom: None,
+        duration,
+    });
+}
+
+pub fn camera_reset_command(
+    In(duration): In<Option<f32>>,
+    mut ev_direct_camera: EventWriter<DirectCamera>,
+) {
+    ev_direct_camera.send(DirectCamera::Reset {
+        duration: duration.unwrap_or(CAMERA_RESET_DURATION),
+    });
+}
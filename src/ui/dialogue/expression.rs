@@ -0,0 +1,190 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_tweening::{lens::*, *};
+use bevy_yarnspinner::{events::*, prelude::*};
+use strum_macros::{Display, EnumIter, EnumString};
+
+use crate::{
+    npc::{conversation::Conversation, NpcDialogue},
+    GameAssets, GameState,
+};
+
+/// The portrait variants that have art, all other combinations fall back to the
+/// neutral portrait. The files live next to the neutral ones and are named
+/// `<name>_<expression>.png`, e.g. `ui/character_icons/jotem_happy.png`.
+const PORTRAIT_VARIANTS: &[(&str, Expression)] = &[
+    ("eleonore", Expression::Happy),
+    ("eleonore", Expression::Angry),
+    ("jotem", Expression::Happy),
+    ("isabelle", Expression::Happy),
+    ("antonius", Expression::Happy),
+    ("antonius", Expression::Sad),
+    ("antonius", Expression::Angry),
+];
+
+const HAPPY_HOP_SCALE: Vec3 = Vec3::new(0.95, 1.08, 1.0);
+const HAPPY_HOP_DURATION: f32 = 0.15;
+const SAD_SLUMP_SCALE: Vec3 = Vec3::new(1.03, 0.93, 1.0);
+const SAD_SLUMP_DURATION: f32 = 0.6;
+const ANGRY_SHAKE_ANGLE: f32 = 0.08;
+const ANGRY_SHAKE_DURATION: f32 = 0.06;
+
+/// The emotion of a line, set with a tag in yarn, e.g. `Jotem: Haha! #happy`.
+#[derive(Clone, Copy, Debug, Default, Display, EnumIter, EnumString, PartialEq, Eq, Hash)]
+#[strum(serialize_all = "lowercase")]
+pub enum Expression {
+    #[default]
+    Neutral,
+    Happy,
+    Sad,
+    Angry,
+}
+
+impl Expression {
+    pub fn from_metadata(metadata: &[String]) -> Self {
+        metadata
+            .iter()
+            .find_map(|tag| Expression::from_str(tag.trim_start_matches('#')).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// The portrait variants for each character,
+/// keyed by the file name of the neutral portrait, e.g. `pai` or `jotem`.
+#[derive(Resource, Default)]
+pub struct PortraitVariants(HashMap<(String, Expression), Handle<Image>>);
+
+impl PortraitVariants {
+    pub fn get(&self, name: &str, expression: Expression) -> Option<Handle<Image>> {
+        self.0.get(&(name.to_string(), expression)).cloned()
+    }
+}
+
+/// The asset path of the portrait variant, `None` if there is no art for it.
+pub fn portrait_variant_path(name: &str, expression: Expression) -> Option<String> {
+    PORTRAIT_VARIANTS
+        .contains(&(name, expression))
+        .then(|| format!("ui/character_icons/{name}_{expression}.png"))
+}
+
+fn load_portrait_variants(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let mut variants = HashMap::new();
+    for (name, expression) in PORTRAIT_VARIANTS {
+        if let Some(path) = portrait_variant_path(name, *expression) {
+            variants.insert((name.to_string(), *expression), asset_server.load(path));
+        }
+    }
+    commands.insert_resource(PortraitVariants(variants));
+}
+
+/// Play a short reaction on the in-world sprite of the speaker.
+/// The NPCs only have idle animations, so these are tweens on the sprite instead.
+/// Scale and rotation are used because the translation is owned by the movement and y-sorting.
+fn reaction_animator(expression: Expression) -> Option<Animator<Transform>> {
+    match expression {
+        Expression::Neutral => None,
+        Expression::Happy => Some(Animator::new(
+            Tween::new(
+                EaseFunction::QuadraticOut,
+                Duration::from_secs_f32(HAPPY_HOP_DURATION),
+                TransformScaleLens {
+                    start: Vec3::ONE,
+                    end: HAPPY_HOP_SCALE,
+                },
+            )
+            .with_repeat_count(RepeatCount::Finite(4))
+            .with_repeat_strategy(RepeatStrategy::MirroredRepeat),
+        )),
+        Expression::Sad => Some(Animator::new(
+            Tween::new(
+                EaseFunction::SineInOut,
+                Duration::from_secs_f32(SAD_SLUMP_DURATION),
+                TransformScaleLens {
+                    start: Vec3::ONE,
+                    end: SAD_SLUMP_SCALE,
+                },
+            )
+            .with_repeat_count(RepeatCount::Finite(2))
+            .with_repeat_strategy(RepeatStrategy::MirroredRepeat),
+        )),
+        Expression::Angry => Some(Animator::new(
+            Tween::new(
+                EaseFunction::SineInOut,
+                Duration::from_secs_f32(ANGRY_SHAKE_DURATION),
+                TransformRotateZLens {
+                    start: -ANGRY_SHAKE_ANGLE,
+                    end: ANGRY_SHAKE_ANGLE,
+                },
+            )
+            .then(
+                Tween::new(
+                    EaseFunction::SineInOut,
+                    Duration::from_secs_f32(ANGRY_SHAKE_DURATION),
+                    TransformRotateZLens {
+                        start: ANGRY_SHAKE_ANGLE,
+                        end: -ANGRY_SHAKE_ANGLE,
+                    },
+                )
+                .with_repeat_count(RepeatCount::Finite(3))
+                .with_repeat_strategy(RepeatStrategy::MirroredRepeat),
+            )
+            .then(Tween::new(
+                EaseFunction::SineInOut,
+                Duration::from_secs_f32(ANGRY_SHAKE_DURATION),
+                TransformRotateZLens {
+                    start: ANGRY_SHAKE_ANGLE,
+                    end: 0.0,
+                },
+            )),
+        )),
+    }
+}
+
+fn play_reactions(
+    mut commands: Commands,
+    conversation: Res<Conversation>,
+    mut q_transforms: Query<&mut Transform>,
+    mut ev_present_line: EventReader<PresentLineEvent>,
+) {
+    for ev in ev_present_line.read() {
+        let expression = Expression::from_metadata(&ev.line.metadata);
+        let Some(animator) = reaction_animator(expression) else {
+            continue;
+        };
+
+        let name = ev.line.character_name().unwrap_or_default();
+        let speaker = match NpcDialogue::from_str(name.trim_start_matches('_')) {
+            Ok(r) => r,
+            Err(_) => continue,
+        };
+        if let Some(participant) = conversation
+            .participants
+            .iter()
+            .find(|p| p.speaker == speaker)
+        {
+            // A reaction may have been cut off by this one.
+            if let Ok(mut transform) = q_transforms.get_mut(participant.entity) {
+                transform.scale = Vec3::ONE;
+                transform.rotation = Quat::IDENTITY;
+            }
+            commands.entity(participant.entity).insert(animator);
+        }
+    }
+}
+
+pub struct DialogueExpressionPlugin;
+
+impl Plugin for DialogueExpressionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PortraitVariants>()
+            .add_systems(OnExit(GameState::AssetLoading), load_portrait_variants)
+            .add_systems(
+                Update,
+                play_reactions
+                    .after(YarnSpinnerSystemSet)
+                    .run_if(in_state(GameState::Gaming).and_then(resource_exists::<GameAssets>)),
+            );
+    }
+}
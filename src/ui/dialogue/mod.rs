@@ -1,6 +1,7 @@
 pub mod runner;
 
 mod command;
+mod expression;
 mod option_selection;
 mod spawn;
 mod start_hint;
@@ -24,6 +25,7 @@ impl Plugin for DialoguePlugin {
             option_selection::DialogueSelectionPlugin,
             typewriter::DialogueTypewriterPlugin,
             runner::DialogueRunnerPlugin,
+            expression::DialogueExpressionPlugin,
             start_hint::DialogueStartHintPlugin,
         ));
     }
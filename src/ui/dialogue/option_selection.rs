@@ -1,5 +1,5 @@
 use bevy::color::palettes::css::TOMATO;
-use bevy::input::mouse::MouseMotion;
+use bevy::input::{mouse::MouseMotion, touch::Touches};
 use bevy::prelude::*;
 use bevy_yarnspinner::{events::*, prelude::*};
 
@@ -105,6 +105,7 @@ fn select_option(
     mut q_text: Query<&mut Text, With<OptionsText>>,
     mut selected_option_event: EventWriter<HasSelectedOptionEvent>,
     mut ev_mouse_motion: EventReader<MouseMotion>,
+    touches: Res<Touches>,
 ) {
     if !typewriter.is_finished() {
         return;
@@ -114,6 +115,10 @@ fn select_option(
         ev_mouse_motion.clear();
         option_selection.mouse_input = true;
     }
+    // Tapping an option goes through `Interaction` just like the mouse.
+    if touches.any_just_pressed() {
+        option_selection.mouse_input = true;
+    }
 
     let direction = player_input.dialogue_direction;
     if direction != 0 {
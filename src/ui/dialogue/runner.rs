@@ -1,15 +1,30 @@
 use bevy::prelude::*;
-use bevy_yarnspinner::{events::DialogueCompleteEvent, prelude::*};
+use bevy_yarnspinner::{
+    events::{DialogueCompleteEvent, NodeStartEvent},
+    prelude::*,
+};
+use rand::Rng;
 
 use crate::{
-    npc::{Npc, NpcDialogue},
-    player::chat::{PlayerStartedChat, PlayerStoppedChat},
-    world::ending::EndingTriggered,
+    npc::{
+        conversation::{start_conversation, Conversation},
+        Npc, NpcDialogue,
+    },
+    player::{
+        chat::{PlayerStartedChat, PlayerStartedInspection, PlayerStoppedChat},
+        inventory::Inventory,
+    },
+    utils::{RngStream, SeededRng},
+    world::{ending::EndingTriggered, exploration::ExploredPercent},
     GameState,
 };
 
 use super::{
-    command::{stop_chat_command, target_npc_mentioned_command, trigger_ending_command},
+    command::{
+        add_lead_command, camera_focus_command, camera_pan_command, camera_reset_command,
+        complete_lead_command, gift_item_command, give_item_command, join_party_command,
+        stop_chat_command, target_npc_mentioned_command, trigger_ending_command,
+    },
     option_selection::{CreateOptions, OptionSelection},
     spawn::{DialogueContent, DialogueRoot},
     typewriter::{Typewriter, WriteDialogueText},
@@ -20,7 +35,9 @@ const DELAY_FRAMES_UPDATE_TARGET_NPCS: usize = 3;
 #[derive(Component)]
 pub struct RunnerFlags {
     pub active: bool,
-    pub dialogue: NpcDialogue,
+    /// The NPC this runner belongs to.
+    /// `None` for inspections of the world, these runners are never cached.
+    pub dialogue: Option<NpcDialogue>,
     pub line: Option<LocalizedLine>,
     pub options: Option<OptionSelection>,
 }
@@ -29,13 +46,14 @@ pub struct RunnerFlags {
 #[derive(Event)]
 struct SpawnDialogueRunner {
     dialogue: NpcDialogue,
+    node: Option<String>,
 }
 
 #[derive(Event)]
 pub struct UpdateTargetNpcs;
 
 impl RunnerFlags {
-    fn new(dialogue: NpcDialogue) -> Self {
+    fn new(dialogue: Option<NpcDialogue>) -> Self {
         Self {
             active: true,
             dialogue,
@@ -45,10 +63,62 @@ impl RunnerFlags {
     }
 }
 
+fn create_dialogue_runner(
+    project: &YarnProject,
+    explored_percent: &ExploredPercent,
+    inventory: &Inventory,
+    rng: &SeededRng,
+) -> DialogueRunner {
+    let mut dialogue_runner = project.create_dialogue_runner();
+    let explored_percent = explored_percent.clone();
+    let inventory = inventory.clone();
+    let (dice_rng, random_rng, random_range_rng) = (rng.clone(), rng.clone(), rng.clone());
+    dialogue_runner
+        .library_mut()
+        .add_function("explored_percent", move || explored_percent.get())
+        .add_function("has_item", move |item: &str| inventory.has(item))
+        // These replace the ones from the standard library, which aren't seeded.
+        .add_function("dice", move |sides: u32| {
+            dice_rng
+                .lock(RngStream::Dialogue)
+                .gen_range(1..=sides.max(1))
+        })
+        .add_function("random", move || {
+            random_rng.lock(RngStream::Dialogue).gen_range(0.0..1.0)
+        })
+        .add_function("random_range", move |min: f32, max: f32| {
+            let mut rng = random_range_rng.lock(RngStream::Dialogue);
+            if min.fract() == 0.0 && max.fract() == 0.0 {
+                rng.gen_range(min as i32..=max.max(min) as i32) as f32
+            } else {
+                rng.gen_range(min..max.max(min + f32::EPSILON))
+            }
+        });
+    dialogue_runner
+        .commands_mut()
+        .add_command("stop_chat", stop_chat_command)
+        .add_command("target_npc_mentioned", target_npc_mentioned_command)
+        .add_command("trigger_ending", trigger_ending_command)
+        .add_command("join_party", join_party_command)
+        .add_command("add_lead", add_lead_command)
+        .add_command("complete_lead", complete_lead_command)
+        .add_command("give_item", give_item_command)
+        .add_command("gift_item", gift_item_command)
+        .add_command("camera_focus", camera_focus_command)
+        .add_command("camera_pan", camera_pan_command)
+        .add_command("camera_reset", camera_reset_command);
+    dialogue_runner
+}
+
+#[allow(clippy::too_many_arguments)]
 fn spawn_dialogue_runner(
     mut commands: Commands,
     mut typewriter: ResMut<Typewriter>,
     project: Res<YarnProject>,
+    conversation: Res<Conversation>,
+    explored_percent: Res<ExploredPercent>,
+    inventory: Res<Inventory>,
+    seeded_rng: Res<SeededRng>,
     mut q_npcs: Query<&mut Npc>,
     mut ev_spawn_dialogue_runner: EventReader<SpawnDialogueRunner>,
     mut ev_update_target_npcs: EventWriter<UpdateTargetNpcs>,
@@ -61,15 +131,24 @@ fn spawn_dialogue_runner(
         }
 
         typewriter.reset();
-        let mut dialogue_runner = project.create_dialogue_runner();
-        dialogue_runner
-            .commands_mut()
-            .add_command("stop_chat", stop_chat_command)
-            .add_command("target_npc_mentioned", target_npc_mentioned_command)
-            .add_command("trigger_ending", trigger_ending_command);
-
-        dialogue_runner.start_node(ev.dialogue.to_string());
-        commands.spawn((dialogue_runner, RunnerFlags::new(ev.dialogue)));
+        let mut dialogue_runner =
+            create_dialogue_runner(&project, &explored_percent, &inventory, &seeded_rng);
+        let node = match &ev.node {
+            Some(node) if dialogue_runner.node_exists(node) => node.clone(),
+            Some(node) => {
+                error!(
+                    "there is no node {}, starting {} instead",
+                    node, ev.dialogue
+                );
+                ev.dialogue.to_string()
+            }
+            None => conversation
+                .node
+                .clone()
+                .unwrap_or_else(|| ev.dialogue.to_string()),
+        };
+        dialogue_runner.start_node(node);
+        commands.spawn((dialogue_runner, RunnerFlags::new(Some(ev.dialogue))));
         ev_update_target_npcs.send(UpdateTargetNpcs);
     }
 }
@@ -77,7 +156,7 @@ fn spawn_dialogue_runner(
 fn activate_dialogue_runner(
     mut commands: Commands,
     mut typewriter: ResMut<Typewriter>,
-    mut q_runner_flags: Query<&mut RunnerFlags>,
+    mut q_runner_flags: Query<(Entity, &mut RunnerFlags)>,
     mut q_dialogue: Query<&mut Visibility, With<DialogueRoot>>,
     mut q_dialogue_content: Query<&mut Text, With<DialogueContent>>,
     mut ev_player_started_chat: EventReader<PlayerStartedChat>,
@@ -94,8 +173,13 @@ fn activate_dialogue_runner(
         *visibility = Visibility::Inherited;
 
         let mut cached = false;
-        for mut flags in &mut q_runner_flags {
-            if flags.dialogue == ev.dialogue {
+        for (entity, mut flags) in &mut q_runner_flags {
+            if flags.dialogue == Some(ev.dialogue) {
+                // Starting from a specific node throws away the cached runner.
+                if ev.node.is_some() {
+                    commands.entity(entity).despawn_recursive();
+                    continue;
+                }
                 cached = true;
                 flags.active = true;
                 if let Some(option_selection) = &flags.options {
@@ -114,19 +198,54 @@ fn activate_dialogue_runner(
             }
             ev_spawn_dialogue_runner.send(SpawnDialogueRunner {
                 dialogue: ev.dialogue,
+                node: ev.node.clone(),
             });
         }
     }
 }
 
-fn update_target_npc(flags: &RunnerFlags, runner: &mut DialogueRunner, dialogue: NpcDialogue) {
+fn start_inspection(
+    mut commands: Commands,
+    mut typewriter: ResMut<Typewriter>,
+    project: Res<YarnProject>,
+    explored_percent: Res<ExploredPercent>,
+    inventory: Res<Inventory>,
+    seeded_rng: Res<SeededRng>,
+    mut q_dialogue: Query<&mut Visibility, With<DialogueRoot>>,
+    mut q_dialogue_content: Query<&mut Text, With<DialogueContent>>,
+    mut ev_player_started_inspection: EventReader<PlayerStartedInspection>,
+) {
+    let mut visibility = match q_dialogue.get_single_mut() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    for ev in ev_player_started_inspection.read() {
+        *visibility = Visibility::Inherited;
+        if let Ok(mut dialogue_content) = q_dialogue_content.get_single_mut() {
+            *dialogue_content = Text::default();
+        }
+
+        typewriter.reset();
+        let mut dialogue_runner =
+            create_dialogue_runner(&project, &explored_percent, &inventory, &seeded_rng);
+        dialogue_runner.start_node(ev.node);
+        commands.spawn((dialogue_runner, RunnerFlags::new(None)));
+    }
+}
+
+fn update_target_npc(
+    runner_dialogue: NpcDialogue,
+    runner: &mut DialogueRunner,
+    dialogue: NpcDialogue,
+) {
     let variable_storage = runner.variable_storage_mut();
     let target_npc: String = match variable_storage.get("$target_npc") {
         Ok(r) => r.to_string(),
         Err(err) => {
             error!(
                 "Dialogue without the variable $target_npc! In dialogue {}, {}",
-                flags.dialogue, err
+                runner_dialogue, err
             );
             String::new()
         }
@@ -134,24 +253,24 @@ fn update_target_npc(flags: &RunnerFlags, runner: &mut DialogueRunner, dialogue:
     if target_npc == dialogue.to_string() {
         if let Err(err) = variable_storage.set("$talked_with_target_npc".to_string(), (true).into())
         {
-            error!("{}, {}", flags.dialogue, err);
+            error!("{}, {}", runner_dialogue, err);
         }
     }
 }
 
 fn update_mentioned_by(
-    flags: &RunnerFlags,
+    runner_dialogue: NpcDialogue,
     runner: &mut DialogueRunner,
     mentioned_by: &NpcDialogue,
 ) {
     let variable_storage = runner.variable_storage_mut();
     let variable = format!("$mentioned_by_{mentioned_by}");
     if !variable_storage.contains(&variable) {
-        error!("Npc {}, does not contain var {}", flags.dialogue, variable);
+        error!("Npc {}, does not contain var {}", runner_dialogue, variable);
         return;
     };
     if let Err(err) = variable_storage.set(variable, (true).into()) {
-        error!("{}, {}", flags.dialogue, err);
+        error!("{}, {}", runner_dialogue, err);
     }
 }
 
@@ -178,20 +297,43 @@ fn update_target_npcs(
     *started = false;
     *frames = 0;
     for (mut runner, flags) in &mut q_dialogue_runners {
+        let Some(runner_dialogue) = flags.dialogue else {
+            continue;
+        };
+
         for npc in &q_npcs {
             if npc.was_talked_to {
-                update_target_npc(flags, &mut runner, npc.dialogue);
+                update_target_npc(runner_dialogue, &mut runner, npc.dialogue);
             }
 
-            if flags.dialogue == npc.dialogue {
+            if runner_dialogue == npc.dialogue {
                 for mentioned_by in &npc.was_mentioned_by {
-                    update_mentioned_by(flags, &mut runner, mentioned_by);
+                    update_mentioned_by(runner_dialogue, &mut runner, mentioned_by);
                 }
             }
         }
     }
 }
 
+/// Group nodes jump to the NPC's own node, which resets the target NPC variables.
+fn update_target_npcs_on_node_start(
+    q_runner_flags: Query<&RunnerFlags>,
+    mut ev_node_start: EventReader<NodeStartEvent>,
+    mut ev_update_target_npcs: EventWriter<UpdateTargetNpcs>,
+) {
+    for ev in ev_node_start.read() {
+        let Ok(flags) = q_runner_flags.get(ev.source) else {
+            continue;
+        };
+        if flags
+            .dialogue
+            .is_some_and(|dialogue| dialogue.to_string() == ev.node_name)
+        {
+            ev_update_target_npcs.send(UpdateTargetNpcs);
+        }
+    }
+}
+
 fn hide_dialogue(mut q_dialogue: Query<&mut Visibility, With<DialogueRoot>>) {
     let mut visibility = match q_dialogue.get_single_mut() {
         Ok(r) => r,
@@ -217,6 +359,19 @@ fn deactivate_dialogue_runner(mut q_runner_flags: Query<&mut RunnerFlags>) {
     }
 }
 
+/// Inspections are short and always start from the top,
+/// so there is no point in keeping them around.
+fn despawn_inspection_runners(
+    mut commands: Commands,
+    q_runner_flags: Query<(Entity, &RunnerFlags)>,
+) {
+    for (entity, flags) in &q_runner_flags {
+        if flags.dialogue.is_none() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
 fn monitor_active_runners(q_runner_flags: Query<&RunnerFlags>) {
     let mut active = 0;
 
@@ -239,16 +394,23 @@ impl Plugin for DialogueRunnerPlugin {
             .add_event::<UpdateTargetNpcs>()
             .add_systems(
                 Update,
-                (activate_dialogue_runner, spawn_dialogue_runner)
+                (
+                    activate_dialogue_runner,
+                    spawn_dialogue_runner,
+                    start_inspection,
+                )
                     .chain()
+                    .after(start_conversation)
                     .run_if(in_state(GameState::Gaming).and_then(resource_exists::<YarnProject>)),
             )
             .add_systems(
                 Update,
                 (
                     despawn_dialogue_runner,
-                    deactivate_dialogue_runner.run_if(on_event::<PlayerStoppedChat>()),
+                    (deactivate_dialogue_runner, despawn_inspection_runners)
+                        .run_if(on_event::<PlayerStoppedChat>()),
                     monitor_active_runners,
+                    update_target_npcs_on_node_start,
                     update_target_npcs,
                 ),
             )
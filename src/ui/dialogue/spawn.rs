@@ -2,7 +2,7 @@ use bevy::prelude::*;
 use bevy_tweening::{lens::*, *};
 use bevy_yarnspinner::prelude::*;
 
-use crate::{GameAssets, GameState};
+use crate::{npc::NpcDialogue, GameAssets, GameState};
 
 use super::option_selection::OptionSelection;
 
@@ -17,6 +17,11 @@ pub struct DialogueNameNode;
 pub struct DialogueCharacterIcon;
 #[derive(Component)]
 pub struct DialogueContinueNode;
+/// Holds a portrait of everyone in a group conversation.
+#[derive(Component)]
+pub struct DialoguePortraits;
+#[derive(Component)]
+pub struct DialoguePortrait(pub Option<NpcDialogue>);
 
 #[derive(Component)]
 pub struct OptionsNode;
@@ -33,6 +38,8 @@ const OPTIONS_WIDTH: f32 = 500.0;
 const TEXT_BORDER_HORIZONTAL: f32 = 40.0;
 const TEXT_BORDER_VERTICAL: f32 = 20.0;
 const OPTIONS_TEXT_BORDER: f32 = 10.0;
+const PORTRAIT_SIZE: f32 = 56.0;
+const PORTRAIT_GAP: f32 = 4.0;
 
 const CONTINUE_BOTTOM: f32 = -5.0;
 const CONTINUE_BOB_DURATION: f32 = 1.0;
@@ -196,6 +203,27 @@ fn spawn_dialogue_bottom(commands: &mut Commands, assets: &Res<GameAssets>) -> E
         .push_children(&[icon_node, name_node])
         .id();
 
+    let portraits_node = commands
+        .spawn((
+            DialoguePortraits,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(-550.0),
+                    bottom: Val::Px(190.0),
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(PORTRAIT_GAP),
+                    padding: UiRect::all(Val::Px(PORTRAIT_GAP)),
+                    ..default()
+                },
+                z_index: ZIndex::Local(1),
+                background_color: Color::BLACK.with_alpha(BACKGROUND_ALPHA).into(),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+        ))
+        .id();
+
     commands
         .spawn((NodeBundle {
             style: Style {
@@ -206,7 +234,7 @@ fn spawn_dialogue_bottom(commands: &mut Commands, assets: &Res<GameAssets>) -> E
             },
             ..default()
         },))
-        .push_children(&[character_icon_node, continue_node])
+        .push_children(&[character_icon_node, portraits_node, continue_node])
         .id()
 }
 
@@ -411,6 +439,31 @@ pub fn spawn_options(
     }
 }
 
+/// A small icon of one of the characters in a group conversation.
+/// `None` is the player.
+pub fn spawn_portrait(
+    commands: &mut Commands,
+    texture: Handle<Image>,
+    speaker: Option<NpcDialogue>,
+    entity: Entity,
+) {
+    let portrait = commands
+        .spawn((
+            DialoguePortrait(speaker),
+            ImageBundle {
+                image: UiImage::new(texture),
+                style: Style {
+                    width: Val::Px(PORTRAIT_SIZE),
+                    height: Val::Px(PORTRAIT_SIZE),
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .id();
+    commands.entity(entity).add_child(portrait);
+}
+
 pub struct DialogueSpawnPlugin;
 
 impl Plugin for DialogueSpawnPlugin {
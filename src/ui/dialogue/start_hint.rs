@@ -3,19 +3,36 @@ use bevy_trickfilm::prelude::*;
 use bevy_tweening::{lens::*, *};
 
 use crate::{
-    npc::Npc,
-    player::{chat::PlayerStartedChat, Player, PlayerState, NPC_PROXIMITY_DISTANCE},
-    world::camera::YSort,
+    npc::companion::Companion,
+    player::{
+        chat::PlayerStartedChat,
+        interaction::{InteractTarget, Interactable, NearestTarget},
+    },
+    world::{camera::YSort, photo_mode::HideInPhotoMode},
     GameAssets, GameState,
 };
 
 const SIZE: f32 = 0.65;
 const SCALE_DURATION: f32 = 0.5;
+const HINT_OFFSET: Vec3 = Vec3::new(0.0, 40.0, 0.0);
+const PROMPT_OFFSET: Vec3 = Vec3::new(0.0, 58.0, 0.0);
+const PROMPT_FONT_SIZE: f32 = 40.0;
+const PROMPT_SCALE: f32 = 0.2;
 
+/// Shown above the closest thing the player can talk to or interact with.
 #[derive(Component)]
-struct StartHint;
+struct StartHint {
+    target: InteractTarget,
+    prompt: &'static str,
+}
 
-fn spawn_hint(commands: &mut Commands, assets: &Res<GameAssets>, pos: Vec3) {
+fn spawn_hint(
+    commands: &mut Commands,
+    assets: &Res<GameAssets>,
+    target: InteractTarget,
+    prompt: &'static str,
+    pos: Vec3,
+) {
     let mut animator = AnimationPlayer2D::default();
     animator
         .play(assets.dialogue_start_hint_animations[0].clone())
@@ -36,8 +53,7 @@ fn spawn_hint(commands: &mut Commands, assets: &Res<GameAssets>, pos: Vec3) {
             Animator::new(tween),
             SpriteBundle {
                 texture: assets.dialogue_start_hint_texture.clone(),
-                transform: Transform::from_translation(Vec3::new(0.0, 40.0, 0.0))
-                    .with_scale(Vec3::ZERO),
+                transform: Transform::from_translation(HINT_OFFSET).with_scale(Vec3::ZERO),
                 ..default()
             },
             TextureAtlas {
@@ -47,89 +63,96 @@ fn spawn_hint(commands: &mut Commands, assets: &Res<GameAssets>, pos: Vec3) {
         ))
         .id();
 
+    let text = commands
+        .spawn(Text2dBundle {
+            text: Text::from_section(
+                prompt,
+                TextStyle {
+                    font: assets.silver_font.clone(),
+                    font_size: PROMPT_FONT_SIZE,
+                    color: Color::WHITE,
+                },
+            ),
+            transform: Transform::from_translation(PROMPT_OFFSET)
+                .with_scale(Vec3::splat(PROMPT_SCALE)),
+            ..default()
+        })
+        .id();
+
     commands
         .spawn((
-            StartHint,
+            StartHint { target, prompt },
+            HideInPhotoMode,
             YSort(100.0),
             SpatialBundle {
                 transform: Transform::from_translation(pos),
                 ..default()
             },
         ))
-        .push_children(&[sprite]);
+        .push_children(&[sprite, text]);
+}
+
+/// The prompt of the target, `None` if there shouldn't be a hint.
+fn target_prompt(
+    target: InteractTarget,
+    q_companions: &Query<(), With<Companion>>,
+    q_interactables: &Query<&Interactable>,
+) -> Option<&'static str> {
+    match target {
+        // Companions are always close, the hint would never go away.
+        InteractTarget::Npc(entity) if q_companions.contains(entity) => None,
+        InteractTarget::Npc(_) => Some("Talk"),
+        InteractTarget::Object(entity) => q_interactables
+            .get(entity)
+            .ok()
+            .map(|interactable| interactable.current_prompt()),
+    }
 }
 
 fn spawn_hints(
     mut commands: Commands,
     assets: Res<GameAssets>,
-    q_player: Query<(&Transform, &Player)>,
-    q_npcs: Query<&Transform, (With<Npc>, Without<Player>)>,
+    nearest_target: Res<NearestTarget>,
+    q_companions: Query<(), With<Companion>>,
+    q_interactables: Query<&Interactable>,
     q_start_hints: Query<&StartHint>,
 ) {
     if !q_start_hints.is_empty() {
         return;
     }
 
-    let (player_transform, player) = match q_player.get_single() {
-        Ok(r) => r,
-        Err(_) => return,
-    };
-
-    if player.state == PlayerState::Talking {
+    let Some(target) = nearest_target.target else {
         return;
-    }
-
-    for npc_transform in &q_npcs {
-        if player_transform
-            .translation
-            .xy()
-            .distance_squared(npc_transform.translation.xy())
-            <= NPC_PROXIMITY_DISTANCE.powi(2)
-        {
-            spawn_hint(&mut commands, &assets, npc_transform.translation);
-        }
+    };
+    if let Some(prompt) = target_prompt(target, &q_companions, &q_interactables) {
+        spawn_hint(
+            &mut commands,
+            &assets,
+            target,
+            prompt,
+            nearest_target.pos.extend(0.0),
+        );
     }
 }
 
+/// Despawn the hint when the target or its prompt changes,
+/// a new one gets spawned in the next frame.
 fn despawn_hint(
     mut commands: Commands,
-    q_player: Query<(&Transform, &Player)>,
-    q_start_hints: Query<(Entity, &Transform), (With<StartHint>, Without<Player>)>,
+    nearest_target: Res<NearestTarget>,
+    q_companions: Query<(), With<Companion>>,
+    q_interactables: Query<&Interactable>,
+    q_start_hints: Query<(Entity, &StartHint)>,
     mut ev_player_started_chat: EventReader<PlayerStartedChat>,
 ) {
-    if !ev_player_started_chat.is_empty() {
-        ev_player_started_chat.clear();
-        for (entity, _) in &q_start_hints {
-            commands.entity(entity).despawn_recursive();
-        }
-        return;
-    }
-
-    if q_start_hints.is_empty() {
-        return;
-    }
-
-    let (player_transform, player) = match q_player.get_single() {
-        Ok(r) => r,
-        Err(_) => return,
-    };
-
-    if player.state == PlayerState::Talking {
-        for (entity, _) in &q_start_hints {
-            commands.entity(entity).despawn_recursive();
-        }
-        return;
-    }
+    let started_chat = !ev_player_started_chat.is_empty();
+    ev_player_started_chat.clear();
 
-    for (entity, transform) in &q_start_hints {
-        if player_transform
-            .translation
-            .xy()
-            .distance_squared(transform.translation.xy())
-            > NPC_PROXIMITY_DISTANCE.powi(2)
-        {
+    for (entity, hint) in &q_start_hints {
+        let outdated = nearest_target.target != Some(hint.target)
+            || target_prompt(hint.target, &q_companions, &q_interactables) != Some(hint.prompt);
+        if started_chat || outdated {
             commands.entity(entity).despawn_recursive();
-            break;
         }
     }
 }
@@ -2,14 +2,37 @@ use std::{
     collections::HashSet,
     fs::{self, DirEntry},
     io::Error,
+    path::Path,
     str::FromStr,
 };
 
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
 use strsim::levenshtein;
+use strum::IntoEnumIterator;
 
-use crate::npc::NpcDialogue;
+use crate::npc::{
+    barks::{GREETING_TAG, MENTIONED_TAG, TALKED_TAG},
+    companion::{NPC_TAG, WATER_TAG},
+    conversation::group_node,
+    Npc, NpcDialogue,
+};
+use crate::player::inventory::item_data;
+use crate::ui::journal::{Journal, JournalUpdated};
+use crate::world::map::{
+    flora::{INSPECT_TREE_NODE, PICK_UP_ROCK_NODE, ROCK_ITEM},
+    generation::poi::PointOfInterestKind,
+};
+
+use super::{
+    command::add_lead_command,
+    expression::{portrait_variant_path, Expression},
+};
 
 const PATH_TO_DIR: &str = "assets/dialogue";
+const PATH_TO_POI_FILE: &str = "assets/dialogue/world/points-of-interest.yarn";
+const PATH_TO_BARKS_FILE: &str = "assets/dialogue/world/barks.yarn";
+const PATH_TO_FLORA_FILE: &str = "assets/dialogue/world/flora.yarn";
+const PATH_TO_COMPANIONS_FILE: &str = "assets/dialogue/world/companions.yarn";
 
 const MAX_SIMILARITY_DISTANCE: usize = 4;
 
@@ -41,12 +64,20 @@ fn try_read_yarn_contents(entry: Result<DirEntry, Error>) -> Option<(String, Str
     None
 }
 
-/// Loop over all yarn files in `PATH_TO_DIR` and apply the predicate on each line.
-fn validate_lines<F>(mut predicate: F)
+fn validate_lines_in_dir<F>(dir: &Path, predicate: &mut F)
 where
     F: FnMut(&str, &str),
 {
-    for entry in fs::read_dir(PATH_TO_DIR).expect("Can't read entries in current dir") {
+    for entry in fs::read_dir(dir).expect("Can't read entries in current dir") {
+        let path = entry
+            .as_ref()
+            .expect("Can't get entry in current dir")
+            .path();
+        if path.is_dir() {
+            validate_lines_in_dir(&path, predicate);
+            continue;
+        }
+
         let (contents, npc_file_name) = match try_read_yarn_contents(entry) {
             Some(r) => r,
             None => continue,
@@ -58,10 +89,31 @@ where
     }
 }
 
+/// Loop over all yarn files in `PATH_TO_DIR` and its subdirectories (e.g. `world`)
+/// and apply the predicate on each line.
+fn validate_lines<F>(mut predicate: F)
+where
+    F: FnMut(&str, &str),
+{
+    validate_lines_in_dir(Path::new(PATH_TO_DIR), &mut predicate);
+}
+
 /// This essentially tests for typos in the commands of the yarn files.
 #[test]
 fn validate_custom_commands() {
-    let custom_commands = ["stop_chat", "target_npc_mentioned", "trigger_ending"];
+    let custom_commands = [
+        "stop_chat",
+        "target_npc_mentioned",
+        "trigger_ending",
+        "join_party",
+        "add_lead",
+        "complete_lead",
+        "give_item",
+        "gift_item",
+        "camera_focus",
+        "camera_pan",
+        "camera_reset",
+    ];
 
     validate_lines(|line, _| {
         if !line.starts_with("<<") {
@@ -118,6 +170,132 @@ fn validate_trigger_ending() {
     })
 }
 
+#[test]
+fn validate_join_party() {
+    validate_lines(|line, _| {
+        if line.starts_with("<<join_party") {
+            assert!(line == "<<join_party {$name}>>", "{}", line);
+        }
+    })
+}
+
+#[test]
+fn validate_add_lead() {
+    validate_lines(|line, _| {
+        if line.starts_with("<<add_lead") {
+            assert!(line == "<<add_lead {$name} {$target_npc}>>", "{}", line);
+        }
+    })
+}
+
+#[test]
+fn validate_complete_lead() {
+    validate_lines(|line, _| {
+        if line.starts_with("<<complete_lead") {
+            assert!(line == "<<complete_lead {$name}>>", "{}", line);
+        }
+    })
+}
+
+/// Every NPC that mentions their target NPC should also add a lead to the journal,
+/// and every NPC should complete the leads that point to them.
+#[test]
+fn validate_journal_leads() {
+    for entry in fs::read_dir(PATH_TO_DIR).expect("Can't read entries in current dir") {
+        let (contents, file_name) = match try_read_yarn_contents(entry) {
+            Some(r) => r,
+            None => continue,
+        };
+
+        let lines: Vec<&str> = contents.lines().map(str::trim).collect();
+        for (i, line) in lines.iter().enumerate() {
+            if line.starts_with("<<target_npc_mentioned") {
+                assert!(
+                    lines
+                        .get(i + 1)
+                        .is_some_and(|l| l.starts_with("<<add_lead")),
+                    "<<target_npc_mentioned>> in {file_name} isn't followed by <<add_lead>>"
+                );
+            }
+        }
+        assert!(
+            lines.iter().any(|l| l.starts_with("<<complete_lead")),
+            "{file_name} never completes the leads pointing to it"
+        );
+    }
+}
+
+/// The camera can only focus on characters that exist, e.g. `<<camera_focus {$name} 1.5>>`.
+#[test]
+fn validate_camera_focus() {
+    validate_lines(|line, npc_file_name| {
+        let Some(args) = line.strip_prefix("<<camera_focus ") else {
+            return;
+        };
+        let args: Vec<&str> = args.trim_end_matches(">>").split_whitespace().collect();
+        assert!(
+            (2..=3).contains(&args.len()),
+            "Invalid <<camera_focus>> in {npc_file_name}, {line}"
+        );
+
+        let name = args[0].trim_start_matches('_');
+        assert!(
+            name == "{$name}" || name == "Pai" || NpcDialogue::from_str(name).is_ok(),
+            "Can't focus the camera on '{name}' in {npc_file_name}"
+        );
+        for number in &args[1..] {
+            assert!(
+                number.parse::<f32>().is_ok(),
+                "Invalid <<camera_focus>> in {npc_file_name}, {line}"
+            );
+        }
+    });
+}
+
+/// Items given, gifted or checked in the yarn files must exist,
+/// and gifting an item sets `$gifted_<item>`, which has to be declared in the same file.
+#[test]
+fn validate_items() {
+    for entry in fs::read_dir(PATH_TO_DIR).expect("Can't read entries in current dir") {
+        let (contents, file_name) = match try_read_yarn_contents(entry) {
+            Some(r) => r,
+            None => continue,
+        };
+
+        for line in contents.lines().map(str::trim) {
+            if let Some(item) = line.strip_prefix("<<give_item ") {
+                let item = item.trim_end_matches(">>");
+                assert!(
+                    item_data(item).is_some(),
+                    "Unknown item '{item}' in {file_name}"
+                );
+            }
+
+            if let Some(item) = line.strip_prefix("<<gift_item {$name} ") {
+                let item = item.trim_end_matches(">>");
+                assert!(
+                    item_data(item).is_some(),
+                    "Unknown item '{item}' in {file_name}"
+                );
+                assert!(
+                    contents.contains(&format!("<<set $gifted_{item} = false>>")),
+                    "{file_name} gifts '{item}' but never sets $gifted_{item}"
+                );
+            } else if line.starts_with("<<gift_item") {
+                panic!("Invalid <<gift_item>> in {file_name}, {line}");
+            }
+
+            for part in line.split("has_item(\"").skip(1) {
+                let item = part.split('"').next().unwrap_or_default();
+                assert!(
+                    item_data(item).is_some(),
+                    "Unknown item '{item}' in {file_name}"
+                );
+            }
+        }
+    }
+}
+
 #[test]
 fn validate_npc_names_existence() {
     validate_lines(|line, _| {
@@ -281,3 +459,249 @@ fn assert_no_narrator_lines() {
         }
     }
 }
+
+/// Make sure every point of interest that can be inspected has a matching node.
+#[test]
+fn validate_poi_inspect_nodes() {
+    let contents =
+        fs::read_to_string(PATH_TO_POI_FILE).expect("Should have been able to read the file");
+    let titles: HashSet<&str> = contents
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("title: "))
+        .collect();
+
+    for kind in PointOfInterestKind::iter() {
+        if let Some(node) = kind.inspect_node() {
+            assert!(
+                titles.contains(node),
+                "Point of interest {kind} references node '{node}' which doesn't exist in {PATH_TO_POI_FILE}"
+            );
+        }
+    }
+}
+
+/// Make sure the flavor text of the flora has matching nodes.
+#[test]
+fn validate_flora_nodes() {
+    let contents =
+        fs::read_to_string(PATH_TO_FLORA_FILE).expect("Should have been able to read the file");
+    let titles: HashSet<&str> = contents
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("title: "))
+        .collect();
+
+    for node in [PICK_UP_ROCK_NODE, INSPECT_TREE_NODE] {
+        assert!(
+            titles.contains(node),
+            "Flora references node '{node}' which doesn't exist in {PATH_TO_FLORA_FILE}"
+        );
+    }
+    assert!(
+        item_data(ROCK_ITEM).is_some(),
+        "Rocks give the item '{ROCK_ITEM}' which doesn't exist"
+    );
+}
+
+/// Bark nodes must belong to an NPC and only use tags we know about.
+#[test]
+fn validate_bark_nodes() {
+    let contents =
+        fs::read_to_string(PATH_TO_BARKS_FILE).expect("Should have been able to read the file");
+
+    for line in contents.lines().map(str::trim) {
+        if let Some(title) = line.strip_prefix("title: ") {
+            let npc = title
+                .strip_suffix("Barks")
+                .unwrap_or_else(|| panic!("Bark node '{title}' must end with 'Barks'"));
+            assert!(
+                NpcDialogue::from_str(npc).is_ok(),
+                "Bark node '{title}' doesn't belong to any NPC"
+            );
+            continue;
+        }
+
+        for tag in line.split_whitespace().filter_map(|w| w.strip_prefix('#')) {
+            assert!(
+                [GREETING_TAG, MENTIONED_TAG, TALKED_TAG].contains(&tag),
+                "Unknown bark tag '#{tag}' in line: {line}"
+            );
+        }
+    }
+}
+
+/// Companion nodes must belong to an NPC and every line needs to say when it's used.
+/// Each companion should have something to say about the water and other NPCs.
+#[test]
+fn validate_companion_nodes() {
+    let contents = fs::read_to_string(PATH_TO_COMPANIONS_FILE)
+        .expect("Should have been able to read the file");
+
+    for node in contents.split("===").filter(|node| !node.trim().is_empty()) {
+        let mut lines = node.lines().map(str::trim).filter(|line| !line.is_empty());
+        let title = lines
+            .next()
+            .and_then(|line| line.strip_prefix("title: "))
+            .expect("Companion node must start with its title");
+        let npc = title
+            .strip_suffix("Companion")
+            .unwrap_or_else(|| panic!("Companion node '{title}' must end with 'Companion'"));
+        assert!(
+            NpcDialogue::from_str(npc).is_ok(),
+            "Companion node '{title}' doesn't belong to any NPC"
+        );
+
+        let mut tags = Vec::new();
+        for line in lines.filter(|line| *line != "---") {
+            let line_tags: Vec<&str> = line
+                .split_whitespace()
+                .filter_map(|w| w.strip_prefix('#'))
+                .collect();
+            assert!(
+                line_tags.len() == 1,
+                "Companion line needs exactly one tag, in line: {line}"
+            );
+            let tag = line_tags[0];
+            assert!(
+                [WATER_TAG, NPC_TAG].contains(&tag) || NpcDialogue::from_str(tag).is_ok(),
+                "Unknown companion tag '#{tag}' in line: {line}"
+            );
+            tags.push(tag);
+        }
+        for tag in [WATER_TAG, NPC_TAG] {
+            assert!(tags.contains(&tag), "{title} has no line tagged '#{tag}'");
+        }
+    }
+}
+
+/// Group nodes must live in the file of the NPC the player talks to
+/// and continue with that NPC's own node.
+#[test]
+fn validate_group_nodes() {
+    for entry in fs::read_dir(PATH_TO_DIR).expect("Can't read entries in current dir") {
+        let (contents, npc_file_name) = match try_read_yarn_contents(entry) {
+            Some(r) => r,
+            None => continue,
+        };
+
+        for node in contents.split("===") {
+            let Some(title) = node
+                .lines()
+                .map(str::trim)
+                .find_map(|line| line.strip_prefix("title: "))
+            else {
+                continue;
+            };
+            for (dialogue, other) in NpcDialogue::iter()
+                .flat_map(|dialogue| NpcDialogue::iter().map(move |other| (dialogue, other)))
+            {
+                if dialogue == other || title != group_node(dialogue, other) {
+                    continue;
+                }
+                assert!(
+                    dialogue.to_string().to_lowercase() == npc_file_name.replace('-', ""),
+                    "Group node '{title}' must be in the yarn file of {dialogue}"
+                );
+                assert!(
+                    node.lines()
+                        .map(str::trim)
+                        .any(|line| line == format!("<<jump {dialogue}>>")),
+                    "Group node '{title}' must jump to '{dialogue}'"
+                );
+            }
+        }
+    }
+}
+
+/// Tags on dialogue lines set the expression of the speaker, so they must be known.
+/// Barks and companion lines use their tags for when they get said instead.
+#[test]
+fn validate_expression_tags() {
+    let tagged_files = [PATH_TO_BARKS_FILE, PATH_TO_COMPANIONS_FILE].map(|path| {
+        Path::new(path)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .expect("Should be a yarn file")
+    });
+
+    validate_lines(|line, file| {
+        if tagged_files.contains(&file) {
+            return;
+        }
+        for tag in line.split_whitespace().filter_map(|w| w.strip_prefix('#')) {
+            assert!(
+                Expression::from_str(tag).is_ok(),
+                "Unknown expression tag '#{tag}' in {file}, line: {line}. Use one of {:?}",
+                Expression::iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+            );
+        }
+    });
+}
+
+/// Lines tagged with an expression should change the portrait of whoever says them.
+#[test]
+fn validate_expression_portraits() {
+    for entry in fs::read_dir(PATH_TO_DIR).expect("Can't read entries in current dir") {
+        let (contents, npc_file_name) = match try_read_yarn_contents(entry) {
+            Some(r) => r,
+            None => continue,
+        };
+
+        for line in contents.lines().map(str::trim) {
+            let Some(expression) = line
+                .split_whitespace()
+                .filter_map(|w| w.strip_prefix('#'))
+                .find_map(|tag| Expression::from_str(tag).ok())
+            else {
+                continue;
+            };
+            let speaker = if line.starts_with("You:") {
+                "pai".to_string()
+            } else if line.starts_with("{$name}:") {
+                npc_file_name.replace('-', "")
+            } else {
+                line.strip_prefix("{$")
+                    .and_then(|line| line.split_once('}'))
+                    .map(|(variable, _)| variable.to_string())
+                    .unwrap_or_else(|| panic!("Unknown speaker in line: {line}"))
+            };
+
+            let path = portrait_variant_path(&speaker, expression).unwrap_or_else(|| {
+                panic!("No {expression} portrait for {speaker}, in line: {line}")
+            });
+            assert!(
+                Path::new("assets").join(&path).is_file(),
+                "Portrait variant '{path}' doesn't exist"
+            );
+        }
+    }
+}
+
+/// Leads to NPCs the player already talked to are done as soon as they are added.
+#[test]
+fn add_lead_to_talked_to_npc() {
+    let mut world = World::new();
+    world.init_resource::<Journal>();
+    world.init_resource::<Events<JournalUpdated>>();
+    world.spawn(Npc {
+        dialogue: NpcDialogue::Eleonore,
+        was_talked_to: true,
+        was_mentioned_by: Vec::new(),
+    });
+
+    world.run_system_once_with(("Jotem", "_Eleonore"), add_lead_command);
+
+    let texts: Vec<String> = world
+        .resource_mut::<Events<JournalUpdated>>()
+        .drain()
+        .map(|ev| ev.0)
+        .collect();
+    assert_eq!(
+        texts,
+        [
+            "Jotem suggested you talk to Eleonore.",
+            "Done! Jotem suggested you talk to Eleonore.",
+        ]
+    );
+}
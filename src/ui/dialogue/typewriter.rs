@@ -11,6 +11,7 @@ use crate::player::chat::PlayerStoppedChat;
 use crate::ui::main_menu::{ButtonAction, MainMenuButtonPressed};
 use crate::{GameAssets, GameState};
 
+use super::expression::Expression;
 use super::option_selection::OptionSelection;
 use super::spawn::{create_dialogue_text, DialogueContent};
 use super::DialogueViewSystemSet;
@@ -30,6 +31,7 @@ pub struct WriteDialogueText;
 #[derive(Resource)]
 pub struct Typewriter {
     pub character_name: Option<String>,
+    pub expression: Expression,
     pub current_text: String,
     pub graphemes_left: Vec<String>,
     pub last_before_options: bool,
@@ -44,6 +46,7 @@ impl Default for Typewriter {
     fn default() -> Self {
         Self {
             character_name: default(),
+            expression: default(),
             current_text: default(),
             graphemes_left: default(),
             last_before_options: default(),
@@ -60,6 +63,7 @@ impl Typewriter {
     pub fn set_completed_line(&mut self, line: &LocalizedLine) {
         *self = Self {
             character_name: line.character_name().map(|s| s.to_string()),
+            expression: Expression::from_metadata(&line.metadata),
             current_text: line.text_without_character_name(),
             last_finished: true,
             last_before_options: line.is_last_line_before_options(),
@@ -72,6 +76,7 @@ impl Typewriter {
     pub fn set_line(&mut self, line: &LocalizedLine) {
         *self = Self {
             character_name: line.character_name().map(|s| s.to_string()),
+            expression: Expression::from_metadata(&line.metadata),
             current_text: String::new(),
             graphemes_left: line
                 .text_without_character_name()
@@ -3,17 +3,23 @@ use std::str::FromStr;
 use bevy::prelude::*;
 use bevy_yarnspinner::{events::*, prelude::*};
 
-use crate::npc::NpcDialogue;
+use crate::npc::{conversation::Conversation, NpcDialogue};
 use crate::player::chat::PlayerStoppedChat;
 use crate::player::input::PlayerInput;
 use crate::GameAssets;
 
+use super::expression::{Expression, PortraitVariants};
 use super::option_selection::OptionSelection;
 use super::runner::RunnerFlags;
-use super::spawn::{DialogueCharacterIcon, DialogueContinueNode, DialogueNameNode};
+use super::spawn::{
+    spawn_portrait, DialogueCharacterIcon, DialogueContinueNode, DialogueNameNode,
+    DialoguePortrait, DialoguePortraits,
+};
 use super::typewriter::{Typewriter, TypewriterFinished, WriteDialogueText};
 use super::DialogueViewSystemSet;
 
+const INACTIVE_PORTRAIT_ALPHA: f32 = 0.35;
+
 fn convert_name(name: &str) -> String {
     if name.starts_with('_') {
         return "???".to_string();
@@ -24,30 +30,42 @@ fn convert_name(name: &str) -> String {
 /// Return an Option so that you only set the texture when there is a proper NPC.
 /// If there is a frame delay (due to events or similar), then we will simply
 /// display the previous NPC for couple of frames. That's okay.
-fn character_icon(assets: &Res<GameAssets>, name: &str) -> Option<Handle<Image>> {
+/// Falls back to the neutral portrait if there is no art for the expression.
+fn character_icon(
+    assets: &Res<GameAssets>,
+    variants: &PortraitVariants,
+    name: &str,
+    expression: Expression,
+) -> Option<Handle<Image>> {
     let name = name.trim_start_matches('_');
     if name == "You" {
-        return Some(assets.pai_icon.clone());
+        return Some(
+            variants
+                .get("pai", expression)
+                .unwrap_or_else(|| assets.pai_icon.clone()),
+        );
     }
 
-    match NpcDialogue::from_str(name) {
-        Ok(r) => match r {
-            NpcDialogue::Eleonore => Some(assets.eleonore_icon.clone()),
-            NpcDialogue::Jotem => Some(assets.jotem_icon.clone()),
-            NpcDialogue::Isabelle => Some(assets.isabelle_icon.clone()),
-            NpcDialogue::Ionas => Some(assets.ionas_icon.clone()),
-            NpcDialogue::Antonius => Some(assets.antonius_icon.clone()),
-            NpcDialogue::IonasAndAntonius => {
-                error!("should never happen, you have used '$name' in antonius and ionas dialogue");
-                None
-            }
-        },
-        Err(_) => None,
+    let npc = NpcDialogue::from_str(name).ok()?;
+    if let Some(variant) = variants.get(&name.to_lowercase(), expression) {
+        return Some(variant);
+    }
+    match npc {
+        NpcDialogue::Eleonore => Some(assets.eleonore_icon.clone()),
+        NpcDialogue::Jotem => Some(assets.jotem_icon.clone()),
+        NpcDialogue::Isabelle => Some(assets.isabelle_icon.clone()),
+        NpcDialogue::Ionas => Some(assets.ionas_icon.clone()),
+        NpcDialogue::Antonius => Some(assets.antonius_icon.clone()),
+        NpcDialogue::IonasAndAntonius => {
+            error!("should never happen, you have used '$name' in antonius and ionas dialogue");
+            None
+        }
     }
 }
 
 fn present_line(
     assets: Res<GameAssets>,
+    variants: Res<PortraitVariants>,
     mut typewriter: ResMut<Typewriter>,
     mut q_character_icon: Query<&mut UiImage, With<DialogueCharacterIcon>>,
     mut q_name_text: Query<&mut Text, With<DialogueNameNode>>,
@@ -65,7 +83,8 @@ fn present_line(
 
     for event in ev_present_line.read() {
         let raw_name = event.line.character_name().unwrap_or_default();
-        if let Some(texture) = character_icon(&assets, raw_name) {
+        let expression = Expression::from_metadata(&event.line.metadata);
+        if let Some(texture) = character_icon(&assets, &variants, raw_name, expression) {
             character_icon_image.texture = texture;
         }
         name_text.sections[0].value = convert_name(raw_name);
@@ -132,6 +151,7 @@ fn continue_dialogue(
 
 fn update_displayed_character(
     assets: Res<GameAssets>,
+    variants: Res<PortraitVariants>,
     typewriter: Res<Typewriter>,
     mut q_character_icon: Query<&mut UiImage, With<DialogueCharacterIcon>>,
     mut q_name_text: Query<&mut Text, With<DialogueNameNode>>,
@@ -146,12 +166,71 @@ fn update_displayed_character(
     };
 
     let raw_name = &typewriter.character_name.clone().unwrap_or_default();
-    if let Some(texture) = character_icon(&assets, raw_name) {
+    if let Some(texture) = character_icon(&assets, &variants, raw_name, typewriter.expression) {
         character_icon_image.texture = texture;
     }
     name_text.sections[0].value = convert_name(raw_name);
 }
 
+/// Show a portrait of everyone in group conversations
+/// and highlight the one that is currently speaking.
+fn update_portraits(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    variants: Res<PortraitVariants>,
+    typewriter: Res<Typewriter>,
+    conversation: Res<Conversation>,
+    mut q_portraits_node: Query<(Entity, &mut Visibility), With<DialoguePortraits>>,
+    mut q_portraits: Query<(&DialoguePortrait, &mut UiImage)>,
+    mut shown: Local<Vec<NpcDialogue>>,
+) {
+    let (entity, mut visibility) = match q_portraits_node.get_single_mut() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    let speakers: Vec<NpcDialogue> = conversation
+        .participants
+        .iter()
+        .map(|p| p.speaker)
+        .collect();
+    if *shown != speakers {
+        commands.entity(entity).despawn_descendants();
+        // A single NPC already has the big character icon.
+        let group = speakers.len() > 1;
+        if group {
+            spawn_portrait(&mut commands, assets.pai_icon.clone(), None, entity);
+            for speaker in &speakers {
+                let name = speaker.to_string();
+                if let Some(texture) =
+                    character_icon(&assets, &variants, &name, Expression::Neutral)
+                {
+                    spawn_portrait(&mut commands, texture, Some(*speaker), entity);
+                }
+            }
+        }
+        *visibility = if group {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        *shown = speakers;
+        return;
+    }
+
+    let player_speaking = typewriter.character_name.as_deref() == Some("You");
+    for (portrait, mut image) in &mut q_portraits {
+        let active = match portrait.0 {
+            Some(speaker) => conversation.speaker == Some(speaker),
+            None => player_speaking,
+        };
+        let alpha = if active { 1.0 } else { INACTIVE_PORTRAIT_ALPHA };
+        if image.color.alpha() != alpha {
+            image.color.set_alpha(alpha);
+        }
+    }
+}
+
 fn show_continue_node(
     typewriter: Res<Typewriter>,
     mut q_visibility: Query<&mut Visibility, With<DialogueContinueNode>>,
@@ -190,6 +269,7 @@ impl Plugin for DialogueUpdatingPlugin {
                 present_options.run_if(on_event::<PresentOptionsEvent>()),
                 continue_dialogue,
                 update_displayed_character.run_if(on_event::<WriteDialogueText>()),
+                update_portraits,
                 show_continue_node.run_if(
                     on_event::<TypewriterFinished>().or_else(on_event::<WriteDialogueText>()),
                 ),
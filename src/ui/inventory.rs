@@ -0,0 +1,154 @@
+use bevy::prelude::*;
+
+use crate::{
+    player::{input::PlayerInput, inventory::Inventory},
+    GameAssets, GameState,
+};
+
+const BACKGROUND_ALPHA: f32 = 0.9;
+const INVENTORY_WIDTH: f32 = 360.0;
+const INVENTORY_MARGIN: f32 = 30.0;
+// Below the minimap, the journal takes up the right side.
+const INVENTORY_TOP: f32 = 300.0;
+const DESCRIPTION_COLOR: Color = Color::srgb(0.55, 0.55, 0.55);
+
+#[derive(Component)]
+struct InventoryRoot;
+#[derive(Component)]
+struct InventoryText;
+
+fn text_style_title(assets: &Res<GameAssets>) -> TextStyle {
+    TextStyle {
+        font: assets.silver_font.clone(),
+        font_size: 50.0,
+        color: Color::WHITE,
+    }
+}
+
+fn text_style_entry(assets: &Res<GameAssets>, color: Color) -> TextStyle {
+    TextStyle {
+        font: assets.silver_font.clone(),
+        font_size: 38.0,
+        color,
+    }
+}
+
+fn spawn_inventory(mut commands: Commands, assets: Res<GameAssets>) {
+    let text = commands
+        .spawn((
+            InventoryText,
+            Label,
+            TextBundle::from_section(String::new(), text_style_entry(&assets, Color::WHITE))
+                .with_style(Style {
+                    max_width: Val::Px(INVENTORY_WIDTH - 2.0 * INVENTORY_MARGIN),
+                    ..default()
+                }),
+        ))
+        .id();
+
+    commands
+        .spawn((
+            InventoryRoot,
+            Interaction::default(),
+            NodeBundle {
+                style: Style {
+                    width: Val::Px(INVENTORY_WIDTH),
+                    top: Val::Px(INVENTORY_TOP),
+                    left: Val::Px(INVENTORY_MARGIN),
+                    padding: UiRect::all(Val::Px(INVENTORY_MARGIN)),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: Color::BLACK.with_alpha(BACKGROUND_ALPHA).into(),
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Local(80),
+                ..default()
+            },
+        ))
+        .add_child(text);
+}
+
+fn toggle_inventory(
+    player_input: Res<PlayerInput>,
+    game_state: Res<State<GameState>>,
+    mut q_root: Query<&mut Visibility, With<InventoryRoot>>,
+) {
+    let mut visibility = match q_root.get_single_mut() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    if *game_state.get() != GameState::Gaming {
+        if *visibility != Visibility::Hidden {
+            *visibility = Visibility::Hidden;
+        }
+        return;
+    }
+
+    if player_input.toggle_inventory {
+        *visibility = if *visibility == Visibility::Hidden {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+fn update_inventory_text(
+    assets: Res<GameAssets>,
+    inventory: Res<Inventory>,
+    q_root: Query<Ref<Visibility>, With<InventoryRoot>>,
+    mut q_text: Query<&mut Text, With<InventoryText>>,
+) {
+    let visibility = match q_root.get_single() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    if *visibility == Visibility::Hidden {
+        return;
+    }
+    if !visibility.is_changed() && !inventory.is_changed() {
+        return;
+    }
+    let mut text = match q_text.get_single_mut() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    let white = text_style_entry(&assets, Color::WHITE);
+    let grey = text_style_entry(&assets, DESCRIPTION_COLOR);
+    let mut sections = vec![TextSection::new("Inventory\n", text_style_title(&assets))];
+
+    let items = inventory.items();
+    if items.is_empty() {
+        sections.push(TextSection::new("Empty.\n", grey.clone()));
+    }
+    for (item, count) in items {
+        let name = if count > 1 {
+            format!("- {} x{}\n", item.name, count)
+        } else {
+            format!("- {}\n", item.name)
+        };
+        sections.push(TextSection::new(name, white.clone()));
+        sections.push(TextSection::new(
+            format!("  {}\n", item.description),
+            grey.clone(),
+        ));
+    }
+
+    text.sections = sections;
+}
+
+pub struct InventoryUiPlugin;
+
+impl Plugin for InventoryUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Gaming), spawn_inventory)
+            .add_systems(
+                Update,
+                (toggle_inventory, update_inventory_text)
+                    .chain()
+                    .run_if(resource_exists::<GameAssets>),
+            );
+    }
+}
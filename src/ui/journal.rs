@@ -0,0 +1,343 @@
+use bevy::prelude::*;
+
+use crate::{
+    npc::{Npc, NpcDialogue},
+    player::input::PlayerInput,
+    GameAssets, GameState,
+};
+
+const BACKGROUND_ALPHA: f32 = 0.9;
+const JOURNAL_WIDTH: f32 = 460.0;
+const JOURNAL_MARGIN: f32 = 30.0;
+const COMPLETED_COLOR: Color = Color::srgb(0.55, 0.55, 0.55);
+const FLASH_DURATION: f32 = 3.5;
+// The flash fades out during this last part of its duration.
+const FLASH_FADE_DURATION: f32 = 1.0;
+
+/// Something an NPC suggested the player should do.
+/// Added with the `add_lead` yarn command and completed with `complete_lead`.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Lead {
+    pub source: NpcDialogue,
+    pub target: NpcDialogue,
+    pub completed: bool,
+}
+
+impl Lead {
+    fn text(&self) -> String {
+        format!(
+            "{} suggested you talk to {}.",
+            display_name(self.source),
+            display_name(self.target)
+        )
+    }
+}
+
+/// Everything the player learned so far.
+/// This lives for the whole game, so it's kept across chats.
+#[derive(Resource, Default)]
+pub struct Journal {
+    leads: Vec<Lead>,
+}
+
+impl Journal {
+    /// Returns the text of the new lead, `None` if the lead already exists.
+    pub fn add_lead(&mut self, source: NpcDialogue, target: NpcDialogue) -> Option<String> {
+        if self
+            .leads
+            .iter()
+            .any(|l| l.source == source && l.target == target)
+        {
+            return None;
+        }
+
+        let lead = Lead {
+            source,
+            target,
+            completed: false,
+        };
+        self.leads.push(lead);
+        Some(lead.text())
+    }
+
+    /// Complete all the leads that point to the given NPC.
+    /// Returns the texts of the leads that got completed.
+    pub fn complete_leads(&mut self, target: NpcDialogue) -> Vec<String> {
+        let mut completed = Vec::new();
+        for lead in &mut self.leads {
+            if lead.target == target && !lead.completed {
+                lead.completed = true;
+                completed.push(lead.text());
+            }
+        }
+        completed
+    }
+}
+
+/// Shows the given text briefly on screen.
+#[derive(Event)]
+pub struct JournalUpdated(pub String);
+
+#[derive(Component)]
+struct JournalRoot;
+#[derive(Component)]
+struct JournalText;
+#[derive(Component)]
+struct JournalFlash {
+    timer: Timer,
+}
+
+fn display_name(dialogue: NpcDialogue) -> String {
+    match dialogue {
+        NpcDialogue::IonasAndAntonius => "Ionas and Antonius".to_string(),
+        _ => dialogue.to_string(),
+    }
+}
+
+fn text_style_title(assets: &Res<GameAssets>) -> TextStyle {
+    TextStyle {
+        font: assets.silver_font.clone(),
+        font_size: 50.0,
+        color: Color::WHITE,
+    }
+}
+
+fn text_style_entry(assets: &Res<GameAssets>, color: Color) -> TextStyle {
+    TextStyle {
+        font: assets.silver_font.clone(),
+        font_size: 38.0,
+        color,
+    }
+}
+
+fn spawn_journal(mut commands: Commands, assets: Res<GameAssets>) {
+    let text = commands
+        .spawn((
+            JournalText,
+            Label,
+            TextBundle::from_section(String::new(), text_style_entry(&assets, Color::WHITE))
+                .with_style(Style {
+                    max_width: Val::Px(JOURNAL_WIDTH - 2.0 * JOURNAL_MARGIN),
+                    ..default()
+                }),
+        ))
+        .id();
+
+    commands
+        .spawn((
+            JournalRoot,
+            Interaction::default(),
+            NodeBundle {
+                style: Style {
+                    width: Val::Px(JOURNAL_WIDTH),
+                    top: Val::Px(JOURNAL_MARGIN),
+                    right: Val::Px(JOURNAL_MARGIN),
+                    padding: UiRect::all(Val::Px(JOURNAL_MARGIN)),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: Color::BLACK.with_alpha(BACKGROUND_ALPHA).into(),
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Local(80),
+                ..default()
+            },
+        ))
+        .add_child(text);
+}
+
+fn toggle_journal(
+    player_input: Res<PlayerInput>,
+    game_state: Res<State<GameState>>,
+    mut q_root: Query<&mut Visibility, With<JournalRoot>>,
+) {
+    let mut visibility = match q_root.get_single_mut() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    if *game_state.get() != GameState::Gaming {
+        if *visibility != Visibility::Hidden {
+            *visibility = Visibility::Hidden;
+        }
+        return;
+    }
+
+    if player_input.toggle_journal {
+        *visibility = if *visibility == Visibility::Hidden {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+fn update_journal_text(
+    assets: Res<GameAssets>,
+    journal: Res<Journal>,
+    q_npcs: Query<&Npc>,
+    q_changed_npcs: Query<(), Changed<Npc>>,
+    q_root: Query<Ref<Visibility>, With<JournalRoot>>,
+    mut q_text: Query<&mut Text, With<JournalText>>,
+) {
+    let visibility = match q_root.get_single() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    if *visibility == Visibility::Hidden {
+        return;
+    }
+    if !visibility.is_changed() && !journal.is_changed() && q_changed_npcs.is_empty() {
+        return;
+    }
+    let mut text = match q_text.get_single_mut() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    let white = text_style_entry(&assets, Color::WHITE);
+    let grey = text_style_entry(&assets, COMPLETED_COLOR);
+    let mut sections = vec![TextSection::new("Leads\n", text_style_title(&assets))];
+
+    if journal.leads.is_empty() {
+        sections.push(TextSection::new("Nothing yet.\n", grey.clone()));
+    }
+    // Open leads first, the completed ones are only there for reference.
+    for lead in journal.leads.iter().filter(|l| !l.completed) {
+        sections.push(TextSection::new(
+            format!("- {}\n", lead.text()),
+            white.clone(),
+        ));
+    }
+    for lead in journal.leads.iter().filter(|l| l.completed) {
+        sections.push(TextSection::new(
+            format!("- {}\n", lead.text()),
+            grey.clone(),
+        ));
+    }
+
+    sections.push(TextSection::new(
+        "\nCharacters\n",
+        text_style_title(&assets),
+    ));
+    let mut known = false;
+    for npc in &q_npcs {
+        let status = if npc.was_talked_to {
+            "talked to".to_string()
+        } else if let Some(source) = npc.was_mentioned_by.first() {
+            format!("heard of from {}", display_name(*source))
+        } else {
+            continue;
+        };
+        known = true;
+        sections.push(TextSection::new(
+            format!("- {} ({})\n", display_name(npc.dialogue), status),
+            white.clone(),
+        ));
+    }
+    if !known {
+        sections.push(TextSection::new("No one yet.\n", grey));
+    }
+
+    text.sections = sections;
+}
+
+fn spawn_flashes(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    q_flashes: Query<Entity, With<JournalFlash>>,
+    mut ev_journal_updated: EventReader<JournalUpdated>,
+) {
+    // Several updates at once (e.g. completing multiple leads) share one flash.
+    let updates: Vec<&str> = ev_journal_updated.read().map(|ev| ev.0.as_str()).collect();
+    if updates.is_empty() {
+        return;
+    }
+
+    // Only show the newest updates.
+    for entity in &q_flashes {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let text = commands
+        .spawn(TextBundle::from_section(
+            format!("Journal updated: {}", updates.join("\n")),
+            text_style_entry(&assets, Color::WHITE),
+        ))
+        .id();
+
+    commands
+        .spawn((
+            JournalFlash {
+                timer: Timer::from_seconds(FLASH_DURATION, TimerMode::Once),
+            },
+            NodeBundle {
+                style: Style {
+                    top: Val::Px(JOURNAL_MARGIN),
+                    left: Val::Percent(50.0),
+                    padding: UiRect::axes(Val::Px(20.0), Val::Px(8.0)),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: Color::BLACK.with_alpha(BACKGROUND_ALPHA).into(),
+                z_index: ZIndex::Local(80),
+                ..default()
+            },
+        ))
+        .add_child(text);
+}
+
+/// Keep the flash centered, we only know its size after the layout.
+fn update_flashes(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_flashes: Query<(
+        Entity,
+        &mut JournalFlash,
+        &Node,
+        &mut Style,
+        &mut BackgroundColor,
+        &Children,
+    )>,
+    mut q_texts: Query<&mut Text>,
+) {
+    for (entity, mut flash, node, mut style, mut background, children) in &mut q_flashes {
+        flash.timer.tick(time.delta());
+        if flash.timer.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        style.margin.left = Val::Px(-node.size().x / 2.0);
+
+        let alpha = (flash.timer.remaining_secs() / FLASH_FADE_DURATION).min(1.0);
+        background.0.set_alpha(alpha * BACKGROUND_ALPHA);
+        for child in children {
+            if let Ok(mut text) = q_texts.get_mut(*child) {
+                for section in &mut text.sections {
+                    section.style.color.set_alpha(alpha);
+                }
+            }
+        }
+    }
+}
+
+pub struct JournalPlugin;
+
+impl Plugin for JournalPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Journal>()
+            .add_event::<JournalUpdated>()
+            .add_systems(OnEnter(GameState::Gaming), spawn_journal)
+            .add_systems(
+                Update,
+                (
+                    toggle_journal,
+                    update_journal_text,
+                    spawn_flashes,
+                    update_flashes,
+                )
+                    .chain()
+                    .run_if(resource_exists::<GameAssets>),
+            );
+    }
+}
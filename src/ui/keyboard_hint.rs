@@ -5,7 +5,11 @@ use bevy_rapier2d::prelude::*;
 use bevy_trickfilm::prelude::*;
 
 use crate::{
-    world::{camera::YSort, map::generation::BitMap},
+    world::{
+        camera::YSort,
+        map::{generation::BitMap, reserved_areas::ReservedAreas},
+        photo_mode::HideInPhotoMode,
+    },
     GameAssets, GameState,
 };
 
@@ -14,7 +18,7 @@ const BUTTON_DIS: f32 = 35.0;
 const SHIFT_DIS: f32 = 35.0;
 const ICON_SIZE: f32 = 0.5;
 
-pub const KEYBOARD_ICON_RADIUS: f32 = 100.0;
+const KEYBOARD_ICON_RADIUS: f32 = 100.0;
 
 #[derive(Component)]
 struct KeyboardIcon;
@@ -172,7 +176,12 @@ fn calculate_dir(vecs: &Vec<Vec2>) -> Vec2 {
     Vec2::from_angle(final_angle)
 }
 
-fn spawn_keyboard_ui(mut commands: Commands, assets: Res<GameAssets>, bitmap: Res<BitMap>) {
+fn spawn_keyboard_ui(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    bitmap: Res<BitMap>,
+    mut reserved_areas: ResMut<ReservedAreas>,
+) {
     let transform = Transform::from_translation(
         calculate_dir(&bitmap.get_origin_edges())
             .normalize_or_zero()
@@ -180,9 +189,12 @@ fn spawn_keyboard_ui(mut commands: Commands, assets: Res<GameAssets>, bitmap: Re
             * ANCHOR_DIS,
     )
     .with_scale(Vec3::splat(ICON_SIZE));
+    reserved_areas.reserve_circle(transform.translation.truncate(), KEYBOARD_ICON_RADIUS);
+
     let root = commands
         .spawn((
             KeyboardHint,
+            HideInPhotoMode,
             YSort(-200.0),
             SpatialBundle::from_transform(transform),
         ))
@@ -0,0 +1,332 @@
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+    utils::HashMap,
+};
+
+use crate::{
+    npc::{companion::Companion, Npc},
+    player::{input::PlayerInput, Player},
+    world::{
+        exploration::{chunk_of, Exploration},
+        map::{generation::BitMap, navigation::NavGrid, CHUNK_SIZE},
+    },
+    GameState,
+};
+
+// The minimap shows this many chunks in every direction around the player's chunk.
+const MINIMAP_CHUNKS_RADIUS: i32 = 3;
+const MINIMAP_CHUNKS: i32 = 2 * MINIMAP_CHUNKS_RADIUS + 1;
+const MINIMAP_TILES: i32 = MINIMAP_CHUNKS * CHUNK_SIZE as i32;
+// Every tile is a square of this many UI pixels, keeps the pixel art look.
+const PIXELS_PER_TILE: f32 = 2.0;
+const MINIMAP_SIZE: f32 = MINIMAP_TILES as f32 * PIXELS_PER_TILE;
+const MINIMAP_MARGIN: f32 = 30.0;
+const MINIMAP_BORDER: f32 = 4.0;
+const MARKER_SIZE: f32 = 6.0;
+
+const BORDER_COLOR: Color = Color::srgb(0.1, 0.08, 0.07);
+const PLAYER_COLOR: Color = Color::WHITE;
+const NPC_COLOR: Color = Color::srgb(0.98, 0.8, 0.25);
+
+const UNEXPLORED_COLOR: [u8; 4] = [20, 16, 14, 230];
+const GRASS_COLOR: [u8; 4] = [106, 156, 78, 255];
+const PATH_COLOR: [u8; 4] = [196, 164, 116, 255];
+const WATER_COLOR: [u8; 4] = [70, 140, 180, 255];
+const BRIDGE_COLOR: [u8; 4] = [140, 96, 60, 255];
+
+/// Toggled with `M`.
+#[derive(Resource, Deref)]
+pub struct MinimapActive(bool);
+
+impl Default for MinimapActive {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+/// The colors of every tile of every explored chunk.
+/// The tile types are cached the first time the chunk is drawn,
+/// so that redrawing doesn't need to go through the `BitMap`.
+#[derive(Resource, Default)]
+struct ExploredChunks(HashMap<IVec2, Vec<[u8; 4]>>);
+
+#[derive(Resource)]
+struct MinimapImage(Handle<Image>);
+
+#[derive(Component)]
+struct MinimapRoot;
+#[derive(Component)]
+struct MinimapMarker(Option<Entity>);
+
+fn tile_color(bitmap: &mut BitMap, v: IVec2) -> [u8; 4] {
+    if bitmap.get_bridge_flag(v) {
+        BRIDGE_COLOR
+    } else if bitmap.get_path_flag(v) {
+        PATH_COLOR
+    } else if bitmap.is_water(v) {
+        WATER_COLOR
+    } else {
+        GRASS_COLOR
+    }
+}
+
+fn spawn_marker(commands: &mut Commands, color: Color, target: Option<Entity>) -> Entity {
+    commands
+        .spawn((
+            MinimapMarker(target),
+            NodeBundle {
+                style: Style {
+                    width: Val::Px(MARKER_SIZE),
+                    height: Val::Px(MARKER_SIZE),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: color.into(),
+                ..default()
+            },
+        ))
+        .id()
+}
+
+fn spawn_minimap(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let image = images.add(Image::new_fill(
+        Extent3d {
+            width: MINIMAP_TILES as u32,
+            height: MINIMAP_TILES as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &UNEXPLORED_COLOR,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    ));
+    commands.insert_resource(MinimapImage(image.clone()));
+
+    let map = commands
+        .spawn(ImageBundle {
+            image: UiImage::new(image),
+            style: Style {
+                width: Val::Px(MINIMAP_SIZE),
+                height: Val::Px(MINIMAP_SIZE),
+                ..default()
+            },
+            ..default()
+        })
+        .id();
+    let player_marker = spawn_marker(&mut commands, PLAYER_COLOR, None);
+
+    commands
+        .spawn((
+            MinimapRoot,
+            Interaction::default(),
+            NodeBundle {
+                style: Style {
+                    top: Val::Px(MINIMAP_MARGIN),
+                    left: Val::Px(MINIMAP_MARGIN),
+                    border: UiRect::all(Val::Px(MINIMAP_BORDER)),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                border_color: BORDER_COLOR.into(),
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Local(70),
+                ..default()
+            },
+        ))
+        .push_children(&[map, player_marker]);
+}
+
+fn explore_chunks(
+    mut bitmap: ResMut<BitMap>,
+    exploration: Res<Exploration>,
+    mut explored_chunks: ResMut<ExploredChunks>,
+) {
+    if !exploration.is_changed() {
+        return;
+    }
+
+    // This includes the chunks that were restored from a previous session.
+    for chunk in exploration.explored_chunks() {
+        if explored_chunks.0.contains_key(chunk) {
+            continue;
+        }
+
+        let size = CHUNK_SIZE as i32;
+        let mut colors = Vec::with_capacity((size * size) as usize);
+        for y in 0..size {
+            for x in 0..size {
+                colors.push(tile_color(&mut bitmap, *chunk * size + IVec2::new(x, y)));
+            }
+        }
+        explored_chunks.0.insert(*chunk, colors);
+    }
+}
+
+/// The chunk in the bottom left corner of the minimap.
+fn minimap_origin(player_pos: Vec2) -> IVec2 {
+    chunk_of(player_pos) - IVec2::splat(MINIMAP_CHUNKS_RADIUS)
+}
+
+fn redraw_minimap(
+    explored_chunks: Res<ExploredChunks>,
+    minimap_image: Res<MinimapImage>,
+    mut images: ResMut<Assets<Image>>,
+    q_player: Query<&Transform, With<Player>>,
+    mut last_origin: Local<Option<IVec2>>,
+) {
+    let player_transform = match q_player.get_single() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    let origin = minimap_origin(player_transform.translation.truncate());
+    if *last_origin == Some(origin) && !explored_chunks.is_changed() {
+        return;
+    }
+    *last_origin = Some(origin);
+
+    let image = match images.get_mut(&minimap_image.0) {
+        Some(r) => r,
+        None => return,
+    };
+
+    let size = CHUNK_SIZE as i32;
+    for chunk_y in 0..MINIMAP_CHUNKS {
+        for chunk_x in 0..MINIMAP_CHUNKS {
+            let chunk = explored_chunks
+                .0
+                .get(&(origin + IVec2::new(chunk_x, chunk_y)));
+
+            for y in 0..size {
+                for x in 0..size {
+                    let color = match chunk {
+                        Some(colors) => colors[(y * size + x) as usize],
+                        None => UNEXPLORED_COLOR,
+                    };
+                    // Images start at the top, the world starts at the bottom.
+                    let px = chunk_x * size + x;
+                    let py = MINIMAP_TILES - 1 - (chunk_y * size + y);
+                    let index = 4 * (py * MINIMAP_TILES + px) as usize;
+                    image.data[index..index + 4].copy_from_slice(&color);
+                }
+            }
+        }
+    }
+}
+
+/// Spawn a marker for every NPC the player knows about.
+fn spawn_npc_markers(
+    mut commands: Commands,
+    q_root: Query<Entity, With<MinimapRoot>>,
+    q_npcs: Query<(Entity, &Npc), Changed<Npc>>,
+    q_markers: Query<&MinimapMarker>,
+) {
+    let root = match q_root.get_single() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    for (entity, npc) in &q_npcs {
+        if !npc.was_talked_to && npc.was_mentioned_by.is_empty() {
+            continue;
+        }
+        if q_markers.iter().any(|m| m.0 == Some(entity)) {
+            continue;
+        }
+
+        let marker = spawn_marker(&mut commands, NPC_COLOR, Some(entity));
+        commands.entity(root).add_child(marker);
+    }
+}
+
+fn update_markers(
+    q_player: Query<&Transform, With<Player>>,
+    q_targets: Query<&Transform, (Without<Player>, Without<Companion>)>,
+    mut q_markers: Query<(&MinimapMarker, &mut Style, &mut Visibility)>,
+) {
+    let player_pos = match q_player.get_single() {
+        Ok(r) => r.translation.truncate(),
+        Err(_) => return,
+    };
+    let origin = minimap_origin(player_pos).as_vec2() * CHUNK_SIZE as f32;
+
+    for (marker, mut style, mut visibility) in &mut q_markers {
+        let pos = match marker.0 {
+            None => player_pos,
+            Some(entity) => match q_targets.get(entity) {
+                Ok(r) => r.translation.truncate(),
+                // Companions are always next to the player, no need to show them.
+                Err(_) => {
+                    *visibility = Visibility::Hidden;
+                    continue;
+                }
+            },
+        };
+
+        let tile = NavGrid::world_to_cell(pos).as_vec2() - origin;
+        let inside =
+            tile.cmpge(Vec2::ZERO).all() && tile.cmplt(Vec2::splat(MINIMAP_TILES as f32)).all();
+        let vis = if inside {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if *visibility != vis {
+            *visibility = vis;
+        }
+
+        let offset = MINIMAP_BORDER - MARKER_SIZE / 2.0;
+        style.left = Val::Px(offset + tile.x * PIXELS_PER_TILE);
+        style.top = Val::Px(offset + (MINIMAP_TILES as f32 - tile.y) * PIXELS_PER_TILE);
+    }
+}
+
+fn toggle_minimap(
+    player_input: Res<PlayerInput>,
+    game_state: Res<State<GameState>>,
+    mut active: ResMut<MinimapActive>,
+    mut q_root: Query<&mut Visibility, With<MinimapRoot>>,
+) {
+    if player_input.toggle_minimap {
+        active.0 = !active.0;
+    }
+
+    let mut visibility = match q_root.get_single_mut() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    let vis = if **active && *game_state.get() == GameState::Gaming {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    if *visibility != vis {
+        *visibility = vis;
+    }
+}
+
+pub struct MinimapPlugin;
+
+impl Plugin for MinimapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MinimapActive>()
+            .init_resource::<ExploredChunks>()
+            .add_systems(OnEnter(GameState::Gaming), spawn_minimap)
+            .add_systems(
+                Update,
+                (
+                    explore_chunks.run_if(resource_exists::<Exploration>),
+                    redraw_minimap.run_if(resource_exists::<MinimapImage>),
+                    spawn_npc_markers,
+                    update_markers,
+                    toggle_minimap,
+                )
+                    .chain(),
+            );
+    }
+}
@@ -1,15 +1,20 @@
 pub mod dialogue;
+pub mod journal;
 pub mod keyboard_hint;
 
 mod audio_bar;
 mod ending_text;
+mod inventory;
 mod main_menu;
+mod minimap;
+mod npc_indicators;
 mod screen_fade;
 mod splash_screen;
+mod touch_controls;
 
-use bevy::{prelude::*, window::WindowResized};
+use bevy::prelude::*;
 
-use crate::DEFAULT_WINDOW_WIDTH;
+use crate::{world::MainCamera, DEFAULT_WINDOW_HEIGHT, DEFAULT_WINDOW_WIDTH};
 
 pub struct UiPlugin;
 
@@ -23,13 +28,30 @@ impl Plugin for UiPlugin {
             ending_text::EndingTextPlugin,
             splash_screen::SplashScreenPlugin,
             main_menu::MainMenuPlugin,
+            touch_controls::TouchControlsPlugin,
+            journal::JournalPlugin,
+            inventory::InventoryUiPlugin,
+            minimap::MinimapPlugin,
+            npc_indicators::NpcIndicatorsPlugin,
         ))
         .add_systems(Update, scale_ui);
     }
 }
 
-fn scale_ui(mut ui_scale: ResMut<UiScale>, mut ev_window_resized: EventReader<WindowResized>) {
-    for ev in ev_window_resized.read() {
-        ui_scale.0 = ev.width / DEFAULT_WINDOW_WIDTH;
+/// Scale the UI so that it fits into the camera viewport both in landscape and portrait mode.
+/// The UI gets laid out inside the viewport, so this also works with letterboxing.
+fn scale_ui(mut ui_scale: ResMut<UiScale>, q_camera: Query<&Camera, With<MainCamera>>) {
+    let size = match q_camera
+        .get_single()
+        .ok()
+        .and_then(|c| c.logical_viewport_size())
+    {
+        Some(r) => r,
+        None => return,
+    };
+
+    let scale = (size.x / DEFAULT_WINDOW_WIDTH).min(size.y / DEFAULT_WINDOW_HEIGHT);
+    if ui_scale.0 != scale {
+        ui_scale.0 = scale;
     }
 }
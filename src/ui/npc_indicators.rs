@@ -0,0 +1,147 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+
+use crate::{
+    npc::{companion::Companion, Npc},
+    player::input::PlayerInput,
+    world::MainCamera,
+    GameAssets, GameState,
+};
+
+// Distance of the arrows to the edges of the viewport, in UI pixels.
+const INDICATOR_MARGIN: f32 = 40.0;
+// The arrow image is 27x22, keep it at an integer scale.
+const INDICATOR_WIDTH: f32 = 54.0;
+const INDICATOR_HEIGHT: f32 = 44.0;
+
+/// Toggled with `N`.
+#[derive(Resource, Deref)]
+pub struct NpcIndicatorsActive(bool);
+
+impl Default for NpcIndicatorsActive {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+/// Arrow on the edge of the screen that points to an NPC the player heard of.
+#[derive(Component)]
+struct NpcIndicator(Entity);
+
+fn spawn_indicators(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    q_npcs: Query<(Entity, &Npc), Changed<Npc>>,
+    q_indicators: Query<&NpcIndicator>,
+) {
+    for (entity, npc) in &q_npcs {
+        if npc.was_mentioned_by.is_empty() {
+            continue;
+        }
+        if q_indicators.iter().any(|i| i.0 == entity) {
+            continue;
+        }
+
+        commands.spawn((
+            NpcIndicator(entity),
+            ImageBundle {
+                image: UiImage::new(assets.dialogue_continue.clone()),
+                style: Style {
+                    width: Val::Px(INDICATOR_WIDTH),
+                    height: Val::Px(INDICATOR_HEIGHT),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Local(60),
+                ..default()
+            },
+        ));
+    }
+}
+
+/// Point the arrows to the NPCs that are off screen.
+/// NPCs that are visible, talked to or following the player don't need an arrow.
+fn update_indicators(
+    game_state: Res<State<GameState>>,
+    ui_scale: Res<UiScale>,
+    active: Res<NpcIndicatorsActive>,
+    q_camera: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    q_npcs: Query<(&GlobalTransform, &Npc, Has<Companion>)>,
+    mut q_indicators: Query<(&NpcIndicator, &mut Style, &mut Transform, &mut Visibility)>,
+) {
+    let (camera, camera_transform) = match q_camera.get_single() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    let viewport_size = match camera.logical_viewport_size() {
+        Some(r) => r / ui_scale.0,
+        None => return,
+    };
+    let center = viewport_size / 2.0;
+    let half_extents = center - Vec2::splat(INDICATOR_MARGIN);
+
+    for (indicator, mut style, mut transform, mut visibility) in &mut q_indicators {
+        let screen_pos = match q_npcs.get(indicator.0) {
+            Ok((npc_transform, npc, is_companion))
+                if **active
+                    && *game_state.get() == GameState::Gaming
+                    && !npc.was_talked_to
+                    && !is_companion =>
+            {
+                camera
+                    .world_to_viewport(camera_transform, npc_transform.translation())
+                    .map(|pos| pos / ui_scale.0)
+            }
+            _ => None,
+        };
+
+        let on_screen = screen_pos
+            .is_none_or(|pos| pos.cmpge(Vec2::ZERO).all() && pos.cmple(viewport_size).all());
+        let vis = if on_screen {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+        if *visibility != vis {
+            *visibility = vis;
+        }
+        let Some(pos) = screen_pos else {
+            continue;
+        };
+        if on_screen {
+            continue;
+        }
+
+        // Clamp the direction onto the rectangle inside the margins.
+        let dir = pos - center;
+        let t = (half_extents.x / dir.x.abs()).min(half_extents.y / dir.y.abs());
+        let edge_pos = center + dir * t;
+        style.left = Val::Px(edge_pos.x - INDICATOR_WIDTH / 2.0);
+        style.top = Val::Px(edge_pos.y - INDICATOR_HEIGHT / 2.0);
+
+        // The arrow image points down, and so does the y axis of the UI.
+        let angle = dir.y.atan2(dir.x) - FRAC_PI_2;
+        transform.rotation = Quat::from_rotation_z(angle);
+    }
+}
+
+fn toggle_indicators(player_input: Res<PlayerInput>, mut active: ResMut<NpcIndicatorsActive>) {
+    if player_input.toggle_npc_indicators {
+        active.0 = !active.0;
+    }
+}
+
+pub struct NpcIndicatorsPlugin;
+
+impl Plugin for NpcIndicatorsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NpcIndicatorsActive>().add_systems(
+            Update,
+            (toggle_indicators, spawn_indicators, update_indicators)
+                .chain()
+                .run_if(resource_exists::<GameAssets>),
+        );
+    }
+}
@@ -0,0 +1,378 @@
+use bevy::input::{touch::Touches, InputSystem};
+use bevy::prelude::*;
+
+use crate::{
+    player::input::{
+        MouseWorldCoords, PlayerInput, PlayerInputOverrideSystemSet, PlayerInputSystemSet,
+    },
+    world::MainCamera,
+    GameAssets, GameState,
+};
+
+const JOYSTICK_SIZE: f32 = 180.0;
+const KNOB_SIZE: f32 = 72.0;
+// Below this the joystick doesn't move the player, so resting a thumb on it is fine.
+const JOYSTICK_DEADZONE: f32 = 0.2;
+const CONTROLS_MARGIN: f32 = 50.0;
+const BUTTON_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.15);
+const PRESSED_BUTTON_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.35);
+
+/// Whether the player is using a touch screen.
+/// On the web we ask the browser at startup, after that we show the controls
+/// once we see a touch and hide them again as soon as the keyboard gets used.
+#[derive(Resource, Deref)]
+pub struct TouchControlsActive(bool);
+
+impl Default for TouchControlsActive {
+    fn default() -> Self {
+        Self(has_touch_screen())
+    }
+}
+
+#[derive(Resource, Default)]
+struct Joystick {
+    touch: Option<u64>,
+    /// Offset of the knob from the center, length is at most 1.
+    /// Note that this is in UI space, so positive y points down.
+    offset: Vec2,
+}
+
+#[derive(Component)]
+struct TouchControlsRoot;
+#[derive(Component)]
+struct JoystickBase;
+#[derive(Component)]
+struct JoystickKnob;
+
+#[derive(Component, Clone, Copy, PartialEq)]
+enum TouchButton {
+    Talk,
+    Run,
+    Back,
+}
+
+/// Phones and tablets report a coarse pointer, fall back to the touch points
+/// for browsers that don't support the media query.
+#[cfg(target_arch = "wasm32")]
+fn has_touch_screen() -> bool {
+    let Some(window) = web_sys::window() else {
+        return false;
+    };
+    match window.match_media("(pointer: coarse)") {
+        Ok(Some(query)) => query.matches(),
+        _ => window.navigator().max_touch_points() > 0,
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn has_touch_screen() -> bool {
+    false
+}
+
+fn spawn_button(
+    commands: &mut Commands,
+    assets: &Res<GameAssets>,
+    button: TouchButton,
+    title: &str,
+    style: Style,
+) -> Entity {
+    let text_style = TextStyle {
+        font: assets.pixel_font.clone(),
+        font_size: 22.0,
+        color: Color::WHITE,
+    };
+    let text = commands
+        .spawn(TextBundle::from_section(title, text_style))
+        .id();
+
+    commands
+        .spawn((
+            button,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..style
+                },
+                background_color: BUTTON_COLOR.into(),
+                border_radius: BorderRadius::MAX,
+                ..default()
+            },
+        ))
+        .add_child(text)
+        .id()
+}
+
+fn spawn_touch_controls(mut commands: Commands, assets: Res<GameAssets>) {
+    let knob = commands
+        .spawn((
+            JoystickKnob,
+            NodeBundle {
+                style: Style {
+                    width: Val::Px(KNOB_SIZE),
+                    height: Val::Px(KNOB_SIZE),
+                    left: Val::Px((JOYSTICK_SIZE - KNOB_SIZE) / 2.0),
+                    top: Val::Px((JOYSTICK_SIZE - KNOB_SIZE) / 2.0),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: PRESSED_BUTTON_COLOR.into(),
+                border_radius: BorderRadius::MAX,
+                ..default()
+            },
+        ))
+        .id();
+    let joystick = commands
+        .spawn((
+            JoystickBase,
+            NodeBundle {
+                style: Style {
+                    width: Val::Px(JOYSTICK_SIZE),
+                    height: Val::Px(JOYSTICK_SIZE),
+                    left: Val::Px(CONTROLS_MARGIN),
+                    bottom: Val::Px(CONTROLS_MARGIN),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: BUTTON_COLOR.into(),
+                border_radius: BorderRadius::MAX,
+                ..default()
+            },
+        ))
+        .add_child(knob)
+        .id();
+
+    let talk = spawn_button(
+        &mut commands,
+        &assets,
+        TouchButton::Talk,
+        "TALK",
+        Style {
+            width: Val::Px(120.0),
+            height: Val::Px(120.0),
+            right: Val::Px(CONTROLS_MARGIN),
+            bottom: Val::Px(CONTROLS_MARGIN + 60.0),
+            ..default()
+        },
+    );
+    let run = spawn_button(
+        &mut commands,
+        &assets,
+        TouchButton::Run,
+        "RUN",
+        Style {
+            width: Val::Px(90.0),
+            height: Val::Px(90.0),
+            right: Val::Px(CONTROLS_MARGIN + 140.0),
+            bottom: Val::Px(CONTROLS_MARGIN),
+            ..default()
+        },
+    );
+    let back = spawn_button(
+        &mut commands,
+        &assets,
+        TouchButton::Back,
+        "BACK",
+        Style {
+            width: Val::Px(110.0),
+            height: Val::Px(60.0),
+            right: Val::Px(CONTROLS_MARGIN / 2.0),
+            top: Val::Px(CONTROLS_MARGIN / 2.0),
+            ..default()
+        },
+    );
+
+    commands
+        .spawn((
+            TouchControlsRoot,
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Local(90),
+                ..default()
+            },
+        ))
+        .push_children(&[joystick, talk, run, back]);
+}
+
+fn detect_touch_device(
+    touches: Res<Touches>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut active: ResMut<TouchControlsActive>,
+) {
+    if touches.any_just_pressed() {
+        active.0 = true;
+    } else if keys.get_just_pressed().next().is_some() {
+        active.0 = false;
+    }
+}
+
+fn toggle_touch_controls(
+    active: Res<TouchControlsActive>,
+    game_state: Res<State<GameState>>,
+    mut q_root: Query<&mut Visibility, With<TouchControlsRoot>>,
+) {
+    let mut visibility = match q_root.get_single_mut() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    let vis = if **active && *game_state.get() == GameState::Gaming {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    if *visibility != vis {
+        *visibility = vis;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn touch_input(
+    touches: Res<Touches>,
+    ui_scale: Res<UiScale>,
+    active: Res<TouchControlsActive>,
+    mut joystick: ResMut<Joystick>,
+    mut player_input: ResMut<PlayerInput>,
+    mut mouse_coords: ResMut<MouseWorldCoords>,
+    q_camera: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    q_joystick: Query<(&Node, &GlobalTransform), With<JoystickBase>>,
+    mut q_buttons: Query<(&Node, &GlobalTransform, &TouchButton, &mut BackgroundColor)>,
+) {
+    if !**active {
+        return;
+    }
+
+    let joystick_rect = match q_joystick.get_single() {
+        Ok((node, transform)) => node.logical_rect(transform),
+        Err(_) => return,
+    };
+    // The UI is laid out inside of the camera viewport, which may be letterboxed.
+    let (camera, camera_transform) = match q_camera.get_single() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    let viewport_origin = camera
+        .logical_viewport_rect()
+        .map(|rect| rect.min)
+        .unwrap_or_default();
+    let to_ui = |touch_pos: Vec2| (touch_pos - viewport_origin) / ui_scale.0;
+
+    for (node, transform, button, mut color) in &mut q_buttons {
+        let rect = node.logical_rect(transform);
+        let held = touches.iter().any(|t| rect.contains(to_ui(t.position())));
+        let pressed = touches
+            .iter_just_pressed()
+            .any(|t| rect.contains(to_ui(t.position())));
+
+        match button {
+            TouchButton::Talk => player_input.dialogue |= pressed,
+            TouchButton::Run => player_input.running |= held,
+            TouchButton::Back => player_input.escape |= pressed,
+        }
+
+        let new_color = if held {
+            PRESSED_BUTTON_COLOR
+        } else {
+            BUTTON_COLOR
+        };
+        if color.0 != new_color {
+            color.0 = new_color;
+        }
+    }
+
+    let on_button = |pos: Vec2| {
+        q_buttons
+            .iter()
+            .any(|(node, transform, _, _)| node.logical_rect(transform).contains(pos))
+    };
+
+    for touch in touches.iter_just_pressed() {
+        let pos = to_ui(touch.position());
+        if on_button(pos) {
+            continue;
+        }
+
+        // Be generous with the joystick area, thumbs tend to miss it.
+        if joystick.touch.is_none()
+            && joystick_rect
+                .inflate(joystick_rect.width() / 2.0)
+                .contains(pos)
+        {
+            joystick.touch = Some(touch.id());
+        } else {
+            // Tapping anywhere else acts like a left click,
+            // e.g. to skip dialogue, to select an option or to walk there.
+            player_input.dialogue = true;
+            if let Some(ray) =
+                camera.viewport_to_world(camera_transform, touch.position() - viewport_origin)
+            {
+                player_input.click = true;
+                mouse_coords.0 = ray.origin.truncate();
+            }
+        }
+    }
+
+    let touch = joystick.touch.and_then(|id| touches.get_pressed(id));
+    match touch {
+        Some(touch) => {
+            let radius = joystick_rect.width() / 2.0;
+            let offset = (to_ui(touch.position()) - joystick_rect.center()) / radius;
+            joystick.offset = offset.clamp_length_max(1.0);
+        }
+        None => {
+            joystick.touch = None;
+            joystick.offset = Vec2::ZERO;
+        }
+    }
+
+    if joystick.offset.length() > JOYSTICK_DEADZONE {
+        player_input.move_direction = Vec2::new(joystick.offset.x, -joystick.offset.y).normalize();
+    }
+}
+
+fn update_knob(joystick: Res<Joystick>, mut q_knob: Query<&mut Style, With<JoystickKnob>>) {
+    let mut style = match q_knob.get_single_mut() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    let center = (JOYSTICK_SIZE - KNOB_SIZE) / 2.0;
+    let offset = joystick.offset * JOYSTICK_SIZE / 2.0;
+    style.left = Val::Px(center + offset.x);
+    style.top = Val::Px(center + offset.y);
+}
+
+pub struct TouchControlsPlugin;
+
+impl Plugin for TouchControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TouchControlsActive>()
+            .init_resource::<Joystick>()
+            .add_systems(OnExit(GameState::AssetLoading), spawn_touch_controls)
+            .add_systems(
+                PreUpdate,
+                (
+                    detect_touch_device,
+                    touch_input.run_if(in_state(GameState::Gaming)),
+                )
+                    .chain()
+                    .after(InputSystem)
+                    .after(PlayerInputSystemSet)
+                    .before(PlayerInputOverrideSystemSet),
+            )
+            .add_systems(
+                Update,
+                (
+                    toggle_touch_controls,
+                    update_knob.run_if(resource_changed::<Joystick>),
+                ),
+            );
+    }
+}
@@ -0,0 +1,147 @@
+use std::str::FromStr;
+
+use bevy::prelude::*;
+use bevy_yarnspinner::prelude::*;
+use strum::IntoEnumIterator;
+
+use crate::npc::NpcDialogue;
+
+/// All the commands with their arguments, for `help`, errors and autocomplete.
+pub const COMMANDS: [(&str, &str); 10] = [
+    ("help", ""),
+    ("tp", "<x> <y>"),
+    ("tp_npc", "<npc>"),
+    ("set_var", "<$variable> <value>"),
+    ("start_node", "<npc> <node>"),
+    ("trigger_ending", "<npc>"),
+    ("seed", ""),
+    ("regen", "<seed>"),
+    ("speed", "[factor]"),
+    ("volume", "[0..1]"),
+];
+
+#[derive(Event, Debug, PartialEq)]
+pub enum ConsoleCommand {
+    Help,
+    /// Teleport the player to the position in world units.
+    Teleport(Vec2),
+    TeleportToNpc(NpcDialogue),
+    /// Set the yarn variable on all dialogue runners.
+    SetVariable {
+        name: String,
+        value: YarnValue,
+    },
+    StartNode {
+        dialogue: NpcDialogue,
+        node: String,
+    },
+    TriggerEnding(NpcDialogue),
+    Seed,
+    /// Restart the game with a world generated from this seed.
+    Regenerate(f32),
+    /// Print or set how fast the game runs.
+    Speed(Option<f32>),
+    /// Print or set the main volume.
+    Volume(Option<f64>),
+}
+
+/// The names and nodes the arguments are completed with, see `complete`.
+#[derive(Resource, Default)]
+pub struct Completions {
+    pub nodes: Vec<String>,
+    pub variables: Vec<String>,
+}
+
+fn parse_number<T: FromStr>(s: &str) -> Result<T, String> {
+    s.parse().map_err(|_| format!("'{}' is not a number", s))
+}
+
+fn parse_npc(s: &str) -> Result<NpcDialogue, String> {
+    NpcDialogue::from_str(s).map_err(|_| format!("there is no npc '{}'", s))
+}
+
+/// Booleans and numbers like in yarn, everything else is a string.
+pub fn parse_yarn_value(s: &str) -> YarnValue {
+    match s {
+        "true" => YarnValue::Boolean(true),
+        "false" => YarnValue::Boolean(false),
+        _ => match s.parse::<f32>() {
+            Ok(number) => YarnValue::Number(number),
+            Err(_) => YarnValue::String(s.trim_matches('"').to_string()),
+        },
+    }
+}
+
+fn usage(name: &str) -> String {
+    match COMMANDS.iter().find(|(command, _)| *command == name) {
+        Some((command, args)) => format!("usage: {} {}", command, args),
+        None => format!("unknown command '{}', try 'help'", name),
+    }
+}
+
+impl ConsoleCommand {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&name, args)) = words.split_first() else {
+            return Err("empty command".to_string());
+        };
+
+        match (name, args) {
+            ("help", []) => Ok(ConsoleCommand::Help),
+            ("tp", [x, y]) => Ok(ConsoleCommand::Teleport(Vec2::new(
+                parse_number(x)?,
+                parse_number(y)?,
+            ))),
+            ("tp_npc", [npc]) => Ok(ConsoleCommand::TeleportToNpc(parse_npc(npc)?)),
+            ("set_var", [variable, value @ ..]) if !value.is_empty() => {
+                if !variable.starts_with('$') {
+                    return Err(format!("variables start with '$', got '{}'", variable));
+                }
+                Ok(ConsoleCommand::SetVariable {
+                    name: variable.to_string(),
+                    value: parse_yarn_value(&value.join(" ")),
+                })
+            }
+            ("start_node", [npc, node]) => Ok(ConsoleCommand::StartNode {
+                dialogue: parse_npc(npc)?,
+                node: node.to_string(),
+            }),
+            ("trigger_ending", [npc]) => Ok(ConsoleCommand::TriggerEnding(parse_npc(npc)?)),
+            ("seed", []) => Ok(ConsoleCommand::Seed),
+            ("regen", [seed]) => Ok(ConsoleCommand::Regenerate(parse_number(seed)?)),
+            ("speed", []) => Ok(ConsoleCommand::Speed(None)),
+            ("speed", [factor]) => Ok(ConsoleCommand::Speed(Some(parse_number(factor)?))),
+            ("volume", []) => Ok(ConsoleCommand::Volume(None)),
+            ("volume", [volume]) => Ok(ConsoleCommand::Volume(Some(parse_number(volume)?))),
+            _ => Err(usage(name)),
+        }
+    }
+}
+
+/// The candidates for the last (possibly empty) word of the line,
+/// depending on the command and the position of the word.
+pub fn complete(line: &str, completions: &Completions) -> Vec<String> {
+    let mut words: Vec<&str> = line.split_whitespace().collect();
+    if line.is_empty() || line.ends_with(char::is_whitespace) {
+        words.push("");
+    }
+    let Some((&last, previous)) = words.split_last() else {
+        return Vec::new();
+    };
+
+    let npcs = || NpcDialogue::iter().map(|npc| npc.to_string()).collect();
+    let candidates: Vec<String> = match previous {
+        [] => COMMANDS.iter().map(|(name, _)| name.to_string()).collect(),
+        ["tp_npc" | "trigger_ending" | "start_node"] => npcs(),
+        ["start_node", _] => completions.nodes.clone(),
+        ["set_var"] => completions.variables.clone(),
+        _ => Vec::new(),
+    };
+
+    let mut candidates: Vec<String> = candidates
+        .into_iter()
+        .filter(|candidate| candidate.starts_with(last))
+        .collect();
+    candidates.sort();
+    candidates
+}
@@ -0,0 +1,210 @@
+use bevy::prelude::*;
+use bevy_yarnspinner::prelude::*;
+
+use crate::{
+    audio::GameAudio,
+    npc::{Npc, NpcDialogue, Speaker},
+    player::{chat::PlayerStartedChat, Player, PlayerState},
+    ui::dialogue::runner::RunnerFlags,
+    world::{
+        ending::EndingTriggered,
+        map::{generation::BitMap, TILE_SIZE},
+    },
+    GameState,
+};
+
+use super::{
+    command::{ConsoleCommand, COMMANDS},
+    Console,
+};
+
+// Where the player ends up relative to the NPC with `tp_npc` and `start_node`.
+const NPC_OFFSET: Vec2 = Vec2::new(0.0, -2.0 * TILE_SIZE);
+
+fn npc_position(
+    dialogue: NpcDialogue,
+    q_npcs: &Query<(&GlobalTransform, &Npc)>,
+    q_speakers: &Query<(&GlobalTransform, &Speaker)>,
+) -> Option<Vec2> {
+    q_npcs
+        .iter()
+        .find(|(_, npc)| npc.dialogue == dialogue)
+        .map(|(transform, _)| transform)
+        .or_else(|| {
+            q_speakers
+                .iter()
+                .find(|(_, speaker)| speaker.0 == dialogue)
+                .map(|(transform, _)| transform)
+        })
+        .map(|transform| transform.translation().truncate())
+}
+
+fn teleport_player(
+    mut console: ResMut<Console>,
+    mut q_player: Query<&mut Transform, With<Player>>,
+    q_npcs: Query<(&GlobalTransform, &Npc)>,
+    q_speakers: Query<(&GlobalTransform, &Speaker)>,
+    mut ev_console_command: EventReader<ConsoleCommand>,
+) {
+    for ev in ev_console_command.read() {
+        let pos = match ev {
+            ConsoleCommand::Teleport(pos) => *pos,
+            ConsoleCommand::TeleportToNpc(dialogue) => {
+                match npc_position(*dialogue, &q_npcs, &q_speakers) {
+                    Some(r) => r + NPC_OFFSET,
+                    None => {
+                        console.print(format!("{} isn't spawned", dialogue));
+                        continue;
+                    }
+                }
+            }
+            _ => continue,
+        };
+        let mut transform = match q_player.get_single_mut() {
+            Ok(r) => r,
+            Err(_) => return,
+        };
+
+        transform.translation = pos.extend(transform.translation.z);
+        console.print(format!("teleported to {} {}", pos.x, pos.y));
+    }
+}
+
+fn run_dialogue_commands(
+    mut console: ResMut<Console>,
+    mut q_player: Query<(&mut Transform, &mut Player)>,
+    q_npcs: Query<(&GlobalTransform, &Npc)>,
+    q_speakers: Query<(&GlobalTransform, &Speaker)>,
+    mut q_dialogue_runners: Query<(&mut DialogueRunner, &RunnerFlags)>,
+    mut ev_console_command: EventReader<ConsoleCommand>,
+    mut ev_player_started_chat: EventWriter<PlayerStartedChat>,
+    mut ev_ending_triggered: EventWriter<EndingTriggered>,
+) {
+    for ev in ev_console_command.read() {
+        match ev {
+            ConsoleCommand::SetVariable { name, value } => {
+                let mut count = 0;
+                for (mut runner, flags) in &mut q_dialogue_runners {
+                    let Some(dialogue) = flags.dialogue else {
+                        continue;
+                    };
+                    match runner
+                        .variable_storage_mut()
+                        .set(name.clone(), value.clone())
+                    {
+                        Ok(()) => count += 1,
+                        Err(err) => console.print(format!("{}, {}", dialogue, err)),
+                    }
+                }
+                // The runners are only spawned once the player talks to the NPC.
+                console.print(format!("set {} on {} dialogue runners", name, count));
+            }
+            ConsoleCommand::StartNode { dialogue, node } => {
+                if q_dialogue_runners.iter().any(|(_, flags)| flags.active) {
+                    console.print("stop the current chat first");
+                    continue;
+                }
+                let npc_pos = match npc_position(*dialogue, &q_npcs, &q_speakers) {
+                    Some(r) => r,
+                    None => {
+                        console.print(format!("{} isn't spawned", dialogue));
+                        continue;
+                    }
+                };
+                let (mut transform, mut player) = match q_player.get_single_mut() {
+                    Ok(r) => r,
+                    Err(_) => return,
+                };
+
+                transform.translation = (npc_pos + NPC_OFFSET).extend(transform.translation.z);
+                player.state = PlayerState::Talking;
+                ev_player_started_chat.send(PlayerStartedChat {
+                    dialogue: *dialogue,
+                    direction: -NPC_OFFSET,
+                    node: Some(node.clone()),
+                });
+                console.print(format!("started {} with {}", node, dialogue));
+            }
+            ConsoleCommand::TriggerEnding(dialogue) => {
+                ev_ending_triggered.send(EndingTriggered {
+                    dialogue: *dialogue,
+                });
+                console.print(format!("triggered the ending with {}", dialogue));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Start a new instance of the game, there is no way to tear down the current world.
+#[cfg(not(target_arch = "wasm32"))]
+fn restart_with_seed(seed: f32) -> std::io::Result<()> {
+    std::process::Command::new(std::env::current_exe()?)
+        .args([super::SEED_ARG, &seed.to_string()])
+        .spawn()
+        .map(|_| ())
+}
+
+fn run_world_commands(
+    mut console: ResMut<Console>,
+    bitmap: Res<BitMap>,
+    mut time: ResMut<Time<Virtual>>,
+    mut game_audio: ResMut<GameAudio>,
+    mut ev_console_command: EventReader<ConsoleCommand>,
+    #[cfg(not(target_arch = "wasm32"))] mut ev_app_exit: EventWriter<AppExit>,
+) {
+    for ev in ev_console_command.read() {
+        match ev {
+            ConsoleCommand::Help => {
+                for (name, args) in COMMANDS {
+                    console.print(format!("{} {}", name, args));
+                }
+            }
+            ConsoleCommand::Seed => {
+                console.print(format!("seed {}", bitmap.seed()));
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            ConsoleCommand::Regenerate(seed) => match restart_with_seed(*seed) {
+                Ok(()) => {
+                    ev_app_exit.send(AppExit::Success);
+                }
+                Err(err) => console.print(format!("failed to restart the game, {}", err)),
+            },
+            #[cfg(target_arch = "wasm32")]
+            ConsoleCommand::Regenerate(_) => {
+                console.print("regen isn't supported on the web, reload the page instead");
+            }
+            ConsoleCommand::Speed(None) => {
+                console.print(format!(
+                    "{:.1}s elapsed at {}x speed",
+                    time.elapsed_seconds(),
+                    time.relative_speed()
+                ));
+            }
+            ConsoleCommand::Speed(Some(factor)) => {
+                time.set_relative_speed(factor.max(0.0));
+                console.print(format!("game speed set to {}x", time.relative_speed()));
+            }
+            ConsoleCommand::Volume(None) => {
+                console.print(format!("volume {:.2}", game_audio.main_volume));
+            }
+            ConsoleCommand::Volume(Some(volume)) => {
+                game_audio.main_volume = volume.clamp(0.0, 1.0);
+                console.print(format!("volume set to {:.2}", game_audio.main_volume));
+            }
+            _ => {}
+        }
+    }
+}
+
+pub struct ConsoleExecutePlugin;
+
+impl Plugin for ConsoleExecutePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (teleport_player, run_dialogue_commands, run_world_commands)
+                .run_if(in_state(GameState::Gaming)),
+        );
+    }
+}
@@ -0,0 +1,340 @@
+#[cfg(test)]
+mod test;
+
+mod command;
+mod execute;
+
+use std::collections::VecDeque;
+
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput},
+        InputSystem,
+    },
+    prelude::*,
+};
+use bevy_yarnspinner::prelude::*;
+
+use crate::{
+    player::input::{PlayerInput, PlayerInputOverrideSystemSet},
+    world::map::generation::BitMap,
+    GameAssets, GameState,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::world::exploration::IgnoreSaves;
+
+use command::{complete, Completions, ConsoleCommand};
+
+// Passed when restarting the game with `regen`.
+const SEED_ARG: &str = "--seed";
+const LOG_LINES: usize = 12;
+const BACKGROUND_ALPHA: f32 = 0.85;
+const CONSOLE_PADDING: f32 = 12.0;
+const FONT_SIZE: f32 = 28.0;
+const LOG_COLOR: Color = Color::srgb(0.75, 0.75, 0.75);
+
+/// Toggled with the backtick, only exists in dev builds.
+#[derive(Resource, Default)]
+pub struct Console {
+    open: bool,
+    input: String,
+    log: VecDeque<String>,
+    history: Vec<String>,
+    /// The index into the history while going through it with the arrow keys.
+    browsing: Option<usize>,
+}
+
+impl Console {
+    pub fn print(&mut self, line: impl Into<String>) {
+        let line = line.into();
+        info!("console: {}", line);
+        self.log.push_back(line);
+        if self.log.len() > LOG_LINES {
+            self.log.pop_front();
+        }
+    }
+
+    /// Take the current input and remember it in the history.
+    fn submit(&mut self) -> String {
+        let line = std::mem::take(&mut self.input).trim().to_string();
+        self.browsing = None;
+        if !line.is_empty() && self.history.last() != Some(&line) {
+            self.history.push(line.clone());
+        }
+        line
+    }
+
+    fn history_previous(&mut self) {
+        if self.history.is_empty() {
+            return;
+        }
+        let index = match self.browsing {
+            Some(index) => index.saturating_sub(1),
+            None => self.history.len() - 1,
+        };
+        self.browsing = Some(index);
+        self.input.clone_from(&self.history[index]);
+    }
+
+    fn history_next(&mut self) {
+        let Some(index) = self.browsing else {
+            return;
+        };
+        if index + 1 < self.history.len() {
+            self.browsing = Some(index + 1);
+            self.input.clone_from(&self.history[index + 1]);
+        } else {
+            self.browsing = None;
+            self.input.clear();
+        }
+    }
+
+    /// Complete the last word if it's unambiguous, otherwise
+    /// complete as much as possible and print the candidates.
+    fn autocomplete(&mut self, completions: &Completions) {
+        let candidates = complete(&self.input, completions);
+        let Some(first) = candidates.first() else {
+            return;
+        };
+
+        let prefix_len = candidates
+            .iter()
+            .fold(first.chars().count(), |len, candidate| {
+                first
+                    .chars()
+                    .zip(candidate.chars())
+                    .take_while(|(a, b)| a == b)
+                    .count()
+                    .min(len)
+            });
+        let start = self
+            .input
+            .rfind(char::is_whitespace)
+            .map_or(0, |index| index + 1);
+        self.input.truncate(start);
+        self.input.extend(first.chars().take(prefix_len));
+
+        if candidates.len() == 1 {
+            self.input.push(' ');
+        } else {
+            self.print(candidates.join("  "));
+        }
+    }
+}
+
+#[derive(Component)]
+struct ConsoleRoot;
+#[derive(Component)]
+struct ConsoleLogText;
+#[derive(Component)]
+struct ConsoleInputText;
+
+/// The seed that `regen` restarts the game with.
+fn seed_from_args(mut args: impl Iterator<Item = String>) -> Option<f32> {
+    while let Some(arg) = args.next() {
+        if arg != SEED_ARG {
+            continue;
+        }
+        match args.next().map(|seed| seed.parse::<f32>()) {
+            Some(Ok(seed)) => return Some(seed),
+            _ => error!("expected a number after '{}'", SEED_ARG),
+        }
+    }
+    None
+}
+
+fn text_style(assets: &Res<GameAssets>, color: Color) -> TextStyle {
+    TextStyle {
+        font: assets.silver_font.clone(),
+        font_size: FONT_SIZE,
+        color,
+    }
+}
+
+fn spawn_console(mut commands: Commands, assets: Res<GameAssets>) {
+    let log = commands
+        .spawn((
+            ConsoleLogText,
+            Label,
+            TextBundle::from_section(String::new(), text_style(&assets, LOG_COLOR)),
+        ))
+        .id();
+    let input = commands
+        .spawn((
+            ConsoleInputText,
+            Label,
+            TextBundle::from_section(String::new(), text_style(&assets, Color::WHITE)),
+        ))
+        .id();
+
+    commands
+        .spawn((
+            ConsoleRoot,
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    top: Val::Px(0.0),
+                    left: Val::Px(0.0),
+                    padding: UiRect::all(Val::Px(CONSOLE_PADDING)),
+                    flex_direction: FlexDirection::Column,
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: Color::BLACK.with_alpha(BACKGROUND_ALPHA).into(),
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Global(300),
+                ..default()
+            },
+        ))
+        .push_children(&[log, input]);
+}
+
+fn collect_completions(mut completions: ResMut<Completions>, project: Res<YarnProject>) {
+    let Some(program) = &project.compilation().program else {
+        return;
+    };
+    completions.nodes = program.nodes.keys().cloned().collect();
+    completions.variables = program.initial_values.keys().cloned().collect();
+}
+
+fn toggle_console(keys: Res<ButtonInput<KeyCode>>, mut console: ResMut<Console>) {
+    if keys.just_pressed(KeyCode::Backquote) {
+        console.open = !console.open;
+    }
+}
+
+fn type_into_console(
+    mut console: ResMut<Console>,
+    completions: Res<Completions>,
+    mut ev_keyboard_input: EventReader<KeyboardInput>,
+    mut ev_console_command: EventWriter<ConsoleCommand>,
+) {
+    if !console.open {
+        ev_keyboard_input.clear();
+        return;
+    }
+
+    for ev in ev_keyboard_input.read() {
+        if !ev.state.is_pressed() {
+            continue;
+        }
+
+        match &ev.logical_key {
+            Key::Character(s) => {
+                let s = s.replace('`', "");
+                console.input.push_str(&s);
+            }
+            Key::Space => console.input.push(' '),
+            Key::Backspace => {
+                console.input.pop();
+            }
+            Key::ArrowUp => console.history_previous(),
+            Key::ArrowDown => console.history_next(),
+            Key::Tab => console.autocomplete(&completions),
+            Key::Escape => console.open = false,
+            Key::Enter => {
+                let line = console.submit();
+                if line.is_empty() {
+                    continue;
+                }
+                console.print(format!("> {}", line));
+                match ConsoleCommand::parse(&line) {
+                    Ok(command) => {
+                        ev_console_command.send(command);
+                    }
+                    Err(err) => console.print(err),
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The game shouldn't react to anything typed into the console,
+/// including the key that closed it.
+fn consume_input(
+    console: Res<Console>,
+    mut player_input: ResMut<PlayerInput>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut was_open: Local<bool>,
+) {
+    let consume = console.open || *was_open;
+    *was_open = console.open;
+    if !consume {
+        return;
+    }
+    *player_input = PlayerInput::default();
+    keys.reset_all();
+}
+
+fn update_console(
+    console: Res<Console>,
+    mut q_root: Query<&mut Visibility, With<ConsoleRoot>>,
+    mut q_log: Query<&mut Text, (With<ConsoleLogText>, Without<ConsoleInputText>)>,
+    mut q_input: Query<&mut Text, (With<ConsoleInputText>, Without<ConsoleLogText>)>,
+) {
+    if !console.is_changed() {
+        return;
+    }
+    let mut visibility = match q_root.get_single_mut() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    let mut log = match q_log.get_single_mut() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    let mut input = match q_input.get_single_mut() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    *visibility = if console.open {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    log.sections[0].value = console
+        .log
+        .iter()
+        .cloned()
+        .collect::<Vec<String>>()
+        .join("\n");
+    input.sections[0].value = format!("> {}_", console.input);
+}
+
+/// A developer console for commands like teleporting or starting dialogue nodes,
+/// see `command::COMMANDS`.
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        // This replaces the world of the save, which is left untouched.
+        if let Some(seed) = seed_from_args(std::env::args().skip(1)) {
+            app.insert_resource(BitMap::with_seed(seed));
+            #[cfg(not(target_arch = "wasm32"))]
+            app.insert_resource(IgnoreSaves);
+        }
+
+        app.add_plugins(execute::ConsoleExecutePlugin)
+            .init_resource::<Console>()
+            .init_resource::<Completions>()
+            .add_event::<ConsoleCommand>()
+            .add_systems(OnExit(GameState::AssetLoading), spawn_console)
+            .add_systems(
+                PreUpdate,
+                (toggle_console, type_into_console, consume_input)
+                    .chain()
+                    .after(InputSystem)
+                    .after(PlayerInputOverrideSystemSet),
+            )
+            .add_systems(
+                Update,
+                (
+                    collect_completions.run_if(resource_added::<YarnProject>),
+                    update_console,
+                ),
+            );
+    }
+}
@@ -0,0 +1,133 @@
+use bevy::prelude::*;
+use bevy_yarnspinner::prelude::*;
+
+use super::{
+    command::{complete, Completions, ConsoleCommand},
+    seed_from_args, Console,
+};
+use crate::npc::NpcDialogue;
+
+fn completions() -> Completions {
+    Completions {
+        nodes: vec!["OppaiIsLost".to_string(), "OppaiIsFound".to_string()],
+        variables: vec!["$talked_with_target_npc".to_string()],
+    }
+}
+
+#[test]
+fn parse_commands() {
+    assert_eq!(
+        ConsoleCommand::parse("tp 10 -20.5"),
+        Ok(ConsoleCommand::Teleport(Vec2::new(10.0, -20.5)))
+    );
+    assert_eq!(
+        ConsoleCommand::parse("  tp_npc   Jotem "),
+        Ok(ConsoleCommand::TeleportToNpc(NpcDialogue::Jotem))
+    );
+    assert_eq!(
+        ConsoleCommand::parse("set_var $talked_with_target_npc true"),
+        Ok(ConsoleCommand::SetVariable {
+            name: "$talked_with_target_npc".to_string(),
+            value: YarnValue::Boolean(true),
+        })
+    );
+    assert_eq!(
+        ConsoleCommand::parse("set_var $name \"Oppai the cat\""),
+        Ok(ConsoleCommand::SetVariable {
+            name: "$name".to_string(),
+            value: YarnValue::String("Oppai the cat".to_string()),
+        })
+    );
+    assert_eq!(
+        ConsoleCommand::parse("start_node Jotem OppaiIsLost"),
+        Ok(ConsoleCommand::StartNode {
+            dialogue: NpcDialogue::Jotem,
+            node: "OppaiIsLost".to_string(),
+        })
+    );
+    assert_eq!(
+        ConsoleCommand::parse("trigger_ending Isabelle"),
+        Ok(ConsoleCommand::TriggerEnding(NpcDialogue::Isabelle))
+    );
+    assert_eq!(
+        ConsoleCommand::parse("regen 1234"),
+        Ok(ConsoleCommand::Regenerate(1234.0))
+    );
+    assert_eq!(
+        ConsoleCommand::parse("speed 2"),
+        Ok(ConsoleCommand::Speed(Some(2.0)))
+    );
+    assert_eq!(
+        ConsoleCommand::parse("volume"),
+        Ok(ConsoleCommand::Volume(None))
+    );
+}
+
+#[test]
+fn invalid_commands() {
+    assert!(ConsoleCommand::parse("").is_err());
+    assert!(ConsoleCommand::parse("fly").is_err());
+    assert!(ConsoleCommand::parse("tp 10").is_err());
+    assert!(ConsoleCommand::parse("tp ten 20").is_err());
+    assert!(ConsoleCommand::parse("tp_npc Oppai").is_err());
+    assert!(ConsoleCommand::parse("set_var talked true").is_err());
+    assert!(ConsoleCommand::parse("seed 1").is_err());
+    assert!(ConsoleCommand::parse("time 2").is_err());
+}
+
+#[test]
+fn autocomplete() {
+    let completions = completions();
+    assert_eq!(complete("tp", &completions), vec!["tp", "tp_npc"]);
+    assert_eq!(
+        complete("tp_npc Io", &completions),
+        vec!["Ionas", "IonasAndAntonius"]
+    );
+    assert_eq!(
+        complete("start_node Jotem Oppai", &completions),
+        vec!["OppaiIsFound", "OppaiIsLost"]
+    );
+    assert!(complete("tp 10 ", &completions).is_empty());
+
+    let mut console = Console {
+        input: "tri".to_string(),
+        ..default()
+    };
+    console.autocomplete(&completions);
+    assert_eq!(console.input, "trigger_ending ");
+
+    console.input = "start_node Jotem O".to_string();
+    console.autocomplete(&completions);
+    assert_eq!(console.input, "start_node Jotem OppaiIs");
+}
+
+#[test]
+fn history() {
+    let mut console = Console::default();
+    for line in ["seed", "volume", "volume"] {
+        console.input = line.to_string();
+        console.submit();
+    }
+    assert_eq!(console.history, vec!["seed", "volume"]);
+
+    console.history_previous();
+    assert_eq!(console.input, "volume");
+    console.history_previous();
+    console.history_previous();
+    assert_eq!(console.input, "seed");
+    console.history_next();
+    assert_eq!(console.input, "volume");
+    console.history_next();
+    assert_eq!(console.input, "");
+}
+
+#[test]
+fn seed_args() {
+    let args = |args: &[&str]| args.iter().map(|a| a.to_string()).collect::<Vec<String>>();
+    assert_eq!(seed_from_args(args(&[]).into_iter()), None);
+    assert_eq!(
+        seed_from_args(args(&["--seed", "123456790"]).into_iter()),
+        Some(123456790.0)
+    );
+    assert_eq!(seed_from_args(args(&["--seed", "abc"]).into_iter()), None);
+}
@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod test;
+
+mod overlay;
+mod stats;
+
+use bevy::{prelude::*, utils::HashSet};
+use bevy_rapier2d::prelude::*;
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter};
+
+use crate::player::input::PlayerInput;
+
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct DebugActive(pub bool);
+
+/// The parts of the debug overlay, each can be toggled with its number key
+/// while the debug mode is active.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Display, EnumIter)]
+#[strum(serialize_all = "title_case")]
+pub enum DebugLayer {
+    Physics,
+    Stats,
+    Vertices,
+    Edges,
+    ControlPoints,
+    Hotspots,
+    Chunks,
+    TileTypes,
+    ReservedAreas,
+}
+
+impl DebugLayer {
+    fn key(self) -> KeyCode {
+        match self {
+            DebugLayer::Physics => KeyCode::Digit1,
+            DebugLayer::Stats => KeyCode::Digit2,
+            DebugLayer::Vertices => KeyCode::Digit3,
+            DebugLayer::Edges => KeyCode::Digit4,
+            DebugLayer::ControlPoints => KeyCode::Digit5,
+            DebugLayer::Hotspots => KeyCode::Digit6,
+            DebugLayer::Chunks => KeyCode::Digit7,
+            DebugLayer::TileTypes => KeyCode::Digit8,
+            DebugLayer::ReservedAreas => KeyCode::Digit9,
+        }
+    }
+
+    fn key_label(self) -> char {
+        char::from_digit(self as u32 + 1, 10).unwrap_or('?')
+    }
+}
+
+#[derive(Resource, Deref)]
+pub struct DebugLayers(HashSet<DebugLayer>);
+
+impl Default for DebugLayers {
+    /// Everything except the tile types, they cover up the whole map.
+    fn default() -> Self {
+        Self(HashSet::from_iter([
+            DebugLayer::Physics,
+            DebugLayer::Stats,
+            DebugLayer::Vertices,
+            DebugLayer::Edges,
+            DebugLayer::ControlPoints,
+            DebugLayer::Hotspots,
+            DebugLayer::Chunks,
+            DebugLayer::ReservedAreas,
+        ]))
+    }
+}
+
+impl DebugLayers {
+    pub fn toggle(&mut self, layer: DebugLayer) {
+        if !self.0.remove(&layer) {
+            self.0.insert(layer);
+        }
+    }
+}
+
+/// Run condition, the debug mode is active and the layer is shown.
+pub fn debug_layer_active(
+    layer: DebugLayer,
+) -> impl Fn(Res<DebugActive>, Res<DebugLayers>) -> bool + Clone {
+    move |debug_active: Res<DebugActive>, layers: Res<DebugLayers>| {
+        **debug_active && layers.contains(&layer)
+    }
+}
+
+fn toggle_debug_mod(player_input: Res<PlayerInput>, mut debug_active: ResMut<DebugActive>) {
+    if player_input.toggle_debug {
+        **debug_active = !**debug_active;
+    }
+}
+
+fn toggle_debug_layers(
+    keys: Res<ButtonInput<KeyCode>>,
+    debug_active: Res<DebugActive>,
+    mut layers: ResMut<DebugLayers>,
+) {
+    if !**debug_active {
+        return;
+    }
+
+    for layer in DebugLayer::iter() {
+        if keys.just_pressed(layer.key()) {
+            layers.toggle(layer);
+        }
+    }
+}
+
+fn toggle_rapier_debug(
+    mut debug_context: ResMut<DebugRenderContext>,
+    debug_active: Res<DebugActive>,
+    layers: Res<DebugLayers>,
+) {
+    let enabled = **debug_active && layers.contains(&DebugLayer::Physics);
+    if debug_context.enabled != enabled {
+        debug_context.enabled = enabled;
+    }
+}
+
+pub struct DebugPlugin;
+
+impl Plugin for DebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((overlay::DebugOverlayPlugin, stats::DebugStatsPlugin))
+            .init_resource::<DebugActive>()
+            .init_resource::<DebugLayers>()
+            .add_systems(
+                Update,
+                (toggle_debug_mod, toggle_debug_layers, toggle_rapier_debug).chain(),
+            );
+    }
+}
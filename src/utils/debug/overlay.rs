@@ -0,0 +1,215 @@
+use bevy::prelude::*;
+
+use crate::{
+    world::{
+        map::{
+            chunk_manager::{ChunkIndex, ChunkManager},
+            generation::{BitMap, NPC_HOTSPOTS},
+            CHUNK_SIZE, TILE_SIZE,
+        },
+        MainCamera,
+    },
+    GameAssets, GameState,
+};
+
+use super::{debug_layer_active, DebugActive, DebugLayer, DebugLayers};
+
+const VERTEX_COLOR: Color = Color::srgb(1.0, 1.0, 1.0);
+const SPANNING_EDGE_COLOR: Color = Color::srgb(0.95, 0.25, 0.85);
+const OUTER_EDGE_COLOR: Color = Color::srgb(0.3, 0.75, 0.95);
+const CURVE_COLOR: Color = Color::srgb(0.95, 0.85, 0.3);
+const CONTROL_POINT_COLOR: Color = Color::srgb(0.95, 0.55, 0.15);
+const HOTSPOT_COLOR: Color = Color::srgb(0.2, 0.95, 0.4);
+const CHUNK_COLOR: Color = Color::srgb(0.6, 0.6, 0.6);
+
+const GRASS_COLOR: Color = Color::srgb(0.42, 0.61, 0.31);
+const PATH_COLOR: Color = Color::srgb(0.77, 0.64, 0.45);
+const WATER_COLOR: Color = Color::srgb(0.27, 0.55, 0.71);
+const BRIDGE_COLOR: Color = Color::srgb(0.55, 0.38, 0.24);
+
+const VERTEX_RADIUS: f32 = 6.0;
+const CONTROL_POINT_RADIUS: f32 = 4.0;
+const HOTSPOT_RADIUS: f32 = 24.0;
+const CURVE_SEGMENTS: usize = 32;
+const CHUNK_LABEL_SIZE: f32 = 24.0;
+// Relative to the chunk, which itself sits far behind everything else.
+const CHUNK_LABEL_Z: f32 = 1700.0;
+
+#[derive(Component)]
+struct ChunkLabel;
+
+fn draw_vertices(mut gizmos: Gizmos, bitmap: Res<BitMap>) {
+    for v in bitmap.vertices() {
+        gizmos.circle_2d(*v, VERTEX_RADIUS, VERTEX_COLOR);
+    }
+}
+
+fn draw_edges(mut gizmos: Gizmos, bitmap: Res<BitMap>) {
+    let vertices = bitmap.vertices();
+    for &(u, v) in bitmap.edges() {
+        let color = if bitmap.is_spanning_edge((u, v)) {
+            SPANNING_EDGE_COLOR
+        } else {
+            OUTER_EDGE_COLOR
+        };
+        gizmos.line_2d(vertices[u], vertices[v], color);
+    }
+}
+
+fn draw_control_points(mut gizmos: Gizmos, bitmap: Res<BitMap>) {
+    for curve in bitmap.path_curves() {
+        let [p1, p2, c1, c2] = curve.map(|p| p * TILE_SIZE);
+        let positions = CubicBezier::new([[p1, c1, c2, p2]])
+            .to_curve()
+            .iter_positions(CURVE_SEGMENTS)
+            .collect::<Vec<Vec2>>();
+        gizmos.linestrip_2d(positions, CURVE_COLOR);
+
+        gizmos.line_2d(p1, c1, CONTROL_POINT_COLOR);
+        gizmos.line_2d(p2, c2, CONTROL_POINT_COLOR);
+        gizmos.circle_2d(c1, CONTROL_POINT_RADIUS, CONTROL_POINT_COLOR);
+        gizmos.circle_2d(c2, CONTROL_POINT_RADIUS, CONTROL_POINT_COLOR);
+    }
+}
+
+fn draw_hotspots(mut gizmos: Gizmos, bitmap: Res<BitMap>) {
+    for hotspot in bitmap.get_furthest_hotspots(NPC_HOTSPOTS) {
+        gizmos.circle_2d(hotspot, HOTSPOT_RADIUS, HOTSPOT_COLOR);
+    }
+}
+
+/// The world area covered by the chunk, tiles are centered on their position.
+fn chunk_rect(chunk_pos: IVec2) -> Rect {
+    let chunk_size = CHUNK_SIZE as f32 * TILE_SIZE;
+    let min = chunk_pos.as_vec2() * chunk_size - TILE_SIZE / 2.0;
+    Rect::from_corners(min, min + chunk_size)
+}
+
+fn draw_chunk_borders(mut gizmos: Gizmos, q_chunks: Query<&ChunkIndex>) {
+    for chunk in &q_chunks {
+        let rect = chunk_rect(**chunk);
+        gizmos.rect_2d(rect.center(), 0.0, rect.size(), CHUNK_COLOR);
+    }
+}
+
+fn spawn_chunk_labels(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    q_chunks: Query<(Entity, &ChunkIndex), Added<ChunkIndex>>,
+) {
+    for (entity, chunk) in &q_chunks {
+        let rect = chunk_rect(**chunk);
+        let label = commands
+            .spawn((
+                ChunkLabel,
+                Text2dBundle {
+                    text: Text::from_section(
+                        format!("{} {}", chunk.x, chunk.y),
+                        TextStyle {
+                            font: assets.silver_font.clone(),
+                            font_size: CHUNK_LABEL_SIZE,
+                            color: CHUNK_COLOR,
+                        },
+                    ),
+                    transform: Transform::from_translation(
+                        (rect.size() / 2.0 - TILE_SIZE / 2.0).extend(CHUNK_LABEL_Z),
+                    ),
+                    visibility: Visibility::Hidden,
+                    ..default()
+                },
+            ))
+            .id();
+        commands.entity(entity).add_child(label);
+    }
+}
+
+fn toggle_chunk_labels(
+    debug_active: Res<DebugActive>,
+    layers: Res<DebugLayers>,
+    mut q_labels: Query<&mut Visibility, With<ChunkLabel>>,
+) {
+    let visibility = if **debug_active && layers.contains(&DebugLayer::Chunks) {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+
+    for mut label_visibility in &mut q_labels {
+        if *label_visibility != visibility {
+            *label_visibility = visibility;
+        }
+    }
+}
+
+fn tile_color(bitmap: &mut BitMap, v: IVec2) -> Color {
+    if bitmap.get_bridge_flag(v) {
+        BRIDGE_COLOR
+    } else if bitmap.get_path_flag(v) {
+        PATH_COLOR
+    } else if bitmap.is_water(v) {
+        WATER_COLOR
+    } else {
+        GRASS_COLOR
+    }
+}
+
+/// Only the tiles on screen, drawing all spawned chunks is too slow.
+fn draw_tile_types(
+    mut gizmos: Gizmos,
+    mut bitmap: ResMut<BitMap>,
+    chunk_manager: Res<ChunkManager>,
+    q_camera: Query<(&Transform, &OrthographicProjection), With<MainCamera>>,
+) {
+    let (transform, projection) = match q_camera.get_single() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    let spawned_rect = match chunk_manager.spawned_rect() {
+        Some(r) => r,
+        None => return,
+    };
+
+    let camera_pos = transform.translation.truncate();
+    let view = Rect::from_corners(
+        camera_pos + projection.area.min,
+        camera_pos + projection.area.max,
+    )
+    .intersect(spawned_rect);
+    // The bitmap vertices lie on the bottom left corner of the rendered tiles.
+    let min = (view.min / TILE_SIZE + 0.5).floor().as_ivec2();
+    let max = (view.max / TILE_SIZE + 0.5).ceil().as_ivec2();
+
+    for x in min.x..=max.x {
+        for y in min.y..=max.y {
+            let v = IVec2::new(x, y);
+            let pos = (v.as_vec2() - 0.5) * TILE_SIZE;
+            gizmos.rect_2d(
+                pos,
+                0.0,
+                Vec2::splat(TILE_SIZE / 2.0),
+                tile_color(&mut bitmap, v),
+            );
+        }
+    }
+}
+
+/// Shows how the world was generated, see `DebugLayer`.
+pub struct DebugOverlayPlugin;
+
+impl Plugin for DebugOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                draw_vertices.run_if(debug_layer_active(DebugLayer::Vertices)),
+                draw_edges.run_if(debug_layer_active(DebugLayer::Edges)),
+                draw_control_points.run_if(debug_layer_active(DebugLayer::ControlPoints)),
+                draw_hotspots.run_if(debug_layer_active(DebugLayer::Hotspots)),
+                draw_chunk_borders.run_if(debug_layer_active(DebugLayer::Chunks)),
+                draw_tile_types.run_if(debug_layer_active(DebugLayer::TileTypes)),
+                (spawn_chunk_labels, toggle_chunk_labels).chain(),
+            )
+                .run_if(in_state(GameState::Gaming)),
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use bevy::{
+    diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    ecs::entity::Entities,
+    prelude::*,
+};
+use strum::IntoEnumIterator;
+
+use crate::{GameAssets, GameState};
+
+use super::{DebugActive, DebugLayer, DebugLayers};
+
+const BACKGROUND_ALPHA: f32 = 0.8;
+const PANEL_MARGIN: f32 = 20.0;
+const FONT_SIZE: f32 = 28.0;
+const INACTIVE_COLOR: Color = Color::srgb(0.55, 0.55, 0.55);
+
+#[derive(Component)]
+struct StatsRoot;
+#[derive(Component)]
+struct StatsText;
+
+fn text_style(assets: &Res<GameAssets>, color: Color) -> TextStyle {
+    TextStyle {
+        font: assets.silver_font.clone(),
+        font_size: FONT_SIZE,
+        color,
+    }
+}
+
+fn spawn_stats_panel(mut commands: Commands) {
+    let text = commands
+        .spawn((StatsText, Label, TextBundle::default()))
+        .id();
+
+    commands
+        .spawn((
+            StatsRoot,
+            NodeBundle {
+                style: Style {
+                    bottom: Val::Px(PANEL_MARGIN),
+                    left: Val::Px(PANEL_MARGIN),
+                    padding: UiRect::all(Val::Px(PANEL_MARGIN / 2.0)),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                background_color: Color::BLACK.with_alpha(BACKGROUND_ALPHA).into(),
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Global(200),
+                ..default()
+            },
+        ))
+        .add_child(text);
+}
+
+fn toggle_stats_panel(
+    debug_active: Res<DebugActive>,
+    mut q_root: Query<&mut Visibility, With<StatsRoot>>,
+) {
+    let mut visibility = match q_root.get_single_mut() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    let target = if **debug_active {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    if *visibility != target {
+        *visibility = target;
+    }
+}
+
+/// The FPS and entity count (if the stats layer is active),
+/// followed by all the layers and their keys.
+fn update_stats_text(
+    assets: Res<GameAssets>,
+    debug_active: Res<DebugActive>,
+    layers: Res<DebugLayers>,
+    diagnostics: Res<DiagnosticsStore>,
+    entities: &Entities,
+    mut q_text: Query<&mut Text, With<StatsText>>,
+) {
+    if !**debug_active {
+        return;
+    }
+    let mut text = match q_text.get_single_mut() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    let white = text_style(&assets, Color::WHITE);
+    let grey = text_style(&assets, INACTIVE_COLOR);
+    let mut sections = Vec::new();
+
+    if layers.contains(&DebugLayer::Stats) {
+        let fps = diagnostics
+            .get(&FrameTimeDiagnosticsPlugin::FPS)
+            .and_then(|fps| fps.smoothed())
+            .unwrap_or_default();
+        sections.push(TextSection::new(
+            format!("FPS: {:.0}\nEntities: {}\n\n", fps, entities.len()),
+            white.clone(),
+        ));
+    }
+
+    for layer in DebugLayer::iter() {
+        let style = if layers.contains(&layer) {
+            white.clone()
+        } else {
+            grey.clone()
+        };
+        sections.push(TextSection::new(
+            format!("[{}] {}\n", layer.key_label(), layer),
+            style,
+        ));
+    }
+
+    text.sections = sections;
+}
+
+pub struct DebugStatsPlugin;
+
+impl Plugin for DebugStatsPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin);
+        }
+
+        app.add_systems(OnExit(GameState::AssetLoading), spawn_stats_panel)
+            .add_systems(
+                Update,
+                (toggle_stats_panel, update_stats_text)
+                    .chain()
+                    .run_if(resource_exists::<GameAssets>),
+            );
+    }
+}
@@ -0,0 +1,25 @@
+use strum::IntoEnumIterator;
+
+use super::{DebugLayer, DebugLayers};
+
+#[test]
+fn layer_keys() {
+    let labels: String = DebugLayer::iter().map(|layer| layer.key_label()).collect();
+    assert_eq!(labels, "123456789");
+    assert_eq!(DebugLayer::ControlPoints.to_string(), "Control Points");
+}
+
+#[test]
+fn toggle_layers() {
+    let mut layers = DebugLayers::default();
+    assert!(layers.contains(&DebugLayer::Edges));
+    assert!(!layers.contains(&DebugLayer::TileTypes));
+
+    layers.toggle(DebugLayer::Edges);
+    layers.toggle(DebugLayer::TileTypes);
+    assert!(!layers.contains(&DebugLayer::Edges));
+    assert!(layers.contains(&DebugLayer::TileTypes));
+
+    layers.toggle(DebugLayer::Edges);
+    assert!(layers.contains(&DebugLayer::Edges));
+}
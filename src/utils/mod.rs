@@ -1,6 +1,12 @@
+#[cfg(debug_assertions)]
+mod console;
 mod debug;
+#[cfg(not(target_arch = "wasm32"))]
+mod replay;
+mod rng;
 
-pub use debug::DebugActive;
+pub use debug::{debug_layer_active, DebugActive, DebugLayer};
+pub use rng::{RngStream, SeededRng};
 
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
@@ -11,7 +17,11 @@ pub struct UtilsPlugin;
 
 impl Plugin for UtilsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(debug::DebugPlugin);
+        app.add_plugins((debug::DebugPlugin, rng::SeededRngPlugin));
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_plugins(replay::ReplayPlugin);
+        #[cfg(debug_assertions)]
+        app.add_plugins(console::ConsolePlugin);
     }
 }
 
@@ -0,0 +1,360 @@
+#[cfg(test)]
+mod test;
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+    time::Duration,
+};
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use crate::{
+    player::input::{MouseWorldCoords, PlayerInput, PlayerInputOverrideSystemSet},
+    world::{exploration::IgnoreSaves, map::generation::BitMap},
+    GameState,
+};
+
+const HEADER: &str = "lost-oppai replay 2";
+const FLUSH_INTERVAL: u32 = 60;
+const FLAGS_COUNT: usize = 13;
+
+/// Started with `--record <path>` or `--replay <path>`.
+#[derive(Debug, PartialEq)]
+enum ReplayMode {
+    Record(PathBuf),
+    Play(PathBuf),
+}
+
+impl ReplayMode {
+    fn from_args(mut args: impl Iterator<Item = String>) -> Option<Self> {
+        while let Some(arg) = args.next() {
+            let mode: fn(PathBuf) -> Self = match arg.as_str() {
+                "--record" => ReplayMode::Record,
+                "--replay" => ReplayMode::Play,
+                _ => continue,
+            };
+            match args.next() {
+                Some(path) => return Some(mode(PathBuf::from(path))),
+                None => error!("missing path after '{}'", arg),
+            }
+        }
+        None
+    }
+}
+
+/// Everything that's needed to play back one frame.
+#[derive(Debug, PartialEq)]
+struct ReplayFrame {
+    input: PlayerInput,
+    mouse: Vec2,
+    /// How long the frame took in the recording,
+    /// otherwise the physics and timers wouldn't line up.
+    delta: Duration,
+}
+
+impl ReplayFrame {
+    /// The axes followed by all the flags as a string of `0`s and `1`s,
+    /// the mouse position and the frame time in nanoseconds.
+    fn to_line(&self) -> String {
+        let PlayerInput {
+            move_direction,
+            scroll,
+            running,
+            escape,
+            dialogue,
+            dialogue_direction,
+            click,
+            toggle_fullscreen,
+            toggle_debug,
+            toggle_journal,
+            toggle_inventory,
+            toggle_minimap,
+            toggle_npc_indicators,
+            toggle_photo_mode,
+            cycle_photo_filter,
+            cycle_viewport_mode,
+        } = &self.input;
+        let flags: [&bool; FLAGS_COUNT] = [
+            running,
+            escape,
+            dialogue,
+            click,
+            toggle_fullscreen,
+            toggle_debug,
+            toggle_journal,
+            toggle_inventory,
+            toggle_minimap,
+            toggle_npc_indicators,
+            toggle_photo_mode,
+            cycle_photo_filter,
+            cycle_viewport_mode,
+        ];
+        let flags: String = flags
+            .iter()
+            .map(|flag| if **flag { '1' } else { '0' })
+            .collect();
+
+        format!(
+            "{} {} {} {} {} {} {} {}",
+            move_direction.x,
+            move_direction.y,
+            scroll,
+            dialogue_direction,
+            flags,
+            self.mouse.x,
+            self.mouse.y,
+            self.delta.as_nanos()
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let [x, y, scroll, dialogue_direction, flags, mouse_x, mouse_y, delta] = parts[..] else {
+            return None;
+        };
+        let flags = flags
+            .chars()
+            .map(|c| match c {
+                '0' => Some(false),
+                '1' => Some(true),
+                _ => None,
+            })
+            .collect::<Option<Vec<bool>>>()?;
+        if flags.len() != FLAGS_COUNT {
+            return None;
+        }
+        // Fields are evaluated in order, so this is the same order as in `to_line`.
+        let mut flags = flags.into_iter();
+
+        Some(Self {
+            input: PlayerInput {
+                running: flags.next()?,
+                escape: flags.next()?,
+                dialogue: flags.next()?,
+                click: flags.next()?,
+                toggle_fullscreen: flags.next()?,
+                toggle_debug: flags.next()?,
+                toggle_journal: flags.next()?,
+                toggle_inventory: flags.next()?,
+                toggle_minimap: flags.next()?,
+                toggle_npc_indicators: flags.next()?,
+                toggle_photo_mode: flags.next()?,
+                cycle_photo_filter: flags.next()?,
+                cycle_viewport_mode: flags.next()?,
+                move_direction: Vec2::new(x.parse().ok()?, y.parse().ok()?),
+                scroll: scroll.parse().ok()?,
+                dialogue_direction: dialogue_direction.parse().ok()?,
+            },
+            mouse: Vec2::new(mouse_x.parse().ok()?, mouse_y.parse().ok()?),
+            delta: Duration::from_nanos(delta.parse().ok()?),
+        })
+    }
+}
+
+/// A plain text format, the header and seed followed by one line per frame.
+/// The seed is stored as the bits of the `f32`, see `BitMap::seed`.
+fn parse_replay(contents: &str) -> Result<(f32, Vec<ReplayFrame>), String> {
+    let mut lines = contents.lines();
+    if lines.next() != Some(HEADER) {
+        return Err(format!("expected the header '{}'", HEADER));
+    }
+    let seed = lines
+        .next()
+        .and_then(|l| l.strip_prefix("seed "))
+        .and_then(|s| s.trim().parse::<u32>().ok())
+        .ok_or("expected the seed on the second line")?;
+
+    let frames = lines
+        .enumerate()
+        .map(|(i, line)| {
+            ReplayFrame::from_line(line).ok_or(format!("invalid frame {}, '{}'", i, line))
+        })
+        .collect::<Result<Vec<ReplayFrame>, String>>()?;
+    Ok((f32::from_bits(seed), frames))
+}
+
+#[derive(Resource)]
+struct Recording {
+    path: PathBuf,
+    writer: Option<BufWriter<File>>,
+    frame: u32,
+}
+
+#[derive(Resource)]
+struct Playback {
+    frames: std::vec::IntoIter<ReplayFrame>,
+}
+
+fn start_recording(mut recording: ResMut<Recording>, bitmap: Res<BitMap>) {
+    if recording.writer.is_some() {
+        return;
+    }
+
+    let file = match File::create(&recording.path) {
+        Ok(r) => r,
+        Err(err) => {
+            error!(
+                "failed to create replay {}, {}",
+                recording.path.display(),
+                err
+            );
+            return;
+        }
+    };
+    let mut writer = BufWriter::new(file);
+    if let Err(err) = writeln!(writer, "{}\nseed {}", HEADER, bitmap.seed().to_bits()) {
+        error!("failed to write replay, {}", err);
+        return;
+    }
+    info!("recording replay to {}", recording.path.display());
+    recording.writer = Some(writer);
+}
+
+fn record_frame(
+    mut recording: ResMut<Recording>,
+    time: Res<Time<Real>>,
+    player_input: Res<PlayerInput>,
+    mouse_coords: Res<MouseWorldCoords>,
+) {
+    let frame = ReplayFrame {
+        input: player_input.clone(),
+        mouse: mouse_coords.0,
+        delta: time.delta(),
+    };
+    recording.frame += 1;
+    let flush = recording.frame.is_multiple_of(FLUSH_INTERVAL);
+    let Some(writer) = recording.writer.as_mut() else {
+        return;
+    };
+
+    let result = writeln!(writer, "{}", frame.to_line()).and_then(|_| {
+        if flush {
+            writer.flush()
+        } else {
+            Ok(())
+        }
+    });
+    if let Err(err) = result {
+        error!("failed to write replay, stopping the recording, {}", err);
+        recording.writer = None;
+    }
+}
+
+fn finish_recording(mut recording: ResMut<Recording>) {
+    if let Some(mut writer) = recording.writer.take() {
+        if let Err(err) = writer.flush() {
+            error!("failed to write replay, {}", err);
+        }
+    }
+}
+
+/// The main menu isn't part of the replay.
+fn skip_main_menu(mut next_state: ResMut<NextState<GameState>>) {
+    next_state.set(GameState::Gaming);
+}
+
+fn play_frame(
+    mut commands: Commands,
+    mut playback: ResMut<Playback>,
+    mut player_input: ResMut<PlayerInput>,
+    mut mouse_coords: ResMut<MouseWorldCoords>,
+) {
+    match playback.frames.next() {
+        Some(frame) => {
+            *player_input = frame.input;
+            mouse_coords.0 = frame.mouse;
+            // The time is updated at the very start of the frame, so this is for the next one.
+            if let Some(next) = playback.frames.as_slice().first() {
+                commands.insert_resource(TimeUpdateStrategy::ManualDuration(next.delta));
+            }
+        }
+        None => {
+            info!("replay finished, handing back control");
+            commands.remove_resource::<Playback>();
+            commands.insert_resource(TimeUpdateStrategy::Automatic);
+        }
+    }
+}
+
+/// Records the `PlayerInput` and the frame time of every frame together with the world seed,
+/// or plays a recording back, see `ReplayMode`.
+/// Both start once the game does, the playback uses the recorded frame times
+/// so that movement, collisions and dialogue play out exactly the same.
+///
+/// Input that doesn't go through `PlayerInput` (e.g. clicking dialogue options)
+/// and the particles (their randomness is internal to `bevy_particle_systems`)
+/// aren't reproduced.
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        let Some(mode) = ReplayMode::from_args(std::env::args().skip(1)) else {
+            return;
+        };
+
+        let in_game = in_state(GameState::Gaming).or_else(in_state(GameState::Ending));
+        match mode {
+            ReplayMode::Record(path) => {
+                // The replay starts from the generated world, so the recording has to as well.
+                app.insert_resource(IgnoreSaves)
+                    .insert_resource(Recording {
+                        path,
+                        writer: None,
+                        frame: 0,
+                    })
+                    .add_systems(
+                        OnEnter(GameState::Gaming),
+                        (start_recording, record_frame).chain(),
+                    )
+                    .add_systems(
+                        PreUpdate,
+                        record_frame
+                            .in_set(PlayerInputOverrideSystemSet)
+                            .run_if(in_game),
+                    )
+                    .add_systems(Last, finish_recording.run_if(on_event::<AppExit>()));
+            }
+            ReplayMode::Play(path) => {
+                let (seed, frames) = match std::fs::read_to_string(&path)
+                    .map_err(|err| err.to_string())
+                    .and_then(|contents| parse_replay(&contents))
+                {
+                    Ok(r) => r,
+                    Err(err) => {
+                        error!("failed to load replay {}, {}", path.display(), err);
+                        return;
+                    }
+                };
+                info!(
+                    "playing back {} frames from {}",
+                    frames.len(),
+                    path.display()
+                );
+
+                // The frames until the game starts (e.g. loading) aren't part of the recording.
+                if let Some(frame) = frames.first() {
+                    app.insert_resource(TimeUpdateStrategy::ManualDuration(frame.delta));
+                }
+                // This replaces the world of the save, which is left untouched.
+                app.insert_resource(BitMap::with_seed(seed))
+                    .insert_resource(IgnoreSaves)
+                    .insert_resource(Playback {
+                        frames: frames.into_iter(),
+                    })
+                    .add_systems(OnEnter(GameState::MainMenu), skip_main_menu)
+                    .add_systems(
+                        OnEnter(GameState::Gaming),
+                        play_frame.run_if(resource_exists::<Playback>),
+                    )
+                    .add_systems(
+                        PreUpdate,
+                        play_frame
+                            .in_set(PlayerInputOverrideSystemSet)
+                            .run_if(in_game.and_then(resource_exists::<Playback>)),
+                    );
+            }
+        }
+    }
+}
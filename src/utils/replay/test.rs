@@ -0,0 +1,165 @@
+use std::{path::PathBuf, time::Duration};
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use rand::Rng;
+
+use super::{parse_replay, play_frame, Playback, ReplayFrame, ReplayMode, HEADER};
+use crate::{
+    player::input::{MouseWorldCoords, PlayerInput},
+    utils::{RngStream, SeededRng},
+};
+
+fn args(args: &[&str]) -> impl Iterator<Item = String> {
+    args.iter()
+        .map(|a| a.to_string())
+        .collect::<Vec<String>>()
+        .into_iter()
+}
+
+fn frame() -> ReplayFrame {
+    ReplayFrame {
+        input: PlayerInput {
+            move_direction: Vec2::new(0.70710677, -0.70710677),
+            scroll: -1.0,
+            running: true,
+            dialogue: true,
+            dialogue_direction: -1,
+            click: true,
+            toggle_photo_mode: true,
+            cycle_viewport_mode: true,
+            ..default()
+        },
+        mouse: Vec2::new(-123.456, 7.5),
+        delta: Duration::from_nanos(16_683_334),
+    }
+}
+
+#[test]
+fn mode_from_args() {
+    assert_eq!(ReplayMode::from_args(args(&[])), None);
+    assert_eq!(
+        ReplayMode::from_args(args(&["--record", "bug.replay"])),
+        Some(ReplayMode::Record(PathBuf::from("bug.replay")))
+    );
+    assert_eq!(
+        ReplayMode::from_args(args(&["--foo", "--replay", "bug.replay"])),
+        Some(ReplayMode::Play(PathBuf::from("bug.replay")))
+    );
+    assert_eq!(ReplayMode::from_args(args(&["--replay"])), None);
+}
+
+#[test]
+fn frame_round_trip() {
+    let frame = frame();
+    assert_eq!(ReplayFrame::from_line(&frame.to_line()), Some(frame));
+
+    let idle = ReplayFrame {
+        input: PlayerInput::default(),
+        mouse: Vec2::ZERO,
+        delta: Duration::ZERO,
+    };
+    assert_eq!(ReplayFrame::from_line(&idle.to_line()), Some(idle));
+}
+
+#[test]
+fn invalid_frames() {
+    let line = frame().to_line();
+    assert_eq!(ReplayFrame::from_line(""), None);
+    assert_eq!(ReplayFrame::from_line(&line.replace('1', "2")), None);
+    assert_eq!(ReplayFrame::from_line(&format!("{} 0", line)), None);
+}
+
+#[test]
+fn replay_round_trip() {
+    let seed = 123456.0_f32;
+    let contents = format!(
+        "{}\nseed {}\n{}\n{}\n",
+        HEADER,
+        seed.to_bits(),
+        frame().to_line(),
+        frame().to_line()
+    );
+
+    let (parsed_seed, frames) = parse_replay(&contents).unwrap();
+    assert_eq!(parsed_seed, seed);
+    assert_eq!(frames, vec![frame(), frame()]);
+
+    assert!(parse_replay("seed 1").is_err());
+    assert!(parse_replay(&format!("{}\nseed 1\nnot a frame", HEADER)).is_err());
+}
+
+/// Stand-ins for the systems that draw from `SeededRng`, they have no order between them.
+#[derive(Resource, Default, Debug, PartialEq)]
+struct Drawn {
+    barks: Vec<u32>,
+    sounds: Vec<u32>,
+    elapsed: Duration,
+}
+
+#[derive(Resource)]
+struct SoundDraws(usize);
+
+fn bark_on_click(input: Res<PlayerInput>, rng: Res<SeededRng>, mut drawn: ResMut<Drawn>) {
+    if input.click {
+        drawn.barks.push(rng.lock(RngStream::Barks).gen());
+    }
+}
+
+fn play_sounds(rng: Res<SeededRng>, draws: Res<SoundDraws>, mut sounds: Local<Vec<u32>>) {
+    for _ in 0..draws.0 {
+        sounds.push(rng.lock(RngStream::Sounds).gen());
+    }
+}
+
+fn collect_sounds(rng: Res<SeededRng>, time: Res<Time>, mut drawn: ResMut<Drawn>) {
+    drawn.sounds.push(rng.lock(RngStream::Sounds).gen());
+    drawn.elapsed = time.elapsed();
+}
+
+fn replay(sound_draws: usize) -> Drawn {
+    let frames: Vec<ReplayFrame> = (0..120)
+        .map(|i| ReplayFrame {
+            input: PlayerInput {
+                click: i % 7 == 0,
+                ..default()
+            },
+            mouse: Vec2::ZERO,
+            delta: Duration::from_millis(10 + i % 13),
+        })
+        .collect();
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(SeededRng::new(42))
+        .insert_resource(SoundDraws(sound_draws))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(frames[0].delta))
+        .init_resource::<PlayerInput>()
+        .init_resource::<MouseWorldCoords>()
+        .init_resource::<Drawn>()
+        .insert_resource(Playback {
+            frames: frames.into_iter(),
+        })
+        .add_systems(PreUpdate, play_frame.run_if(resource_exists::<Playback>))
+        .add_systems(
+            Update,
+            (bark_on_click, (play_sounds, collect_sounds).chain()),
+        );
+    for _ in 0..120 {
+        app.update();
+    }
+    app.world_mut()
+        .remove_resource::<Drawn>()
+        .expect("Drawn should exist")
+}
+
+#[test]
+fn replays_are_deterministic() {
+    let first = replay(1);
+    assert_eq!(first.barks.len(), 18);
+    assert_eq!(first, replay(1));
+
+    // The streams are independent, drawing more sounds doesn't change the barks.
+    let more_sounds = replay(3);
+    assert_eq!(more_sounds.barks, first.barks);
+    assert_eq!(more_sounds.elapsed, first.elapsed);
+}
@@ -0,0 +1,78 @@
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use bevy::prelude::*;
+use chrono::Utc;
+use rand::SeedableRng;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::{world::map::generation::BitMap, GameRng, GameState};
+
+// So that the gameplay doesn't draw the same numbers as the world generation.
+const GAMEPLAY_SEED_OFFSET: u64 = 31;
+
+/// Each system that draws from `SeededRng` gets its own stream.
+/// Systems run in parallel, with a shared stream the numbers each of them gets
+/// would depend on the order they happened to run in and replays would diverge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+pub enum RngStream {
+    Sounds,
+    Behaviors,
+    Barks,
+    CompanionLines,
+    /// The yarn functions, they only run in the dialogue runner.
+    Dialogue,
+}
+
+/// The randomness of everything that happens while playing (NPC behavior, barks, sounds, ...).
+/// It's reseeded from the world seed when the game starts, so that replays are deterministic.
+/// Shared with the yarn functions `dice()`, `random()` and `random_range()`,
+/// yarn functions can't access the ECS so they hold a clone of this.
+#[derive(Resource, Clone)]
+pub struct SeededRng(Arc<Vec<Mutex<GameRng>>>);
+
+impl Default for SeededRng {
+    fn default() -> Self {
+        Self::new(Utc::now().timestamp_nanos_opt().unwrap_or_default() as u64)
+    }
+}
+
+fn stream_rng(seed: u64, stream: RngStream) -> GameRng {
+    GameRng::seed_from_u64(seed ^ ((stream as u64) << 32))
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        Self(Arc::new(
+            RngStream::iter()
+                .map(|stream| Mutex::new(stream_rng(seed, stream)))
+                .collect(),
+        ))
+    }
+
+    pub fn reseed(&self, seed: u64) {
+        for stream in RngStream::iter() {
+            *self.lock(stream) = stream_rng(seed, stream);
+        }
+    }
+
+    pub fn lock(&self, stream: RngStream) -> MutexGuard<'_, GameRng> {
+        self.0[stream as usize]
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Whatever happened in the main menu shouldn't change the game.
+fn reseed_from_world(bitmap: Res<BitMap>, rng: Res<SeededRng>) {
+    rng.reseed(bitmap.seed() as u64 + GAMEPLAY_SEED_OFFSET);
+}
+
+pub struct SeededRngPlugin;
+
+impl Plugin for SeededRngPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SeededRng>()
+            .add_systems(OnEnter(GameState::Gaming), reseed_from_world);
+    }
+}
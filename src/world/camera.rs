@@ -1,13 +1,18 @@
 use bevy::render::camera::ScalingMode;
 #[cfg(not(target_arch = "wasm32"))]
-use bevy::render::view::screenshot::ScreenshotManager;
-#[cfg(not(target_arch = "wasm32"))]
 use bevy::window::{PrimaryWindow, WindowMode};
 use bevy::{prelude::*, transform::TransformSystem};
 use bevy_kira_audio::prelude::AudioReceiver;
-use bevy_rapier2d::plugin::PhysicsSet;
+use bevy_rapier2d::{dynamics::Velocity, plugin::PhysicsSet};
+use bevy_yarnspinner::events::DialogueCompleteEvent;
 
+use super::camera_director::camera_directed;
 use super::camera_shake::{update_camera, CameraShake};
+use super::map::chunk_manager::ChunkManager;
+use super::photo_mode::photo_mode_active;
+use super::viewport::ViewportMode;
+use crate::npc::conversation::Conversation;
+use crate::player::chat::{PlayerStartedChat, PlayerStoppedChat};
 use crate::player::input::PlayerInput;
 use crate::player::Player;
 use crate::utils::DebugActive;
@@ -20,7 +25,42 @@ use crate::utils::DebugActive;
 // too large values will lead to overflow of the 1000 range
 // (in which case they won't get rendered on the camera anymore).
 const YSORT_SCALE: f32 = 0.0001;
-const PROJECTION_SCALE: f32 = 250.0;
+pub const PROJECTION_SCALE: f32 = 250.0;
+// Where the camera sits between the player (0.0) and the NPC (1.0) during chats.
+const DIALOGUE_FRAMING_WEIGHT: f32 = 0.5;
+
+/// How the camera follows the player, the fields can be tweaked at runtime.
+#[derive(Resource)]
+pub struct CameraFollow {
+    /// Half extents of the area around the camera center the player can move in
+    /// without the camera following.
+    pub dead_zone: Vec2,
+    /// The camera looks ahead to where the player will be in this many seconds.
+    pub look_ahead_time: f32,
+    pub max_look_ahead: f32,
+    /// How fast the camera catches up, higher is snappier.
+    pub damping: f32,
+    pos: Vec2,
+    look_ahead: Vec2,
+    /// Center of the current chat, set from `PlayerStartedChat::direction`.
+    framing: Option<Vec2>,
+    initialized: bool,
+}
+
+impl Default for CameraFollow {
+    fn default() -> Self {
+        Self {
+            dead_zone: Vec2::new(12.0, 8.0),
+            look_ahead_time: 0.35,
+            max_look_ahead: 40.0,
+            damping: 5.0,
+            pos: Vec2::ZERO,
+            look_ahead: Vec2::ZERO,
+            framing: None,
+            initialized: false,
+        }
+    }
+}
 
 #[derive(Component)]
 pub struct MainCamera;
@@ -85,24 +125,126 @@ fn apply_y_sort_static_child(
 
 fn spawn_camera(mut commands: Commands) {
     let mut camera = Camera2dBundle::default();
+    // The actual scaling depends on the `ViewportMode`, see `viewport.rs`.
     camera.projection.scaling_mode = ScalingMode::FixedVertical(PROJECTION_SCALE);
     commands.spawn((MainCamera, camera, AudioReceiver));
 }
 
-fn update_camera_target(mut shake: ResMut<CameraShake>, q_player: Query<&Transform, With<Player>>) {
+fn update_camera_target(
+    time: Res<Time>,
+    conversation: Res<Conversation>,
+    chunk_manager: Res<ChunkManager>,
+    mut follow: ResMut<CameraFollow>,
+    mut shake: ResMut<CameraShake>,
+    q_player: Query<(&Transform, &Velocity), With<Player>>,
+    q_projection: Query<&OrthographicProjection, With<MainCamera>>,
+) {
+    let (player_transform, velocity) = match q_player.get_single() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    let projection = match q_projection.get_single() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    let player_pos = player_transform.translation.truncate();
+    if !follow.initialized {
+        follow.initialized = true;
+        follow.pos = player_pos;
+    }
+
+    let t = 1.0 - (-follow.damping * time.delta_seconds()).exp();
+    let goal = match follow.framing {
+        // Pan between the speakers during conversations,
+        // otherwise keep both the player and the NPC in the picture.
+        Some(framing) => match (conversation.speaker, conversation.focus) {
+            (Some(_), Some(focus)) => focus,
+            _ => framing,
+        },
+        None => {
+            let look_ahead =
+                (velocity.linvel * follow.look_ahead_time).clamp_length_max(follow.max_look_ahead);
+            follow.look_ahead = follow.look_ahead.lerp(look_ahead, t);
+            let target = player_pos + follow.look_ahead;
+
+            // Only move once the target leaves the dead zone, and then only up to its edge.
+            let offset = target - follow.pos;
+            let outside = (offset.abs() - follow.dead_zone).max(Vec2::ZERO);
+            follow.pos + outside * offset.signum()
+        }
+    };
+
+    let pos = follow.pos.lerp(goal, t);
+    follow.pos = clamp_to_chunks(pos, &chunk_manager, projection);
+    shake.update_target(follow.pos);
+}
+
+/// Keep the view inside the spawned chunks, so that their edges never show up.
+/// If the view is larger than the chunks (e.g. zoomed out) there is nothing we can do.
+fn clamp_to_chunks(
+    pos: Vec2,
+    chunk_manager: &ChunkManager,
+    projection: &OrthographicProjection,
+) -> Vec2 {
+    let Some(chunks) = chunk_manager.spawned_rect() else {
+        return pos;
+    };
+    let half_view = projection.area.half_size();
+    let min = chunks.min + half_view;
+    let max = chunks.max - half_view;
+
+    Vec2::new(
+        if min.x <= max.x {
+            pos.x.clamp(min.x, max.x)
+        } else {
+            pos.x
+        },
+        if min.y <= max.y {
+            pos.y.clamp(min.y, max.y)
+        } else {
+            pos.y
+        },
+    )
+}
+
+/// While someone else controls the camera, the follow starts from wherever they left it.
+fn sync_camera_follow(
+    mut follow: ResMut<CameraFollow>,
+    q_camera: Query<&Transform, With<MainCamera>>,
+) {
+    if let Ok(transform) = q_camera.get_single() {
+        follow.pos = transform.translation.truncate();
+        follow.look_ahead = Vec2::ZERO;
+    }
+}
+
+fn frame_dialogue(
+    mut follow: ResMut<CameraFollow>,
+    q_player: Query<&Transform, With<Player>>,
+    mut ev_player_started_chat: EventReader<PlayerStartedChat>,
+) {
     let player_transform = match q_player.get_single() {
         Ok(r) => r,
         Err(_) => return,
     };
-    shake.update_target(player_transform.translation.truncate());
+
+    for ev in ev_player_started_chat.read() {
+        follow.framing =
+            Some(player_transform.translation.truncate() + ev.direction * DIALOGUE_FRAMING_WEIGHT);
+    }
+}
+
+fn release_dialogue_framing(mut follow: ResMut<CameraFollow>) {
+    follow.framing = None;
 }
 
 fn zoom_camera(
     player_input: Res<PlayerInput>,
     debug_active: Res<DebugActive>,
+    viewport_mode: Res<ViewportMode>,
     mut q_projection: Query<&mut OrthographicProjection, With<MainCamera>>,
 ) {
-    if !**debug_active {
+    if !**debug_active || viewport_mode.is_pixel_perfect() {
         return;
     }
 
@@ -138,25 +280,6 @@ fn toggle_full_screen(
     }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
-fn take_screenshot(
-    keys: Res<ButtonInput<KeyCode>>,
-    main_window: Query<Entity, With<PrimaryWindow>>,
-    mut screenshot_manager: ResMut<ScreenshotManager>,
-    mut counter: Local<u32>,
-) {
-    if !keys.just_pressed(KeyCode::F12) {
-        return;
-    }
-
-    let path = format!("./screenshot-{}.png", *counter);
-    *counter += 1;
-    match screenshot_manager.save_screenshot_to_disk(main_window.single(), path) {
-        Ok(()) => {}
-        Err(err) => error!("failed to take screenshot, {}", err),
-    }
-}
-
 pub struct CameraPlugin;
 
 impl Plugin for CameraPlugin {
@@ -167,8 +290,6 @@ impl Plugin for CameraPlugin {
                 (
                     #[cfg(not(target_arch = "wasm32"))]
                     toggle_full_screen,
-                    #[cfg(not(target_arch = "wasm32"))]
-                    take_screenshot,
                     apply_y_sort,
                     apply_y_sort_child
                         .after(apply_y_sort)
@@ -178,9 +299,25 @@ impl Plugin for CameraPlugin {
                     zoom_camera,
                 ),
             )
+            .init_resource::<CameraFollow>()
+            .add_systems(
+                Update,
+                (
+                    frame_dialogue,
+                    release_dialogue_framing.run_if(
+                        on_event::<PlayerStoppedChat>()
+                            .or_else(on_event::<DialogueCompleteEvent>()),
+                    ),
+                )
+                    .chain(),
+            )
             .add_systems(
                 PostUpdate,
-                update_camera_target
+                (
+                    update_camera_target
+                        .run_if(not(photo_mode_active).and_then(not(camera_directed))),
+                    sync_camera_follow.run_if(photo_mode_active.or_else(camera_directed)),
+                )
                     .after(PhysicsSet::Writeback)
                     .before(TransformSystem::TransformPropagate)
                     .before(update_camera),
@@ -0,0 +1,259 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, transform::TransformSystem};
+use bevy_rapier2d::plugin::PhysicsSet;
+use bevy_tweening::{component_animator_system, *};
+use bevy_yarnspinner::events::DialogueCompleteEvent;
+
+use super::{
+    camera_shake::{update_camera, CameraShake},
+    photo_mode::photo_mode_active,
+    MainCamera,
+};
+use crate::{
+    player::{chat::PlayerStoppedChat, Player},
+    GameState,
+};
+
+// Tweens can't have a zero duration.
+const MIN_DURATION: f32 = 0.01;
+const RESET_AFTER_DIALOGUE_DURATION: f32 = 0.75;
+
+/// Scripted camera shots, from Rust or with the yarn commands
+/// `<<camera_focus Eleonore 1.5>>`, `<<camera_pan x y duration>>` and `<<camera_reset>>`.
+/// The zoom is the scale of the projection, `None` keeps the current zoom.
+#[derive(Event, Clone, Copy, Debug, PartialEq)]
+pub enum DirectCamera {
+    /// Ease to the entity and keep following it.
+    Focus {
+        entity: Entity,
+        zoom: Option<f32>,
+        duration: f32,
+    },
+    /// Ease to the position and stay there.
+    Pan {
+        pos: Vec2,
+        zoom: Option<f32>,
+        duration: f32,
+    },
+    /// Ease back to the player, afterwards the camera follows the player as usual.
+    Reset { duration: f32 },
+}
+
+/// Takes over the camera from `update_camera_target` while `active`.
+/// The position and zoom are animated with `bevy_tweening`.
+#[derive(Component, Default)]
+pub struct CameraDirector {
+    active: bool,
+    pos: Vec2,
+    zoom: f32,
+    follow: Option<Entity>,
+    resetting: bool,
+    /// The zoom before the director took over, restored on reset.
+    previous_zoom: f32,
+}
+
+struct ShotLens {
+    start_pos: Vec2,
+    end_pos: Vec2,
+    start_zoom: f32,
+    end_zoom: f32,
+}
+
+impl Lens<CameraDirector> for ShotLens {
+    fn lerp(&mut self, target: &mut dyn Targetable<CameraDirector>, ratio: f32) {
+        target.pos = self.start_pos.lerp(self.end_pos, ratio);
+        target.zoom = self.start_zoom.lerp(self.end_zoom, ratio);
+    }
+}
+
+pub fn camera_directed(q_director: Query<&CameraDirector>) -> bool {
+    q_director.iter().any(|director| director.active)
+}
+
+fn spawn_director(mut commands: Commands) {
+    commands.spawn((
+        CameraDirector::default(),
+        Animator::new(Delay::<CameraDirector>::new(Duration::from_secs_f32(
+            MIN_DURATION,
+        ))),
+    ));
+}
+
+fn direct_camera(
+    q_camera: Query<(&Transform, &OrthographicProjection), With<MainCamera>>,
+    q_player: Query<Entity, With<Player>>,
+    q_transforms: Query<&GlobalTransform>,
+    mut q_director: Query<(&mut CameraDirector, &mut Animator<CameraDirector>)>,
+    mut ev_direct_camera: EventReader<DirectCamera>,
+) {
+    let (camera_transform, projection) = match q_camera.get_single() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    let (mut director, mut animator) = match q_director.get_single_mut() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    for ev in ev_direct_camera.read() {
+        let (follow, pos, zoom, duration) = match *ev {
+            DirectCamera::Focus {
+                entity,
+                zoom,
+                duration,
+            } => {
+                let Ok(transform) = q_transforms.get(entity) else {
+                    error!("trying to focus the camera on an entity without a transform");
+                    continue;
+                };
+                (
+                    Some(entity),
+                    transform.translation().truncate(),
+                    zoom,
+                    duration,
+                )
+            }
+            DirectCamera::Pan {
+                pos,
+                zoom,
+                duration,
+            } => (None, pos, zoom, duration),
+            DirectCamera::Reset { duration } => {
+                if !director.active {
+                    continue;
+                }
+                let Some(pos) = q_player
+                    .get_single()
+                    .ok()
+                    .and_then(|player| q_transforms.get(player).ok())
+                    .map(|transform| transform.translation().truncate())
+                else {
+                    director.active = false;
+                    continue;
+                };
+                (None, pos, Some(director.previous_zoom), duration)
+            }
+        };
+
+        if !director.active {
+            director.active = true;
+            director.pos = camera_transform.translation.truncate();
+            director.zoom = projection.scale;
+            director.previous_zoom = projection.scale;
+        }
+        director.follow = follow;
+        director.resetting = matches!(ev, DirectCamera::Reset { .. });
+
+        let start_zoom = projection.scale;
+        animator.set_tweenable(Tween::new(
+            EaseFunction::QuadraticInOut,
+            Duration::from_secs_f32(duration.max(MIN_DURATION)),
+            ShotLens {
+                start_pos: camera_transform.translation.truncate(),
+                end_pos: pos,
+                start_zoom,
+                end_zoom: zoom.unwrap_or(start_zoom),
+            },
+        ));
+        animator.state = AnimatorState::Playing;
+    }
+}
+
+/// Once the shot arrived, keep following the focused entity or release the camera after a reset.
+fn finish_shot(
+    q_transforms: Query<&GlobalTransform>,
+    mut q_director: Query<(&mut CameraDirector, &Animator<CameraDirector>)>,
+) {
+    let (mut director, animator) = match q_director.get_single_mut() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    if !director.active || animator.tweenable().progress() < 1.0 {
+        return;
+    }
+
+    if director.resetting {
+        director.active = false;
+        director.resetting = false;
+        return;
+    }
+
+    if let Some(entity) = director.follow {
+        match q_transforms.get(entity) {
+            Ok(transform) => director.pos = transform.translation().truncate(),
+            Err(_) => director.follow = None,
+        }
+    }
+}
+
+/// Shots set up by dialogue shouldn't outlive it.
+fn reset_after_dialogue(
+    q_director: Query<&CameraDirector>,
+    mut ev_direct_camera: EventWriter<DirectCamera>,
+) {
+    if q_director.iter().any(|director| director.active) {
+        ev_direct_camera.send(DirectCamera::Reset {
+            duration: RESET_AFTER_DIALOGUE_DURATION,
+        });
+    }
+}
+
+fn apply_director(
+    mut shake: ResMut<CameraShake>,
+    q_director: Query<&CameraDirector>,
+    mut q_projection: Query<&mut OrthographicProjection, With<MainCamera>>,
+) {
+    let director = match q_director.get_single() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    let mut projection = match q_projection.get_single_mut() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    shake.update_target(director.pos);
+    if projection.scale != director.zoom {
+        projection.scale = director.zoom;
+    }
+}
+
+pub struct CameraDirectorPlugin;
+
+impl Plugin for CameraDirectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DirectCamera>()
+            .add_systems(Startup, spawn_director)
+            .add_systems(
+                Update,
+                (
+                    reset_after_dialogue.run_if(
+                        in_state(GameState::Gaming).and_then(
+                            on_event::<PlayerStoppedChat>()
+                                .or_else(on_event::<DialogueCompleteEvent>()),
+                        ),
+                    ),
+                    direct_camera,
+                )
+                    .chain()
+                    .before(AnimationSystem::AnimationUpdate),
+            )
+            .add_systems(
+                Update,
+                (
+                    component_animator_system::<CameraDirector>
+                        .in_set(AnimationSystem::AnimationUpdate),
+                    finish_shot.after(AnimationSystem::AnimationUpdate),
+                ),
+            )
+            .add_systems(
+                PostUpdate,
+                apply_director
+                    .run_if(camera_directed.and_then(not(photo_mode_active)))
+                    .after(PhysicsSet::Writeback)
+                    .before(TransformSystem::TransformPropagate)
+                    .before(update_camera),
+            );
+    }
+}
@@ -3,7 +3,7 @@ use chrono::Utc;
 use bevy::{prelude::*, transform::TransformSystem};
 use noisy_bevy::simplex_noise_2d_seeded;
 
-use super::MainCamera;
+use super::{viewport::ViewportMode, MainCamera};
 
 const NOISE_STRENGTH: f32 = 10.0;
 const TRANSLATION_SHAKE_STRENGTH: f32 = 15.0;
@@ -56,12 +56,19 @@ fn decay_shake_trauma(time: Res<Time>, mut shake: ResMut<CameraShake>) {
 pub fn update_camera(
     mut q_camera: Query<&mut Transform, With<MainCamera>>,
     shake: ResMut<CameraShake>,
+    viewport_mode: Res<ViewportMode>,
 ) {
     let mut transform = match q_camera.get_single_mut() {
         Ok(t) => t,
         Err(_) => return,
     };
 
+    if viewport_mode.is_pixel_perfect() {
+        transform.translation = shake.target.extend(transform.translation.z);
+        transform.rotation = Quat::IDENTITY;
+        return;
+    }
+
     let translation_offset = Vec3::new(shake.noise_value(0), shake.noise_value(1), 0.0)
         * shake.trauma.powi(2)
         * TRANSLATION_SHAKE_STRENGTH;
@@ -4,12 +4,12 @@ use bevy::prelude::*;
 use bevy_tweening::{lens::*, *};
 
 use crate::{
-    npc::{Npc, NpcDialogue},
+    npc::{companion::Companion, Npc, NpcDialogue},
     player::Player,
     GameAssets, GameState,
 };
 
-use super::camera::YSort;
+use super::{camera::YSort, camera_director::DirectCamera};
 
 const FADE_OUT_DURATION: f32 = 2.0;
 const BLACK_OUT_FADE_DURATION: f32 = 4.5;
@@ -19,6 +19,7 @@ const END_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 1.0);
 
 const BACKGROUND_SPRITE_YSORT: f32 = 40_000.0;
 const CHARACTER_YSORT: f32 = 50_000.0;
+const FRAME_ZOOM: f32 = 0.7;
 
 #[derive(Event)]
 pub struct EndingTriggered {
@@ -27,7 +28,7 @@ pub struct EndingTriggered {
 
 fn increase_ysorts(
     mut q_player: Query<&mut YSort, With<Player>>,
-    mut q_npcs: Query<(&mut YSort, &Npc), Without<Player>>,
+    mut q_npcs: Query<(&mut YSort, &Npc, Has<Companion>), Without<Player>>,
     mut ev_ending_triggered: EventReader<EndingTriggered>,
 ) {
     let mut player_ysort = match q_player.get_single_mut() {
@@ -38,14 +39,37 @@ fn increase_ysorts(
     for ev in ev_ending_triggered.read() {
         *player_ysort = YSort(CHARACTER_YSORT);
 
-        for (mut npc_ysort, npc) in &mut q_npcs {
-            if npc.dialogue == ev.dialogue {
+        // Companions are part of the party, so they stay visible as well.
+        for (mut npc_ysort, npc, is_companion) in &mut q_npcs {
+            if npc.dialogue == ev.dialogue || is_companion {
                 *npc_ysort = YSort(CHARACTER_YSORT);
             }
         }
     }
 }
 
+/// Move the camera in on the characters that stay visible, see `increase_ysorts`.
+fn frame_characters(
+    q_characters: Query<(&GlobalTransform, &YSort), Or<(With<Player>, With<Npc>)>>,
+    mut ev_direct_camera: EventWriter<DirectCamera>,
+) {
+    let positions: Vec<Vec2> = q_characters
+        .iter()
+        .filter(|(_, ysort)| ysort.0 == CHARACTER_YSORT)
+        .map(|(transform, _)| transform.translation().truncate())
+        .collect();
+    if positions.is_empty() {
+        return;
+    }
+
+    let center = positions.iter().sum::<Vec2>() / positions.len() as f32;
+    ev_direct_camera.send(DirectCamera::Pan {
+        pos: center,
+        zoom: Some(FRAME_ZOOM),
+        duration: FADE_OUT_DURATION,
+    });
+}
+
 /// This fades in a black sprite that covers everything except the two characters and some text.
 fn fade_in_black_sprite(mut commands: Commands, assets: Res<GameAssets>) {
     let tween = Tween::new(
@@ -139,6 +163,7 @@ impl Plugin for EndingPlugin {
                 .run_if(not(in_state(GameState::AssetLoading))),
         )
         .add_event::<EndingTriggered>()
+        .add_systems(OnEnter(GameState::Ending), frame_characters)
         .add_systems(
             Update,
             (fade_in_black_screen,).run_if(in_state(GameState::Ending)),
@@ -0,0 +1,355 @@
+#[cfg(test)]
+mod test;
+
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
+
+use bevy::{prelude::*, utils::HashSet};
+
+use crate::{player::Player, GameState};
+
+use super::{
+    camera::PROJECTION_SCALE,
+    map::{
+        generation::{poi::PointOfInterestKind, BitMap},
+        navigation::NavGrid,
+        CHUNK_SIZE,
+    },
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+const SAVE_PATH: &str = "./exploration.save";
+#[cfg(not(target_arch = "wasm32"))]
+const SAVE_INTERVAL: f32 = 10.0;
+// Ignores the save and generates a random world, the save is overwritten once something is explored.
+#[cfg(not(target_arch = "wasm32"))]
+const NEW_WORLD_ARG: &str = "--new-world";
+// The part of the world around the player that counts as seen, in world units.
+// This is the area the camera shows without zoom.
+const VIEW_HALF_EXTENTS: Vec2 =
+    Vec2::new(PROJECTION_SCALE * 16.0 / 9.0 / 2.0, PROJECTION_SCALE / 2.0);
+// Chunks around the path graph that still count towards the explored percentage.
+const BOUNDS_MARGIN_CHUNKS: i32 = 1;
+
+/// Sent the first time the player sees a chunk or a point of interest.
+#[derive(Event, Clone, Copy, Debug, PartialEq)]
+pub enum Discovered {
+    Chunk(IVec2),
+    PointOfInterest {
+        kind: PointOfInterestKind,
+        pos: Vec2,
+    },
+}
+
+/// The world doesn't come from the save (`--seed`, replays) or has to start as generated
+/// (recordings), the exploration and flora saves are neither restored nor written.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Resource)]
+pub struct IgnoreSaves;
+
+/// Everything the player has seen so far.
+/// This is written to disk so it survives between sessions,
+/// but only restored when the world was generated with the same seed.
+/// The save also decides which world is generated, see `ExplorationPlugin`.
+#[derive(Resource, Default)]
+pub struct Exploration {
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    seed: u32,
+    chunks: HashSet<IVec2>,
+    /// The tiles of the points of interest.
+    points_of_interest: HashSet<IVec2>,
+    /// The chunks that make up the world, used for the explored percentage.
+    bounds: IRect,
+    unsaved: bool,
+}
+
+impl Exploration {
+    fn new(seed: f32, bounds: IRect) -> Self {
+        Self {
+            seed: seed.to_bits(),
+            bounds,
+            ..default()
+        }
+    }
+
+    pub fn is_chunk_explored(&self, chunk: IVec2) -> bool {
+        self.chunks.contains(&chunk)
+    }
+
+    pub fn is_point_of_interest_explored(&self, pos: Vec2) -> bool {
+        self.points_of_interest
+            .contains(&NavGrid::world_to_cell(pos))
+    }
+
+    pub fn explored_chunks(&self) -> impl Iterator<Item = &IVec2> {
+        self.chunks.iter()
+    }
+
+    /// How much of the world (between 0 and 100) the player has seen.
+    pub fn explored_percent(&self) -> f32 {
+        let size = self.bounds.size() + IVec2::ONE;
+        let total = (size.x * size.y).max(1) as f32;
+        let explored = self
+            .chunks
+            .iter()
+            .filter(|chunk| self.bounds.contains(**chunk))
+            .count() as f32;
+        100.0 * explored / total
+    }
+
+    /// Returns `true` if the chunk wasn't explored before.
+    fn explore_chunk(&mut self, chunk: IVec2) -> bool {
+        let new = self.chunks.insert(chunk);
+        self.unsaved |= new;
+        new
+    }
+
+    /// Returns `true` if the point of interest wasn't explored before.
+    fn explore_point_of_interest(&mut self, pos: Vec2) -> bool {
+        let new = self.points_of_interest.insert(NavGrid::world_to_cell(pos));
+        self.unsaved |= new;
+        new
+    }
+
+    /// A plain text format, one entry per line.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    fn to_save_string(&self) -> String {
+        let mut lines = vec![format!("seed {}", self.seed)];
+        for chunk in &self.chunks {
+            lines.push(format!("chunk {} {}", chunk.x, chunk.y));
+        }
+        for cell in &self.points_of_interest {
+            lines.push(format!("poi {} {}", cell.x, cell.y));
+        }
+        lines.join("\n")
+    }
+
+    /// Restore the explored chunks and points of interest from the save.
+    /// Saves from a different world are ignored, returns `false` in that case.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    fn load_save_string(&mut self, contents: &str) -> bool {
+        if seed_from_save_string(contents) != Some(self.seed) {
+            return false;
+        }
+
+        for line in contents.lines().skip(1) {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let [kind, x, y] = parts[..] else {
+                error!("invalid line in exploration save, '{}'", line);
+                continue;
+            };
+            let (Ok(x), Ok(y)) = (x.parse(), y.parse()) else {
+                error!("invalid line in exploration save, '{}'", line);
+                continue;
+            };
+            match kind {
+                "chunk" => self.chunks.insert(IVec2::new(x, y)),
+                "poi" => self.points_of_interest.insert(IVec2::new(x, y)),
+                _ => {
+                    error!("invalid line in exploration save, '{}'", line);
+                    continue;
+                }
+            };
+        }
+        true
+    }
+}
+
+/// The bits of the world seed on the first line of the save.
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+fn seed_from_save_string(contents: &str) -> Option<u32> {
+    contents
+        .lines()
+        .next()
+        .and_then(|l| l.strip_prefix("seed "))
+        .and_then(|s| s.trim().parse::<u32>().ok())
+}
+
+/// The world of the last session, unless the player asked for a new one.
+#[cfg(not(target_arch = "wasm32"))]
+fn saved_seed(mut args: impl Iterator<Item = String>) -> Option<f32> {
+    if args.any(|arg| arg == NEW_WORLD_ARG) {
+        info!("starting a new world, {} is overwritten", SAVE_PATH);
+        return None;
+    }
+
+    let contents = std::fs::read_to_string(SAVE_PATH).ok()?;
+    match seed_from_save_string(&contents) {
+        Some(bits) => Some(f32::from_bits(bits)),
+        None => {
+            error!("invalid seed in {}", SAVE_PATH);
+            None
+        }
+    }
+}
+
+/// The explored percentage, shared with the yarn function `explored_percent()`.
+/// Yarn functions can't access the ECS, so this is updated whenever the exploration changes.
+#[derive(Resource, Clone, Default)]
+pub struct ExploredPercent(Arc<AtomicU32>);
+
+impl ExploredPercent {
+    pub fn get(&self) -> f32 {
+        f32::from_bits(self.0.load(Ordering::Relaxed))
+    }
+
+    fn set(&self, percent: f32) {
+        self.0.store(percent.to_bits(), Ordering::Relaxed);
+    }
+}
+
+pub fn chunk_of(pos: Vec2) -> IVec2 {
+    NavGrid::world_to_cell(pos).div_euclid(IVec2::splat(CHUNK_SIZE as i32))
+}
+
+/// The chunks spanned by the path graph, everything outside is just filler.
+fn world_bounds(bitmap: &BitMap) -> IRect {
+    let (min, max) = bitmap
+        .vertices()
+        .iter()
+        .fold((Vec2::MAX, Vec2::MIN), |(min, max), v| {
+            (min.min(*v), max.max(*v))
+        });
+    if min.cmpgt(max).any() {
+        return IRect::default();
+    }
+
+    let margin = IVec2::splat(BOUNDS_MARGIN_CHUNKS);
+    IRect::from_corners(chunk_of(min) - margin, chunk_of(max) + margin)
+}
+
+fn init_exploration(
+    mut commands: Commands,
+    bitmap: Res<BitMap>,
+    #[cfg(not(target_arch = "wasm32"))] ignore_saves: Option<Res<IgnoreSaves>>,
+) {
+    #[allow(unused_mut)]
+    let mut exploration = Exploration::new(bitmap.seed(), world_bounds(&bitmap));
+
+    #[cfg(not(target_arch = "wasm32"))]
+    if ignore_saves.is_some() {
+        info!("not restoring or writing {}", SAVE_PATH);
+    } else if let Ok(contents) = std::fs::read_to_string(SAVE_PATH) {
+        if exploration.load_save_string(&contents) {
+            info!("restored exploration from {}", SAVE_PATH);
+        }
+    }
+
+    commands.insert_resource(exploration);
+}
+
+fn discover(
+    mut exploration: ResMut<Exploration>,
+    bitmap: Res<BitMap>,
+    q_player: Query<&Transform, With<Player>>,
+    mut ev_discovered: EventWriter<Discovered>,
+) {
+    let player_pos = match q_player.get_single() {
+        Ok(r) => r.translation.truncate(),
+        Err(_) => return,
+    };
+
+    let view = Rect::from_center_half_size(player_pos, VIEW_HALF_EXTENTS);
+    let min_chunk = chunk_of(view.min);
+    let max_chunk = chunk_of(view.max);
+    for x in min_chunk.x..=max_chunk.x {
+        for y in min_chunk.y..=max_chunk.y {
+            let chunk = IVec2::new(x, y);
+            // Avoid triggering change detection when nothing is new.
+            if !exploration.is_chunk_explored(chunk) && exploration.explore_chunk(chunk) {
+                ev_discovered.send(Discovered::Chunk(chunk));
+            }
+        }
+    }
+
+    for poi in bitmap.points_of_interest() {
+        if !view.contains(poi.pos) {
+            continue;
+        }
+        if exploration.is_point_of_interest_explored(poi.pos)
+            || !exploration.explore_point_of_interest(poi.pos)
+        {
+            continue;
+        }
+
+        ev_discovered.send(Discovered::PointOfInterest {
+            kind: poi.kind,
+            pos: poi.pos,
+        });
+    }
+}
+
+fn update_explored_percent(exploration: Res<Exploration>, explored_percent: Res<ExploredPercent>) {
+    if exploration.is_changed() {
+        explored_percent.set(exploration.explored_percent());
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_save(exploration: &mut Exploration) {
+    if !exploration.unsaved {
+        return;
+    }
+
+    match std::fs::write(SAVE_PATH, exploration.to_save_string()) {
+        Ok(()) => exploration.unsaved = false,
+        Err(err) => error!("failed to save exploration to {}, {}", SAVE_PATH, err),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_exploration(
+    time: Res<Time>,
+    mut exploration: ResMut<Exploration>,
+    mut timer: Local<Option<Timer>>,
+) {
+    let timer =
+        timer.get_or_insert_with(|| Timer::from_seconds(SAVE_INTERVAL, TimerMode::Repeating));
+    if timer.tick(time.delta()).just_finished() {
+        write_save(exploration.bypass_change_detection());
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_exploration_on_exit(mut exploration: ResMut<Exploration>) {
+    write_save(exploration.bypass_change_detection());
+}
+
+pub struct ExplorationPlugin;
+
+impl Plugin for ExplorationPlugin {
+    fn build(&self, app: &mut App) {
+        // The console and replays replace this with their own seed.
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(seed) = saved_seed(std::env::args().skip(1)) {
+            app.insert_resource(BitMap::with_seed(seed));
+        }
+
+        app.init_resource::<ExploredPercent>()
+            .add_event::<Discovered>()
+            .add_systems(OnExit(GameState::AssetLoading), init_exploration)
+            .add_systems(
+                Update,
+                (
+                    discover.run_if(in_state(GameState::Gaming)),
+                    update_explored_percent,
+                    #[cfg(not(target_arch = "wasm32"))]
+                    save_exploration.run_if(not(resource_exists::<IgnoreSaves>)),
+                )
+                    .chain()
+                    .run_if(resource_exists::<Exploration>),
+            );
+
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_systems(
+            Last,
+            save_exploration_on_exit.run_if(
+                on_event::<AppExit>()
+                    .and_then(resource_exists::<Exploration>)
+                    .and_then(not(resource_exists::<IgnoreSaves>)),
+            ),
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use bevy::prelude::*;
+
+use super::{seed_from_save_string, Exploration};
+
+fn new_exploration() -> Exploration {
+    Exploration::new(42.0, IRect::new(0, 0, 1, 1))
+}
+
+#[test]
+fn explored_percent_counts_chunks_inside_bounds() {
+    let mut exploration = new_exploration();
+    assert_eq!(exploration.explored_percent(), 0.0);
+
+    assert!(exploration.explore_chunk(IVec2::new(0, 0)));
+    assert!(!exploration.explore_chunk(IVec2::new(0, 0)));
+    assert_eq!(exploration.explored_percent(), 25.0);
+
+    // Chunks outside of the world don't count.
+    exploration.explore_chunk(IVec2::new(5, 5));
+    assert_eq!(exploration.explored_percent(), 25.0);
+
+    for chunk in [IVec2::new(1, 0), IVec2::new(0, 1), IVec2::new(1, 1)] {
+        exploration.explore_chunk(chunk);
+    }
+    assert_eq!(exploration.explored_percent(), 100.0);
+}
+
+#[test]
+fn save_round_trip() {
+    let mut exploration = new_exploration();
+    exploration.explore_chunk(IVec2::new(-3, 7));
+    exploration.explore_point_of_interest(Vec2::new(160.0, -32.0));
+
+    let mut restored = new_exploration();
+    assert!(restored.load_save_string(&exploration.to_save_string()));
+    assert!(restored.is_chunk_explored(IVec2::new(-3, 7)));
+    assert!(restored.is_point_of_interest_explored(Vec2::new(160.0, -32.0)));
+    assert!(!restored.is_chunk_explored(IVec2::ZERO));
+}
+
+#[test]
+fn save_from_other_world_is_ignored() {
+    let mut exploration = new_exploration();
+    exploration.explore_chunk(IVec2::ZERO);
+
+    let mut other_world = Exploration::new(7.0, IRect::new(0, 0, 1, 1));
+    assert!(!other_world.load_save_string(&exploration.to_save_string()));
+    assert!(!other_world.is_chunk_explored(IVec2::ZERO));
+}
+
+#[test]
+fn seed_from_save() {
+    let exploration = new_exploration();
+    assert_eq!(
+        seed_from_save_string(&exploration.to_save_string()),
+        Some(42.0_f32.to_bits())
+    );
+    assert_eq!(seed_from_save_string("chunk 0 0"), None);
+    assert_eq!(seed_from_save_string(""), None);
+}
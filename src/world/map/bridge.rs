@@ -0,0 +1,127 @@
+use bevy::prelude::*;
+
+use crate::{GameAssets, GameState};
+
+use super::{
+    chunk_manager::{despawn_chunks, spawn_chunks, ChunkIndex},
+    generation::BitMap,
+    poi::{DARK_WOOD_COLOR, WOOD_COLOR},
+    CHUNK_SIZE, TILE_SIZE,
+};
+
+// Relative to the chunk, which itself lies in the background.
+const DECK_ZINDEX: f32 = 1.0;
+const RAIL_ZINDEX: f32 = 2.0;
+const RAIL_WIDTH: f32 = 3.0;
+
+const NEIGHBOR_OFFSETS: [IVec2; 4] = [IVec2::X, IVec2::Y, IVec2::NEG_X, IVec2::NEG_Y];
+
+fn spawn_sprite(
+    commands: &mut Commands,
+    assets: &Res<GameAssets>,
+    color: Color,
+    size: Vec2,
+    pos: Vec3,
+) -> Entity {
+    commands
+        .spawn(SpriteBundle {
+            texture: assets.white_pixel.clone(),
+            sprite: Sprite {
+                color,
+                custom_size: Some(size),
+                ..default()
+            },
+            transform: Transform::from_translation(pos),
+            ..default()
+        })
+        .id()
+}
+
+/// Spawn the deck and the railings of a single bridge tile.
+/// The deck reaches up to the neighboring tiles,
+/// which is exactly where the colliders are placed.
+fn spawn_bridge_tile(
+    commands: &mut Commands,
+    assets: &Res<GameAssets>,
+    map: &mut ResMut<BitMap>,
+    v: IVec2,
+    local_pos: Vec2,
+) -> Vec<Entity> {
+    let mut entities = vec![spawn_sprite(
+        commands,
+        assets,
+        WOOD_COLOR,
+        Vec2::splat(2.0 * TILE_SIZE),
+        local_pos.extend(DECK_ZINDEX),
+    )];
+
+    for offset in NEIGHBOR_OFFSETS {
+        let w = v + offset;
+        if !map.is_water(w) || map.get_bridge_flag(w) {
+            continue;
+        }
+
+        let size = if offset.x == 0 {
+            Vec2::new(2.0 * TILE_SIZE, RAIL_WIDTH)
+        } else {
+            Vec2::new(RAIL_WIDTH, 2.0 * TILE_SIZE)
+        };
+        let pos = local_pos + offset.as_vec2() * TILE_SIZE;
+        entities.push(spawn_sprite(
+            commands,
+            assets,
+            DARK_WOOD_COLOR,
+            size,
+            pos.extend(RAIL_ZINDEX),
+        ));
+    }
+    entities
+}
+
+fn spawn_bridges(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    mut map: ResMut<BitMap>,
+    q_chunks: Query<(Entity, &ChunkIndex), Added<ChunkIndex>>,
+) {
+    for (entity, chunk) in &q_chunks {
+        let mut entities = Vec::new();
+        for x in 0..CHUNK_SIZE {
+            for y in 0..CHUNK_SIZE {
+                let v = IVec2::new(
+                    x as i32 + chunk.x * CHUNK_SIZE as i32,
+                    y as i32 + chunk.y * CHUNK_SIZE as i32,
+                );
+                if !map.get_bridge_flag(v) {
+                    continue;
+                }
+
+                // Tiles are centered on their position,
+                // so the vertices lie on the bottom left corner.
+                let local_pos = Vec2::new(x as f32, y as f32) * TILE_SIZE - TILE_SIZE / 2.0;
+                entities.append(&mut spawn_bridge_tile(
+                    &mut commands,
+                    &assets,
+                    &mut map,
+                    v,
+                    local_pos,
+                ));
+            }
+        }
+        commands.entity(entity).push_children(&entities);
+    }
+}
+
+pub struct BridgePlugin;
+
+impl Plugin for BridgePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            spawn_bridges
+                .before(spawn_chunks)
+                .before(despawn_chunks)
+                .run_if(in_state(GameState::Gaming)),
+        );
+    }
+}
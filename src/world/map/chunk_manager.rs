@@ -1,7 +1,8 @@
 use crate::{world::MainCamera, GameAssets, GameState};
 
 use super::{
-    generation::BitMap, BACKGROUND_ZINDEX_ABS, CHUNK_SIZE, RENDERED_CHUNKS_RADIUS, TILE_SIZE,
+    generation::{BitMap, ChunkRngStream},
+    BACKGROUND_ZINDEX_ABS, CHUNK_SIZE, RENDERED_CHUNKS_RADIUS, TILE_SIZE,
 };
 use bevy::{math::Vec3Swizzles, prelude::*, utils::HashSet};
 use bevy_ecs_tilemap::prelude::*;
@@ -20,6 +21,29 @@ pub struct ChunkManager {
     spawned_chunks: HashSet<IVec2>,
 }
 
+impl ChunkManager {
+    /// The area covered by the spawned chunks in world units, `None` if there are none.
+    pub fn spawned_rect(&self) -> Option<Rect> {
+        let (min, max) = self
+            .spawned_chunks
+            .iter()
+            .fold((IVec2::MAX, IVec2::MIN), |(min, max), chunk| {
+                (min.min(*chunk), max.max(*chunk))
+            });
+        if self.spawned_chunks.is_empty() {
+            return None;
+        }
+
+        // Tiles are centered on their position, so the chunks start half a tile earlier.
+        let chunk_size = CHUNK_SIZE as f32 * TILE_SIZE;
+        let offset = Vec2::splat(TILE_SIZE / 2.0);
+        Some(Rect::from_corners(
+            min.as_vec2() * chunk_size - offset,
+            (max + IVec2::ONE).as_vec2() * chunk_size - offset,
+        ))
+    }
+}
+
 #[derive(Component, Deref)]
 pub struct ChunkIndex(pub IVec2);
 
@@ -42,6 +66,7 @@ fn spawn_chunk(
     let tilemap_entity = commands.spawn(ChunkIndex(chunk_pos)).id();
 
     let mut tile_storage = TileStorage::empty(TilemapSize::new(CHUNK_SIZE, CHUNK_SIZE));
+    let mut rng = map.chunk_rng(chunk_pos, ChunkRngStream::Tiles);
 
     for x in 0..CHUNK_SIZE {
         for y in 0..CHUNK_SIZE {
@@ -53,7 +78,7 @@ fn spawn_chunk(
 
             let tile_entity = if map.get_water_sparkle_flag(v) {
                 // let tile_entity = if false {
-                let indices = map.get_water_sparkle_indices();
+                let indices = map.get_water_sparkle_indices(&mut rng);
                 commands
                     .spawn((
                         TileBundle {
@@ -70,7 +95,7 @@ fn spawn_chunk(
                     ))
                     .id()
             } else {
-                let index = map.get_tile_index(v) as u32;
+                let index = map.get_tile_index(&mut rng, v) as u32;
                 commands
                     .spawn(TileBundle {
                         position: tile_pos,
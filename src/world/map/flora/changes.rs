@@ -0,0 +1,215 @@
+use std::str::FromStr;
+
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use strum_macros::{Display, EnumString};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::world::exploration::IgnoreSaves;
+use crate::{
+    world::map::{generation::BitMap, CHUNK_SIZE},
+    GameState,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+const SAVE_PATH: &str = "./flora.save";
+#[cfg(not(target_arch = "wasm32"))]
+const SAVE_INTERVAL: f32 = 10.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Display, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum FloraKind {
+    Tree,
+    LargeBush,
+    SmallBush,
+    Rock,
+}
+
+/// How a chunk differs from what the generation spawns there.
+#[derive(Default, Debug, PartialEq)]
+pub struct ChunkFloraDelta {
+    /// The tiles of generated flora that was removed.
+    pub removed: HashSet<IVec2>,
+    /// Flora that isn't part of the generation, by tile.
+    pub added: Vec<(IVec2, FloraKind)>,
+}
+
+/// The changes to the flora, applied whenever a chunk is spawned.
+/// This is written to disk like the `Exploration`,
+/// and only restored when the world was generated with the same seed.
+#[derive(Resource, Default)]
+pub struct FloraChanges {
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    seed: u32,
+    chunks: HashMap<IVec2, ChunkFloraDelta>,
+    unsaved: bool,
+}
+
+fn chunk_of_tile(tile: IVec2) -> IVec2 {
+    tile.div_euclid(IVec2::splat(CHUNK_SIZE as i32))
+}
+
+impl FloraChanges {
+    pub(super) fn new(seed: f32) -> Self {
+        Self {
+            seed: seed.to_bits(),
+            ..default()
+        }
+    }
+
+    pub fn chunk(&self, chunk_pos: IVec2) -> Option<&ChunkFloraDelta> {
+        self.chunks.get(&chunk_pos)
+    }
+
+    /// Remove the flora at the tile, no matter if it was generated or added.
+    pub fn remove(&mut self, tile: IVec2) {
+        let delta = self.chunks.entry(chunk_of_tile(tile)).or_default();
+        match delta.added.iter().position(|(v, _)| *v == tile) {
+            Some(index) => {
+                delta.added.remove(index);
+            }
+            None => {
+                delta.removed.insert(tile);
+            }
+        }
+        self.unsaved = true;
+    }
+
+    /// Place flora at the tile, replacing flora that was added there before.
+    /// Nothing checks whether there is enough space for it.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub fn add(&mut self, tile: IVec2, kind: FloraKind) {
+        let delta = self.chunks.entry(chunk_of_tile(tile)).or_default();
+        delta.added.retain(|(v, _)| *v != tile);
+        delta.added.push((tile, kind));
+        self.unsaved = true;
+    }
+
+    /// A plain text format, one entry per line.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub(super) fn to_save_string(&self) -> String {
+        let mut lines = vec![format!("seed {}", self.seed)];
+        for delta in self.chunks.values() {
+            for tile in &delta.removed {
+                lines.push(format!("removed {} {}", tile.x, tile.y));
+            }
+            for (tile, kind) in &delta.added {
+                lines.push(format!("added {} {} {}", tile.x, tile.y, kind));
+            }
+        }
+        lines.join("\n")
+    }
+
+    /// Restore the changes from the save.
+    /// Saves from a different world are ignored, returns `false` in that case.
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    pub(super) fn load_save_string(&mut self, contents: &str) -> bool {
+        let mut lines = contents.lines();
+        let seed = lines
+            .next()
+            .and_then(|l| l.strip_prefix("seed "))
+            .and_then(|s| s.trim().parse::<u32>().ok());
+        if seed != Some(self.seed) {
+            return false;
+        }
+
+        for line in lines {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let (kind, x, y, flora) = match parts[..] {
+                [kind, x, y] => (kind, x, y, None),
+                [kind, x, y, flora] => (kind, x, y, Some(flora)),
+                _ => {
+                    error!("invalid line in flora save, '{}'", line);
+                    continue;
+                }
+            };
+            let (Ok(x), Ok(y)) = (x.parse(), y.parse()) else {
+                error!("invalid line in flora save, '{}'", line);
+                continue;
+            };
+            let tile = IVec2::new(x, y);
+            match (kind, flora.map(FloraKind::from_str)) {
+                ("removed", None) => self.remove(tile),
+                ("added", Some(Ok(flora))) => self.add(tile, flora),
+                _ => error!("invalid line in flora save, '{}'", line),
+            }
+        }
+        self.unsaved = false;
+        true
+    }
+}
+
+fn init_flora_changes(
+    mut commands: Commands,
+    bitmap: Res<BitMap>,
+    #[cfg(not(target_arch = "wasm32"))] ignore_saves: Option<Res<IgnoreSaves>>,
+) {
+    #[allow(unused_mut)]
+    let mut changes = FloraChanges::new(bitmap.seed());
+
+    #[cfg(not(target_arch = "wasm32"))]
+    if ignore_saves.is_some() {
+        info!("not restoring or writing {}", SAVE_PATH);
+    } else if let Ok(contents) = std::fs::read_to_string(SAVE_PATH) {
+        if changes.load_save_string(&contents) {
+            info!("restored flora changes from {}", SAVE_PATH);
+        } else {
+            info!("{} is from another world, starting over", SAVE_PATH);
+        }
+    }
+
+    commands.insert_resource(changes);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_save(changes: &mut FloraChanges) {
+    if !changes.unsaved {
+        return;
+    }
+
+    match std::fs::write(SAVE_PATH, changes.to_save_string()) {
+        Ok(()) => changes.unsaved = false,
+        Err(err) => error!("failed to save flora changes to {}, {}", SAVE_PATH, err),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_flora_changes(
+    time: Res<Time>,
+    mut changes: ResMut<FloraChanges>,
+    mut timer: Local<Option<Timer>>,
+) {
+    let timer =
+        timer.get_or_insert_with(|| Timer::from_seconds(SAVE_INTERVAL, TimerMode::Repeating));
+    if timer.tick(time.delta()).just_finished() {
+        write_save(changes.bypass_change_detection());
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_flora_changes_on_exit(mut changes: ResMut<FloraChanges>) {
+    write_save(changes.bypass_change_detection());
+}
+
+pub struct FloraChangesPlugin;
+
+impl Plugin for FloraChangesPlugin {
+    fn build(&self, app: &mut App) {
+        // Replaced with the one for the generated world once it's known.
+        app.init_resource::<FloraChanges>()
+            .add_systems(OnExit(GameState::AssetLoading), init_flora_changes);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_systems(
+            Update,
+            save_flora_changes.run_if(not(resource_exists::<IgnoreSaves>)),
+        )
+        .add_systems(
+            Last,
+            save_flora_changes_on_exit
+                .run_if(on_event::<AppExit>().and_then(not(resource_exists::<IgnoreSaves>))),
+        );
+    }
+}
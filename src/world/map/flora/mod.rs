@@ -1,8 +1,17 @@
+mod changes;
+#[cfg(test)]
+mod test;
+
+pub use changes::{FloraChanges, FloraKind};
+
 use std::f32::consts::PI;
 
-use rand::{thread_rng, Rng};
+use rand::Rng;
 
-use bevy::{prelude::*, utils::HashSet};
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
 use bevy_particle_systems::{
     CircleSegment, ColorOverTime, Curve, CurvePoint, EmitterShape, JitteredValue, Noise2D,
     ParticleSystem, ParticleSystemBundle, Playing, VectorOverTime, VelocityModifier,
@@ -10,21 +19,28 @@ use bevy_particle_systems::{
 use bevy_rapier2d::prelude::*;
 
 use crate::{
-    npc::Npc,
-    ui::keyboard_hint::{KeyboardHint, KEYBOARD_ICON_RADIUS},
+    player::interaction::{InteractAction, Interactable},
     world::camera::{YSort, YSortStatic, YSortStaticChild},
-    GameAssets, GameState,
+    GameAssets, GameRng, GameState,
 };
 
 use super::{
     chunk_manager::{DespawnedChunk, SpawnedChunk},
-    generation::BitMap,
+    generation::{BitMap, ChunkRngStream},
+    navigation::NavObstacle,
     poisson_sampling::generate_poisson_points_variable_radii,
+    reserved_areas::ReservedAreas,
     CHUNK_SIZE, TILE_SIZE,
 };
 
 const ROCKS_COUNT: usize = 3;
 
+/// The flavor text nodes, defined in `dialogue/world/flora.yarn`.
+pub const PICK_UP_ROCK_NODE: &str = "PickUpRock";
+pub const INSPECT_TREE_NODE: &str = "InspectTree";
+/// See `player::inventory::ITEMS`.
+pub const ROCK_ITEM: &str = "stone";
+
 const REJECTION_ITER: usize = 20;
 const MIN_RADIUS: f32 = 1.0;
 const MAX_RADIUS: f32 = 4.0;
@@ -34,7 +50,8 @@ const BUSH_1_RADIUS: f32 = 2.25;
 const BUSH_2_RADIUS: f32 = 1.25;
 const ROCK_RADIUS: f32 = 1.15;
 
-const NPC_FLORA_RADIUS: f32 = 64.0;
+const ROCK_INTERACT_RADIUS: f32 = 20.0;
+const TREE_INTERACT_RADIUS: f32 = 32.0;
 
 #[derive(Component)]
 struct SakuraPedal;
@@ -44,25 +61,44 @@ struct Flora {
     chunk_pos: IVec2,
 }
 
+/// The tiles of the spawned flora.
+/// Flora that gets despawned while it's still in here was removed from the world
+/// (e.g. picked up), as opposed to despawned together with its chunk.
+#[derive(Resource, Default, Deref, DerefMut)]
+struct SpawnedFlora(HashMap<Entity, IVec2>);
+
 impl Flora {
     fn new(chunk_pos: IVec2) -> Self {
         Self { chunk_pos }
     }
 }
 
-fn spawn_rock(commands: &mut Commands, assets: &Res<GameAssets>, chunk_pos: IVec2, pos: Vec3) {
+fn spawn_rock(
+    commands: &mut Commands,
+    assets: &Res<GameAssets>,
+    chunk_pos: IVec2,
+    pos: Vec3,
+    index: usize,
+) -> Entity {
     let collider = commands
         .spawn((
+            NavObstacle,
             Collider::cuboid(8.0, 8.0),
             TransformBundle::from_transform(Transform::from_translation(Vec3::new(0.0, 0.0, 0.0))),
         ))
         .id();
 
-    let mut rng = thread_rng();
-
     commands
         .spawn((
             Flora::new(chunk_pos),
+            Interactable::new(
+                ROCK_INTERACT_RADIUS,
+                "Pick up",
+                InteractAction::PickUp {
+                    item: ROCK_ITEM,
+                    node: Some(PICK_UP_ROCK_NODE),
+                },
+            ),
             YSort(-8.0),
             SpriteBundle {
                 texture: assets.rocks_texture.clone(),
@@ -71,10 +107,11 @@ fn spawn_rock(commands: &mut Commands, assets: &Res<GameAssets>, chunk_pos: IVec
             },
             TextureAtlas {
                 layout: assets.rocks_layout.clone(),
-                index: rng.gen_range(0..ROCKS_COUNT),
+                index,
             },
         ))
-        .push_children(&[collider]);
+        .push_children(&[collider])
+        .id()
 }
 
 fn spawn_bush(
@@ -83,7 +120,7 @@ fn spawn_bush(
     chunk_pos: IVec2,
     pos: Vec3,
     index: usize,
-) {
+) -> Entity {
     let texture = if index == 0 {
         assets.bush1.clone()
     } else {
@@ -97,6 +134,7 @@ fn spawn_bush(
 
     let collider = commands
         .spawn((
+            NavObstacle,
             c,
             TransformBundle::from_transform(Transform::from_translation(Vec3::new(0.0, -8.0, 0.0))),
         ))
@@ -112,10 +150,16 @@ fn spawn_bush(
                 ..default()
             },
         ))
-        .push_children(&[collider]);
+        .push_children(&[collider])
+        .id()
 }
 
-fn spawn_tree(commands: &mut Commands, assets: &Res<GameAssets>, chunk_pos: IVec2, pos: Vec3) {
+fn spawn_tree(
+    commands: &mut Commands,
+    assets: &Res<GameAssets>,
+    chunk_pos: IVec2,
+    pos: Vec3,
+) -> Entity {
     let pos = pos + Vec3::new(0.0, 48.0, 0.0);
 
     let trunk = commands
@@ -138,8 +182,15 @@ fn spawn_tree(commands: &mut Commands, assets: &Res<GameAssets>, chunk_pos: IVec
         ))
         .id();
 
+    // The tree is positioned at its crown, the base is where the player can reach it.
     let collider = commands
         .spawn((
+            NavObstacle,
+            Interactable::new(
+                TREE_INTERACT_RADIUS,
+                "Inspect",
+                InteractAction::RunNode(INSPECT_TREE_NODE),
+            ),
             Collider::cuboid(16.0, 8.0),
             TransformBundle::from_transform(Transform::from_translation(Vec3::new(
                 0.0, -48.0, 0.0,
@@ -199,56 +250,67 @@ fn spawn_tree(commands: &mut Commands, assets: &Res<GameAssets>, chunk_pos: IVec
                 ..default()
             },
         ))
-        .push_children(&[trunk, shadow, collider, tree_pedals]);
+        .push_children(&[trunk, shadow, collider, tree_pedals])
+        .id()
 }
 
-fn spawn_flora(
-    commands: &mut Commands,
-    assets: &Res<GameAssets>,
-    bitmap: &mut ResMut<BitMap>,
-    chunk_pos: IVec2,
-    ipos: IVec2,
-    radius: f32,
-) {
-    let v = IVec2::new(
-        ipos.x + chunk_pos.x * CHUNK_SIZE as i32,
-        ipos.y + chunk_pos.y * CHUNK_SIZE as i32,
-    );
-    let pos = TILE_SIZE * Vec3::new(v.x as f32, v.y as f32, 0.0);
-
-    if v.x.unsigned_abs() % CHUNK_SIZE == 0 || v.y.unsigned_abs() % CHUNK_SIZE == 0 {
-        return;
+/// The flora the generation places at the tile, if there is enough space for it.
+fn generated_flora_kind(bitmap: &mut BitMap, v: IVec2, radius: f32) -> Option<FloraKind> {
+    if v.x.unsigned_abs().is_multiple_of(CHUNK_SIZE)
+        || v.y.unsigned_abs().is_multiple_of(CHUNK_SIZE)
+    {
+        return None;
     }
+
+    let fits = |bitmap: &mut BitMap, offsets: &[IVec2]| {
+        offsets
+            .iter()
+            .all(|offset| bitmap.get_flora_flag(v + *offset))
+    };
     if radius > TREE_RADIUS {
-        if !(bitmap.get_flora_flag(v)
-            && bitmap.get_flora_flag(v + IVec2::new(1, 0))
-            && bitmap.get_flora_flag(v + IVec2::new(0, 1))
-            && bitmap.get_flora_flag(v + IVec2::new(1, 1))
-            && bitmap.get_flora_flag(v + IVec2::new(2, 0))
-            && bitmap.get_flora_flag(v + IVec2::new(-2, 0)))
-        {
-            return;
-        }
-        spawn_tree(commands, assets, chunk_pos, pos);
+        let offsets = [
+            IVec2::ZERO,
+            IVec2::new(1, 0),
+            IVec2::new(0, 1),
+            IVec2::new(1, 1),
+            IVec2::new(2, 0),
+            IVec2::new(-2, 0),
+        ];
+        fits(bitmap, &offsets).then_some(FloraKind::Tree)
     } else if radius > BUSH_1_RADIUS {
-        if !(bitmap.get_flora_flag(v)
-            && bitmap.get_flora_flag(v - IVec2::Y)
-            && bitmap.get_flora_flag(v + IVec2::X)
-            && bitmap.get_flora_flag(v - IVec2::X))
-        {
-            return;
-        }
-        spawn_bush(commands, assets, chunk_pos, pos, 0);
+        let offsets = [IVec2::ZERO, -IVec2::Y, IVec2::X, -IVec2::X];
+        fits(bitmap, &offsets).then_some(FloraKind::LargeBush)
     } else if radius > BUSH_2_RADIUS {
-        if !(bitmap.get_flora_flag(v) && bitmap.get_flora_flag(v - IVec2::Y)) {
-            return;
-        }
-        spawn_bush(commands, assets, chunk_pos, pos, 1);
+        fits(bitmap, &[IVec2::ZERO, -IVec2::Y]).then_some(FloraKind::SmallBush)
     } else if radius > ROCK_RADIUS {
-        if !bitmap.get_flora_flag(v) {
-            return;
-        }
-        spawn_rock(commands, assets, chunk_pos, pos);
+        fits(bitmap, &[IVec2::ZERO]).then_some(FloraKind::Rock)
+    } else {
+        None
+    }
+}
+
+/// Only rocks have variants.
+fn draw_variant(rng: &mut GameRng, kind: FloraKind) -> usize {
+    match kind {
+        FloraKind::Rock => rng.gen_range(0..ROCKS_COUNT),
+        FloraKind::Tree | FloraKind::LargeBush | FloraKind::SmallBush => 0,
+    }
+}
+
+fn spawn_flora(
+    commands: &mut Commands,
+    assets: &Res<GameAssets>,
+    chunk_pos: IVec2,
+    v: IVec2,
+    kind: FloraKind,
+    variant: usize,
+) -> Entity {
+    let pos = TILE_SIZE * v.as_vec2().extend(0.0);
+    match kind {
+        FloraKind::Tree => spawn_tree(commands, assets, chunk_pos, pos),
+        FloraKind::LargeBush => spawn_bush(commands, assets, chunk_pos, pos, 0),
+        FloraKind::SmallBush => spawn_bush(commands, assets, chunk_pos, pos, 1),
+        FloraKind::Rock => spawn_rock(commands, assets, chunk_pos, pos, variant),
     }
 }
 
@@ -256,11 +318,14 @@ fn spawn_flora_chunks(
     mut commands: Commands,
     assets: Res<GameAssets>,
     mut bitmap: ResMut<BitMap>,
+    changes: Res<FloraChanges>,
+    reserved_areas: Res<ReservedAreas>,
+    mut spawned_flora: ResMut<SpawnedFlora>,
     mut ev_spawned_chunk: EventReader<SpawnedChunk>,
 ) {
     for ev in ev_spawned_chunk.read() {
-        let seed =
-            bitmap.seed() as u64 + ev.pos.x.unsigned_abs() as u64 + ev.pos.y.unsigned_abs() as u64;
+        let seed = bitmap.chunk_seed(ev.pos, ChunkRngStream::FloraPositions);
+        let chunk_origin = (ev.pos * CHUNK_SIZE as i32).as_vec2();
 
         // Because we discretize our positions here, we have to make sure that no two
         // flora positions map to the same IVec2.
@@ -272,6 +337,8 @@ fn spawn_flora_chunks(
             CHUNK_SIZE as f32 * Vec2::ONE,
             REJECTION_ITER,
             seed,
+            // The flora is placed on the tile the point falls into.
+            |p| reserved_areas.contains((chunk_origin + p.floor()) * TILE_SIZE),
         )
         .into_iter()
         .map(|p_r| {
@@ -281,61 +348,58 @@ fn spawn_flora_chunks(
         .filter(|(p, _)| unique_points.insert(*p))
         .collect();
 
+        let mut rng = bitmap.chunk_rng(ev.pos, ChunkRngStream::FloraVariants);
+        let delta = changes.chunk(ev.pos);
         for (p, r) in points_with_radius {
-            spawn_flora(&mut commands, &assets, &mut bitmap, ev.pos, p, r);
+            let v = p + ev.pos * CHUNK_SIZE as i32;
+            let Some(kind) = generated_flora_kind(&mut bitmap, v, r) else {
+                continue;
+            };
+            // Drawn even if it was removed, so that the rest of the flora keeps its variants.
+            let variant = draw_variant(&mut rng, kind);
+            if delta.is_some_and(|delta| delta.removed.contains(&v)) {
+                continue;
+            }
+            let entity = spawn_flora(&mut commands, &assets, ev.pos, v, kind, variant);
+            spawned_flora.insert(entity, v);
+        }
+
+        for (v, kind) in delta
+            .map(|delta| delta.added.as_slice())
+            .unwrap_or_default()
+        {
+            let variant = draw_variant(&mut rng, *kind);
+            let entity = spawn_flora(&mut commands, &assets, ev.pos, *v, *kind, variant);
+            spawned_flora.insert(entity, *v);
         }
     }
 }
 
 fn despawn_flora_chunks(
     mut commands: Commands,
+    mut spawned_flora: ResMut<SpawnedFlora>,
     q_floras: Query<(Entity, &Flora)>,
     mut ev_despawned_chunk: EventReader<DespawnedChunk>,
 ) {
     for ev in ev_despawned_chunk.read() {
         for (entity, flora) in &q_floras {
             if flora.chunk_pos == ev.chunk_pos {
+                spawned_flora.remove(&entity);
                 commands.entity(entity).despawn_recursive();
             }
         }
     }
 }
 
-fn despawn_flora_around_start_hint(
-    mut commands: Commands,
-    q_keyboard_icon: Query<&Transform, (With<KeyboardHint>, Without<Flora>)>,
-    q_floras: Query<(Entity, &Transform), Added<Flora>>,
-) {
-    let start_hint_transform = match q_keyboard_icon.get_single() {
-        Ok(r) => r,
-        Err(_) => return,
-    };
-
-    for (entity, flora_transform) in &q_floras {
-        if flora_transform
-            .translation
-            .distance_squared(start_hint_transform.translation)
-            <= KEYBOARD_ICON_RADIUS.powi(2)
-        {
-            commands.entity(entity).despawn_recursive();
-        }
-    }
-}
-
-fn despawn_flora_around_npcs(
-    mut commands: Commands,
-    q_npcs: Query<&Transform, (With<Npc>, Without<Flora>)>,
-    q_floras: Query<(Entity, &Transform), Added<Flora>>,
+/// Remember removed flora, so that it stays removed when the chunk is spawned again.
+fn record_removed_flora(
+    mut changes: ResMut<FloraChanges>,
+    mut spawned_flora: ResMut<SpawnedFlora>,
+    mut removed_floras: RemovedComponents<Flora>,
 ) {
-    for (entity, flora_transform) in &q_floras {
-        for npc_transform in &q_npcs {
-            if flora_transform
-                .translation
-                .distance_squared(npc_transform.translation)
-                <= NPC_FLORA_RADIUS.powi(2)
-            {
-                commands.entity(entity).despawn_recursive();
-            }
+    for entity in removed_floras.read() {
+        if let Some(tile) = spawned_flora.remove(&entity) {
+            changes.remove(tile);
         }
     }
 }
@@ -350,16 +414,17 @@ pub struct FloraPlugin;
 
 impl Plugin for FloraPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (
-                play_sakura_pedal_particles.before(spawn_flora_chunks),
-                spawn_flora_chunks,
-                despawn_flora_chunks,
-                despawn_flora_around_start_hint,
-                despawn_flora_around_npcs,
-            )
-                .run_if(in_state(GameState::Gaming)),
-        );
+        app.add_plugins(changes::FloraChangesPlugin)
+            .init_resource::<SpawnedFlora>()
+            .add_systems(
+                Update,
+                (
+                    play_sakura_pedal_particles.before(spawn_flora_chunks),
+                    spawn_flora_chunks,
+                    despawn_flora_chunks,
+                    record_removed_flora,
+                )
+                    .run_if(in_state(GameState::Gaming)),
+            );
     }
 }
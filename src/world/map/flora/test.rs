@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+
+use super::changes::ChunkFloraDelta;
+use super::{FloraChanges, FloraKind};
+
+#[test]
+fn removing_and_adding_flora() {
+    let mut changes = FloraChanges::new(42.0);
+    assert_eq!(changes.chunk(IVec2::ZERO), None);
+
+    changes.remove(IVec2::new(3, 4));
+    changes.add(IVec2::new(-1, 5), FloraKind::Tree);
+    changes.add(IVec2::new(-1, 5), FloraKind::Rock);
+    assert_eq!(
+        changes.chunk(IVec2::ZERO).map(|delta| delta.removed.len()),
+        Some(1)
+    );
+    assert_eq!(
+        changes
+            .chunk(IVec2::new(-1, 0))
+            .map(|delta| delta.added.clone()),
+        Some(vec![(IVec2::new(-1, 5), FloraKind::Rock)])
+    );
+
+    // Removing added flora just takes it back.
+    changes.remove(IVec2::new(-1, 5));
+    assert_eq!(
+        changes.chunk(IVec2::new(-1, 0)),
+        Some(&ChunkFloraDelta::default())
+    );
+}
+
+#[test]
+fn save_round_trip() {
+    let mut changes = FloraChanges::new(42.0);
+    changes.remove(IVec2::new(3, 4));
+    changes.remove(IVec2::new(-20, 17));
+    changes.add(IVec2::new(5, 5), FloraKind::SmallBush);
+    changes.add(IVec2::new(6, 5), FloraKind::LargeBush);
+
+    let mut restored = FloraChanges::new(42.0);
+    assert!(restored.load_save_string(&changes.to_save_string()));
+    for chunk in [IVec2::ZERO, IVec2::new(-2, 1)] {
+        assert_eq!(restored.chunk(chunk), changes.chunk(chunk));
+    }
+
+    // Saves from a different world don't apply.
+    let mut other = FloraChanges::new(7.0);
+    assert!(!other.load_save_string(&changes.to_save_string()));
+    assert_eq!(other.chunk(IVec2::ZERO), None);
+}
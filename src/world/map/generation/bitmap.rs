@@ -2,23 +2,47 @@ use bevy::prelude::*;
 use bevy::utils::HashSet;
 use chrono::{Timelike, Utc};
 use noisy_bevy::simplex_noise_2d_seeded;
+use rand::SeedableRng;
 
-use crate::world::map::TILE_SIZE;
+use crate::{world::map::TILE_SIZE, GameRng};
 
 use super::bitmask::{BitMasks, GRASS_FLOWER_SUPER_POSITION};
+use super::poi::PointOfInterest;
 use super::{
     TileCollision, TileType, BITMASK_BOT_LEFT, BITMASK_BOT_RIGHT, BITMASK_TOP_LEFT,
-    BITMASK_TOP_RIGHT, CHUNK_SIZE, EMPTY_TYPE_MASK, FLOWER_HEIGHT_LEVEL, FLOWER_NOISE_ZOOM,
-    GRASS_TYPE_MASK, INVALID_TILE, NOISE_ZOOM, PATH_TYPE_MASK, RENDERED_CHUNKS_RADIUS,
-    WATER_HEIGH_LEVEL, WATER_SPARKLE_HEIGHT_LEVEL_MAX, WATER_SPARKLE_HEIGHT_LEVEL_MIN,
-    WATER_SPARKLE_NOISE_ZOOM, WATER_SPARKLE_TYPE_MASK, WATER_TYPE_MASK,
+    BITMASK_TOP_RIGHT, BRIDGE_TYPE_MASK, CHUNK_SIZE, EMPTY_TYPE_MASK, FLOWER_HEIGHT_LEVEL,
+    FLOWER_NOISE_ZOOM, GRASS_TYPE_MASK, INVALID_TILE, NOISE_ZOOM, PATH_TYPE_MASK,
+    RENDERED_CHUNKS_RADIUS, WATER_HEIGH_LEVEL, WATER_SPARKLE_HEIGHT_LEVEL_MAX,
+    WATER_SPARKLE_HEIGHT_LEVEL_MIN, WATER_SPARKLE_NOISE_ZOOM, WATER_SPARKLE_TYPE_MASK,
+    WATER_TYPE_MASK,
 };
 
+/// Each kind of random choice in a chunk draws from its own stream,
+/// so that e.g. adding a flora variant doesn't move the flora around.
+#[derive(Clone, Copy)]
+pub enum ChunkRngStream {
+    Tiles = 1,
+    FloraPositions = 2,
+    FloraVariants = 3,
+}
+
+/// SplitMix64, mixes all the input bits into the output.
+fn mix(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
 #[derive(Resource)]
 pub struct BitMap {
     seed: f32,
     vertices: Vec<Vec2>,
     edges: HashSet<(usize, usize)>,
+    // The edges of the minimum spanning tree, the rest connect the outer vertices.
+    spanning_edges: HashSet<(usize, usize)>,
+    path_curves: Vec<[Vec2; 4]>,
+    points_of_interest: Vec<PointOfInterest>,
 
     grass_mask: BitMasks,
     path_mask: BitMasks,
@@ -38,6 +62,9 @@ impl Default for BitMap {
             seed: Utc::now().nanosecond() as f32,
             vertices: Vec::new(),
             edges: HashSet::new(),
+            spanning_edges: HashSet::new(),
+            path_curves: Vec::new(),
+            points_of_interest: Vec::new(),
 
             grass_mask: BitMasks::grass(),
             path_mask: BitMasks::path(),
@@ -53,6 +80,11 @@ impl Default for BitMap {
 }
 
 impl BitMap {
+    /// The same seed generates the same world, e.g. for replays.
+    pub fn with_seed(seed: f32) -> Self {
+        Self { seed, ..default() }
+    }
+
     fn tileset_quadrant(&self, v: IVec2) -> u16 {
         if v.x >= 0 && v.y >= 0 {
             1
@@ -178,6 +210,22 @@ impl BitMap {
         self.get_tileset_raw(v).0 & PATH_TYPE_MASK == PATH_TYPE_MASK
     }
 
+    pub fn get_bridge_flag(&mut self, v: IVec2) -> bool {
+        self.get_tileset_raw(v).0 & BRIDGE_TYPE_MASK == BRIDGE_TYPE_MASK
+    }
+
+    pub fn set_bridge_flag(&mut self, v: IVec2) {
+        self.set_type_index(v, WATER_TYPE_MASK | BRIDGE_TYPE_MASK);
+    }
+
+    /// Whether the tile closest to the given world position is a bridge.
+    pub fn is_on_bridge(&mut self, pos: Vec2) -> bool {
+        // Tiles are centered on their position,
+        // so the vertices lie on the bottom left corner.
+        let v = (pos / TILE_SIZE + 0.5).round().as_ivec2();
+        self.get_bridge_flag(v)
+    }
+
     pub fn get_water_sparkle_flag(&mut self, v: IVec2) -> bool {
         if self.get_tileset_raw(v).0 & WATER_SPARKLE_TYPE_MASK != WATER_SPARKLE_TYPE_MASK {
             return false;
@@ -238,6 +286,27 @@ impl BitMap {
         noise + secondary_noise
     }
 
+    /// Whether the given tile lies below the sea level.
+    /// Unlike `collapse_water` this doesn't set any flags,
+    /// so it also works on tiles that were overwritten by paths.
+    fn is_natural_water(&self, v: IVec2) -> bool {
+        self.water_height(v) < WATER_HEIGH_LEVEL
+    }
+
+    /// Whether the given tile is (or will collapse to) water.
+    /// Unlike `collapse_water` this doesn't set any flags.
+    pub fn is_water(&mut self, v: IVec2) -> bool {
+        if self.get_empty_flag(v) {
+            self.is_natural_water(v)
+        } else {
+            self.get_water_flag(v)
+        }
+    }
+
+    fn is_walkable(&mut self, v: IVec2) -> bool {
+        !self.collapse_water(v) || self.get_bridge_flag(v)
+    }
+
     /// Determine if a given tile is water or grass.
     /// This will only set the water bit flag,
     /// not the actual tile index.
@@ -261,7 +330,7 @@ impl BitMap {
         is_water
     }
 
-    fn get_flower_tile(&self, v: IVec2) -> u16 {
+    fn get_flower_tile(&self, rng: &mut GameRng, v: IVec2) -> u16 {
         let w = Vec2::new(v.x as f32, v.y as f32);
 
         let noise = simplex_noise_2d_seeded(w * FLOWER_NOISE_ZOOM, self.seed() + 2.0);
@@ -270,29 +339,30 @@ impl BitMap {
         let h = noise + secondary_noise;
 
         if h < FLOWER_HEIGHT_LEVEL {
-            self.flower_mask.get_index(1)
+            self.flower_mask.get_index(rng, 1)
         } else {
-            self.flower_mask.get_index(0)
+            self.flower_mask.get_index(rng, 0)
         }
     }
 
     /// Determine which tile to place. This will collapse
     /// the neigbhoring four tiles to see if they are grass or water.
     /// This is used for all tiles, both grass and water.
-    fn collapse_tile(&mut self, v: IVec2) {
+    fn collapse_tile(&mut self, rng: &mut GameRng, v: IVec2) {
         let tile = match self.tile_type(v) {
             TileType::GrassWater => {
                 let t = self.neigbhor_bitmask_grass(v);
-                self.grass_mask.get_index(t)
+                self.grass_mask.get_index(rng, t)
             }
             TileType::PathOrGrass => {
                 let t = self.neigbhor_bitmask_path(v);
-                self.path_mask.get_index(t)
+                self.path_mask.get_index(rng, t)
             }
         };
 
         if tile == GRASS_FLOWER_SUPER_POSITION {
-            self.set_tileset(v, self.get_flower_tile(v));
+            let flower_tile = self.get_flower_tile(rng, v);
+            self.set_tileset(v, flower_tile);
         } else {
             self.set_tileset(v, tile);
         }
@@ -310,6 +380,17 @@ impl BitMap {
         mask
     }
 
+    /// Same as `neigbhor_bitmask_grass`, but bridges count as walkable.
+    fn neigbhor_bitmask_walkable(&mut self, v: IVec2) -> u16 {
+        let mut mask = 0u16;
+
+        mask |= BITMASK_BOT_LEFT * self.is_walkable(v) as u16;
+        mask |= BITMASK_TOP_LEFT * self.is_walkable(v + IVec2::new(0, 1)) as u16;
+        mask |= BITMASK_TOP_RIGHT * self.is_walkable(v + IVec2::new(1, 1)) as u16;
+        mask |= BITMASK_BOT_RIGHT * self.is_walkable(v + IVec2::new(1, 0)) as u16;
+        mask
+    }
+
     fn neigbhor_bitmask_path(&mut self, v: IVec2) -> u16 {
         let mut mask = 0u16;
 
@@ -324,15 +405,17 @@ impl BitMap {
     /// The tile index corresponds to the index in the tile atlas.
     /// It is not garuanteed to be a valid tile, i.e. it can be
     /// an invalid tile.
-    pub fn get_tile_index(&mut self, v: IVec2) -> u16 {
+    /// The tile variant is drawn from `rng`, see `chunk_rng`.
+    pub fn get_tile_index(&mut self, rng: &mut GameRng, v: IVec2) -> u16 {
         self.collapse_water(v);
-        self.collapse_tile(v);
+        self.collapse_tile(rng, v);
         self.get_tileset(v)
     }
 
     /// Determine if a given tile should have a collision and what type.
+    /// Bridges are treated like land, so they get the same colliders as the shore.
     pub fn get_tile_collision(&mut self, v: IVec2) -> TileCollision {
-        let mask = self.neigbhor_bitmask_grass(v);
+        let mask = self.neigbhor_bitmask_walkable(v);
         if mask == !BITMASK_BOT_LEFT & BITMASK_TOP_LEFT | BITMASK_TOP_RIGHT & !BITMASK_BOT_RIGHT {
             TileCollision::BotRect
         } else if mask
@@ -368,6 +451,13 @@ impl BitMap {
         }
     }
 
+    /// Whether the whole tile can be walked on,
+    /// i.e. all four corners are either land or bridges.
+    pub fn get_walkable_flag(&mut self, v: IVec2) -> bool {
+        self.neigbhor_bitmask_walkable(v)
+            == BITMASK_BOT_LEFT | BITMASK_TOP_LEFT | BITMASK_TOP_RIGHT | BITMASK_BOT_RIGHT
+    }
+
     /// Check if flora can be placed on the given tile.
     /// Flora can only be placed if the tile is surrounded by grass tiles.
     pub fn get_flora_flag(&mut self, v: IVec2) -> bool {
@@ -381,14 +471,39 @@ impl BitMap {
         self.seed
     }
 
+    /// Derived from the world seed and the chunk position only,
+    /// so a chunk comes out the same across runs and when it's respawned.
+    pub fn chunk_seed(&self, chunk_pos: IVec2, stream: ChunkRngStream) -> u64 {
+        let seed = mix(self.seed.to_bits() as u64 ^ ((stream as u64) << 32));
+        let seed = mix(seed ^ chunk_pos.x as u32 as u64);
+        mix(seed ^ chunk_pos.y as u32 as u64)
+    }
+
+    pub fn chunk_rng(&self, chunk_pos: IVec2, stream: ChunkRngStream) -> GameRng {
+        GameRng::seed_from_u64(self.chunk_seed(chunk_pos, stream))
+    }
+
     pub fn get_furthest_hotspots(&self, number_of_hotspots: usize) -> Vec<Vec2> {
+        self.get_furthest_hotspot_indices(number_of_hotspots)
+            .into_iter()
+            .map(|i| self.vertices[i])
+            .collect()
+    }
+
+    /// Same as `get_furthest_hotspots`, but returns the indices of the vertices.
+    pub fn get_furthest_hotspot_indices(&self, number_of_hotspots: usize) -> Vec<usize> {
         if number_of_hotspots >= self.vertices.len() {
             error!("Requesting more hotspots than exist in the bitmap! This should never happen. It means that you world proc gen isn't working properly");
-            return self.vertices.clone();
+            return (0..self.vertices.len()).collect();
         }
 
-        let mut hotspots = self.vertices.clone();
-        hotspots.sort_by(|a, b| a.length_squared().partial_cmp(&b.length_squared()).unwrap());
+        let mut hotspots: Vec<usize> = (0..self.vertices.len()).collect();
+        hotspots.sort_by(|a, b| {
+            self.vertices[*a]
+                .length_squared()
+                .partial_cmp(&self.vertices[*b].length_squared())
+                .unwrap()
+        });
         let range = hotspots.len() - number_of_hotspots..hotspots.len();
         hotspots[range].to_vec()
     }
@@ -399,8 +514,52 @@ impl BitMap {
         }
     }
 
-    pub fn set_edges(&mut self, edges: &HashSet<(usize, usize)>) {
+    pub fn set_edges(
+        &mut self,
+        edges: &HashSet<(usize, usize)>,
+        spanning_edges: &HashSet<(usize, usize)>,
+    ) {
         self.edges.clone_from(edges);
+        self.spanning_edges.clone_from(spanning_edges);
+    }
+
+    pub fn edges(&self) -> &HashSet<(usize, usize)> {
+        &self.edges
+    }
+
+    /// Whether the edge is part of the minimum spanning tree (Kruskal)
+    /// or was added to connect the outer vertices.
+    pub fn is_spanning_edge(&self, edge: (usize, usize)) -> bool {
+        self.spanning_edges.contains(&edge)
+    }
+
+    pub fn vertices(&self) -> &Vec<Vec2> {
+        &self.vertices
+    }
+
+    pub fn vertex_degree(&self, vertex_index: usize) -> usize {
+        self.edges
+            .iter()
+            .filter(|&&(u, v)| u == vertex_index || v == vertex_index)
+            .count()
+    }
+
+    /// The bezier curves (start, end, first control, second control)
+    /// of all the paths in tile coordinates.
+    pub fn path_curves(&self) -> &Vec<[Vec2; 4]> {
+        &self.path_curves
+    }
+
+    pub fn push_path_curve(&mut self, curve: [Vec2; 4]) {
+        self.path_curves.push(curve);
+    }
+
+    pub fn points_of_interest(&self) -> &Vec<PointOfInterest> {
+        &self.points_of_interest
+    }
+
+    pub fn set_points_of_interest(&mut self, points_of_interest: Vec<PointOfInterest>) {
+        self.points_of_interest = points_of_interest;
     }
 
     pub fn get_origin_edges(&self) -> Vec<Vec2> {
@@ -418,7 +577,7 @@ impl BitMap {
             .collect()
     }
 
-    pub fn get_water_sparkle_indices(&mut self) -> Vec<u16> {
-        self.water_sparkle_mask.get_animation_indices()
+    pub fn get_water_sparkle_indices(&mut self, rng: &mut GameRng) -> Vec<u16> {
+        self.water_sparkle_mask.get_animation_indices(rng)
     }
 }
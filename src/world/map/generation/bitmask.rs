@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 
-use rand::{thread_rng, Rng};
+use rand::Rng;
+
+use crate::GameRng;
 
 use super::{
     BITMASK_BOT_LEFT, BITMASK_BOT_RIGHT, BITMASK_TOP_LEFT, BITMASK_TOP_RIGHT, INVALID_TILE,
@@ -183,17 +185,15 @@ impl BitMasks {
 }
 
 impl BitMasks {
-    pub fn get_index(&self, mask: u16) -> u16 {
+    pub fn get_index(&self, rng: &mut GameRng, mask: u16) -> u16 {
         let binding = vec![INVALID_TILE];
         let indices = self.masks.get(&mask).unwrap_or(&binding);
-        let mut rng = thread_rng();
         let index = rng.gen_range(0..indices.len());
         indices[index]
     }
 
-    pub fn get_animation_indices(&self) -> Vec<u16> {
+    pub fn get_animation_indices(&self, rng: &mut GameRng) -> Vec<u16> {
         let binding = vec![INVALID_TILE];
-        let mut rng = thread_rng();
         let index = rng.gen_range(0..self.masks.len()) as u16;
         self.masks.get(&index).unwrap_or(&binding).to_vec()
     }
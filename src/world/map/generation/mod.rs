@@ -1,15 +1,22 @@
+#[cfg(test)]
+mod test;
+
 pub mod bitmap;
+pub mod poi;
 
 mod bitmask;
 mod graph;
 mod path;
 
-pub use bitmap::BitMap;
+pub use bitmap::{BitMap, ChunkRngStream};
 
 use bevy::prelude::*;
 
 use super::{CHUNK_SIZE, RENDERED_CHUNKS_RADIUS};
 
+// The number of vertices (furthest away from the origin) that are reserved for NPCs.
+pub const NPC_HOTSPOTS: usize = 4;
+
 const NOISE_ZOOM: f32 = 0.02;
 const FLOWER_NOISE_ZOOM: f32 = 0.1;
 const WATER_SPARKLE_NOISE_ZOOM: f32 = 0.1;
@@ -26,6 +33,8 @@ const WATER_TYPE_MASK: u8 = 1 << 1;
 const GRASS_TYPE_MASK: u8 = 1 << 2;
 const PATH_TYPE_MASK: u8 = 1 << 3;
 const WATER_SPARKLE_TYPE_MASK: u8 = 1 << 4;
+// Always set together with the water mask, bridges don't replace the water below.
+const BRIDGE_TYPE_MASK: u8 = 1 << 5;
 const INVALID_TILE: u16 = 15 * 16;
 
 const BITMASK_TOP_RIGHT: u16 = 1 << 0;
@@ -37,7 +46,7 @@ pub struct MapGenerationPlugin;
 
 impl Plugin for MapGenerationPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(path::PathGenerationPlugin)
+        app.add_plugins((path::PathGenerationPlugin, poi::PoiGenerationPlugin))
             .init_resource::<BitMap>();
     }
 }
@@ -3,7 +3,12 @@ use rand::{Rng, SeedableRng};
 use bevy::prelude::*;
 use noisy_bevy::simplex_noise_2d_seeded;
 
-use crate::{world::map::poisson_sampling::generate_poisson_points, GameRng, GameState};
+use crate::{
+    world::map::{
+        poisson_sampling::generate_poisson_points, reserved_areas::ReservedAreas, TILE_SIZE,
+    },
+    GameRng, GameState,
+};
 
 use super::{
     graph::{connect_outer_vertices, kruskals_edges},
@@ -12,20 +17,32 @@ use super::{
 
 const NOISE_ZOOM: f32 = 0.02;
 const START_FILL_RADIUS: i32 = 15;
+// In tiles, so that the player doesn't start inside of a tree.
+const START_RESERVED_RADIUS: f32 = 4.0;
 
 const MIN_RADIUS: i32 = 1;
 const MAX_RADIUS: i32 = MIN_RADIUS + 1;
 const MIN_RADIUS_GRASS: i32 = MAX_RADIUS + 1;
 const MAX_RADIUS_GRASS: i32 = MIN_RADIUS_GRASS + 3;
+// Bridges need to be wider than paths, otherwise the player won't fit through the colliders.
+pub const BRIDGE_RADIUS: i32 = 3;
+// How many different curves we try for each edge to avoid water.
+const ROUTE_CANDIDATES: usize = 8;
 
 const DISK_RADIUS: f32 = 35.0;
-const SAMPLE_RATE: usize = 3 * DISK_RADIUS as usize;
+pub const SAMPLE_RATE: usize = 3 * DISK_RADIUS as usize;
 const REGION_SIZE: Vec2 = Vec2::new(120.0, 120.0);
 const POISSON_REJECTION_ITER: usize = 20;
 
 // Use bezier curve to compute the points along
 // the curve based on the given sample_size.
-fn compute_path_points(p1: Vec2, p2: Vec2, c1: Vec2, c2: Vec2, sample_size: usize) -> Vec<IVec2> {
+pub fn compute_path_points(
+    p1: Vec2,
+    p2: Vec2,
+    c1: Vec2,
+    c2: Vec2,
+    sample_size: usize,
+) -> Vec<IVec2> {
     fn curve(p1: Vec2, p2: Vec2, c1: Vec2, c2: Vec2, t: f32) -> Vec2 {
         (1.0 - t).powi(3) * p1
             + 3.0 * t * (1.0 - t).powi(2) * c1
@@ -57,6 +74,30 @@ fn generate_bezier_points(rng: &mut GameRng, p1: Vec2, p2: Vec2) -> (Vec2, Vec2)
     (c1, c2)
 }
 
+/// Try several control points and pick the curve that crosses the least water.
+/// On ties the first candidate wins.
+fn route_around_water(
+    bitmap: &mut ResMut<BitMap>,
+    rng: &mut GameRng,
+    p1: Vec2,
+    p2: Vec2,
+) -> (Vec2, Vec2) {
+    let candidates: Vec<(Vec2, Vec2)> = (0..ROUTE_CANDIDATES)
+        .map(|_| generate_bezier_points(rng, p1, p2))
+        .collect();
+
+    candidates
+        .into_iter()
+        .min_by_key(|(c1, c2)| {
+            compute_path_points(p1, p2, *c1, *c2, SAMPLE_RATE)
+                .into_iter()
+                .filter(|v| bitmap.is_water(*v))
+                .count()
+        })
+        // Straight line, only happens if there are no candidates at all.
+        .unwrap_or((p1, p2))
+}
+
 fn fill_path_point(bitmap: &mut ResMut<BitMap>, v: IVec2) {
     let w = Vec2::new(v.x as f32, v.y as f32);
 
@@ -75,6 +116,14 @@ fn fill_path_point(bitmap: &mut ResMut<BitMap>, v: IVec2) {
         for y in -radius_grass..=radius_grass {
             let offset = IVec2::new(x, y);
             let dis = offset.length_squared();
+            // Keep the water intact and place a bridge on top of it instead.
+            if bitmap.is_water(v + offset) {
+                if dis < BRIDGE_RADIUS.pow(2) {
+                    bitmap.set_bridge_flag(v + offset);
+                }
+                continue;
+            }
+
             if dis < sqrt_radius {
                 bitmap.set_type_index(v + offset, PATH_TYPE_MASK);
             } else if dis < sqrt_radius_grass && !bitmap.get_path_flag(v + offset) {
@@ -90,7 +139,7 @@ fn fill_path_points(bitmap: &mut ResMut<BitMap>, points: Vec<IVec2>) {
     }
 }
 
-fn generate_path(mut bitmap: ResMut<BitMap>) {
+pub fn generate_path(mut bitmap: ResMut<BitMap>) {
     let vertices = generate_poisson_points(
         DISK_RADIUS,
         REGION_SIZE,
@@ -99,22 +148,29 @@ fn generate_path(mut bitmap: ResMut<BitMap>) {
     );
     bitmap.set_vertices(&vertices);
 
-    let mut edges = kruskals_edges(&vertices);
+    let spanning_edges = kruskals_edges(&vertices);
+    let mut edges = spanning_edges.clone();
     connect_outer_vertices(&vertices, &mut edges);
-    bitmap.set_edges(&edges);
+    bitmap.set_edges(&edges, &spanning_edges);
     let mut edges: Vec<(usize, usize)> = edges.into_iter().collect();
     edges.sort();
 
     let mut rng = GameRng::seed_from_u64(bitmap.seed() as u64);
     for (u, v) in edges {
         let (p1, p2) = (vertices[u], vertices[v]);
-        let (c1, c2) = generate_bezier_points(&mut rng, p1, p2);
+        let (c1, c2) = route_around_water(&mut bitmap, &mut rng, p1, p2);
+        bitmap.push_path_curve([p1, p2, c1, c2]);
         let points = compute_path_points(p1, p2, c1, c2, SAMPLE_RATE);
         fill_path_points(&mut bitmap, points);
     }
 }
 
-fn fill_player_starting_position(mut bitmap: ResMut<BitMap>) {
+fn fill_player_starting_position(
+    mut bitmap: ResMut<BitMap>,
+    mut reserved_areas: ResMut<ReservedAreas>,
+) {
+    reserved_areas.reserve_circle(Vec2::ZERO, START_RESERVED_RADIUS * TILE_SIZE);
+
     for x in -START_FILL_RADIUS..=START_FILL_RADIUS {
         for y in -START_FILL_RADIUS..=START_FILL_RADIUS {
             let v = IVec2::new(x, y);
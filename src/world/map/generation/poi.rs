@@ -0,0 +1,264 @@
+use std::f32::consts::TAU;
+use std::ops::{Range, RangeInclusive};
+
+use rand::{Rng, SeedableRng};
+use strum_macros::{Display, EnumIter};
+
+use bevy::prelude::*;
+
+use crate::{
+    world::map::{reserved_areas::ReservedAreas, TILE_SIZE},
+    GameRng, GameState,
+};
+
+use super::{
+    path::{compute_path_points, generate_path, BRIDGE_RADIUS, SAMPLE_RATE},
+    BitMap, NPC_HOTSPOTS,
+};
+
+// Added to the seed of the bitmap so that we don't get
+// the same random numbers as the path generation.
+const POI_SEED_OFFSET: u64 = 17;
+// Distance in tiles from the vertex to the point of interest.
+// The vertex itself lies on the path, so we need to move away from it.
+const VERTEX_OFFSET: f32 = 7.0;
+const PLACEMENT_ATTEMPTS: usize = 8;
+// The area (in tiles) around the point of interest that must be free of paths and water.
+const FREE_AREA_RADIUS: i32 = 2;
+// In world units, nothing grows this close to the points of interest.
+const RESERVED_RADIUS: f32 = 40.0;
+// The minimum number of consecutive path samples over water to place a bridge.
+const MIN_BRIDGE_SAMPLES: usize = 2;
+// In tiles, how far past the shore the area around a bridge is reserved,
+// so that the bridge ends aren't blocked by flora.
+const BRIDGE_RESERVED_MARGIN: f32 = 2.0;
+
+#[derive(Clone, Copy, Debug, Display, EnumIter, PartialEq, Eq, Hash)]
+pub enum PointOfInterestKind {
+    Ruins,
+    Shrine,
+    Campfire,
+    Bridge,
+    Signpost,
+}
+
+impl PointOfInterestKind {
+    /// The yarn node that should be run when the player inspects this point of interest.
+    /// The nodes are defined in `dialogue/world/points-of-interest.yarn`.
+    pub fn inspect_node(&self) -> Option<&'static str> {
+        match self {
+            PointOfInterestKind::Ruins => Some("InspectRuins"),
+            PointOfInterestKind::Shrine => Some("InspectShrine"),
+            PointOfInterestKind::Campfire => Some("InspectCampfire"),
+            PointOfInterestKind::Bridge => None,
+            PointOfInterestKind::Signpost => Some("InspectSignpost"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct PointOfInterest {
+    pub kind: PointOfInterestKind,
+    /// World position of the point of interest.
+    pub pos: Vec2,
+    /// Signposts point into this direction, for bridges this is the direction of the path.
+    /// Zero for all other kinds.
+    pub direction: Vec2,
+}
+
+/// Decides which point of interest (if any) to place on a vertex of the path graph.
+/// The first rule that matches a vertex is the only one that gets rolled.
+struct PoiRule {
+    kind: PointOfInterestKind,
+    degree: RangeInclusive<usize>,
+    /// Distance from the origin in tiles.
+    distance: Range<f32>,
+    chance: f64,
+}
+
+impl PoiRule {
+    fn matches(&self, degree: usize, distance: f32) -> bool {
+        self.degree.contains(&degree) && self.distance.contains(&distance)
+    }
+}
+
+const POI_RULES: [PoiRule; 4] = [
+    // Crossings close to the start help the player find the NPCs.
+    PoiRule {
+        kind: PointOfInterestKind::Signpost,
+        degree: 3..=usize::MAX,
+        distance: 0.0..60.0,
+        chance: 0.8,
+    },
+    // Dead ends far away from the start should reward the player for exploring.
+    PoiRule {
+        kind: PointOfInterestKind::Ruins,
+        degree: 0..=1,
+        distance: 40.0..f32::MAX,
+        chance: 0.7,
+    },
+    PoiRule {
+        kind: PointOfInterestKind::Shrine,
+        degree: 3..=usize::MAX,
+        distance: 60.0..f32::MAX,
+        chance: 0.6,
+    },
+    PoiRule {
+        kind: PointOfInterestKind::Campfire,
+        degree: 2..=2,
+        distance: 25.0..f32::MAX,
+        chance: 0.35,
+    },
+];
+
+fn is_free_tile(bitmap: &mut BitMap, v: IVec2) -> bool {
+    for x in -FREE_AREA_RADIUS..=FREE_AREA_RADIUS {
+        for y in -FREE_AREA_RADIUS..=FREE_AREA_RADIUS {
+            let w = v + IVec2::new(x, y);
+            if bitmap.get_path_flag(w) || bitmap.is_water(w) {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+/// Search for a tile next to the given vertex that isn't covered by paths or water.
+/// Returns the world position of that tile.
+fn find_free_spot(bitmap: &mut BitMap, rng: &mut GameRng, vertex: Vec2) -> Option<Vec2> {
+    let start_angle = rng.gen_range(0.0..TAU);
+    for i in 0..PLACEMENT_ATTEMPTS {
+        let angle = start_angle + i as f32 / PLACEMENT_ATTEMPTS as f32 * TAU;
+        let candidate = (vertex / TILE_SIZE + Vec2::from_angle(angle) * VERTEX_OFFSET).round();
+        if is_free_tile(bitmap, candidate.as_ivec2()) {
+            return Some(candidate * TILE_SIZE);
+        }
+    }
+    None
+}
+
+fn closest_hotspot(hotspots: &[Vec2], pos: Vec2) -> Option<Vec2> {
+    hotspots
+        .iter()
+        .min_by(|a, b| {
+            a.distance_squared(pos)
+                .partial_cmp(&b.distance_squared(pos))
+                .unwrap()
+        })
+        .copied()
+}
+
+/// The area along a bridge from shore to shore (in tiles), in world units.
+fn bridge_footprint(start: Vec2, end: Vec2) -> Vec<Vec2> {
+    let direction = (end - start).normalize_or_zero();
+    let along = direction * BRIDGE_RESERVED_MARGIN;
+    let across = direction.perp() * (BRIDGE_RADIUS as f32 + 1.0);
+    [
+        start - along - across,
+        end + along - across,
+        end + along + across,
+        start - along + across,
+    ]
+    .map(|p| p * TILE_SIZE)
+    .to_vec()
+}
+
+/// Mark the middle of every stretch where a path crosses water and reserve the area around it.
+/// The bridge itself is placed during path generation.
+fn generate_bridges(
+    bitmap: &mut BitMap,
+    reserved_areas: &mut ReservedAreas,
+) -> Vec<PointOfInterest> {
+    let mut bridges = Vec::new();
+
+    for [p1, p2, c1, c2] in bitmap.path_curves().clone() {
+        let points = compute_path_points(p1, p2, c1, c2, SAMPLE_RATE);
+
+        let mut water_start = None;
+        for i in 0..=points.len() {
+            let is_water = i < points.len() && bitmap.get_bridge_flag(points[i]);
+            match (is_water, water_start) {
+                (true, None) => water_start = Some(i),
+                (false, Some(start)) => {
+                    water_start = None;
+                    if i - start < MIN_BRIDGE_SAMPLES {
+                        continue;
+                    }
+
+                    let middle = (start + i) / 2;
+                    let before = points[start.saturating_sub(1)];
+                    let after = points[i.min(points.len() - 1)];
+                    reserved_areas
+                        .reserve_polygon(bridge_footprint(before.as_vec2(), after.as_vec2()));
+                    bridges.push(PointOfInterest {
+                        kind: PointOfInterestKind::Bridge,
+                        pos: points[middle].as_vec2() * TILE_SIZE,
+                        direction: (after - before).as_vec2().normalize_or_zero(),
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+    bridges
+}
+
+pub fn generate_points_of_interest(
+    mut bitmap: ResMut<BitMap>,
+    mut reserved_areas: ResMut<ReservedAreas>,
+) {
+    let mut rng = GameRng::seed_from_u64(bitmap.seed() as u64 + POI_SEED_OFFSET);
+    let hotspot_indices = bitmap.get_furthest_hotspot_indices(NPC_HOTSPOTS);
+    let hotspots = bitmap.get_furthest_hotspots(NPC_HOTSPOTS);
+    let vertices = bitmap.vertices().clone();
+
+    let mut points_of_interest = Vec::new();
+    // The first vertex is always the origin where the player spawns.
+    for (i, vertex) in vertices.iter().enumerate().skip(1) {
+        if hotspot_indices.contains(&i) {
+            continue;
+        }
+
+        let degree = bitmap.vertex_degree(i);
+        let distance = vertex.length() / TILE_SIZE;
+        let Some(rule) = POI_RULES.iter().find(|rule| rule.matches(degree, distance)) else {
+            continue;
+        };
+        if !rng.gen_bool(rule.chance) {
+            continue;
+        }
+        let Some(pos) = find_free_spot(&mut bitmap, &mut rng, *vertex) else {
+            continue;
+        };
+
+        let direction = match rule.kind {
+            PointOfInterestKind::Signpost => closest_hotspot(&hotspots, pos)
+                .map(|hotspot| (hotspot - pos).normalize_or_zero())
+                .unwrap_or_default(),
+            _ => Vec2::ZERO,
+        };
+
+        points_of_interest.push(PointOfInterest {
+            kind: rule.kind,
+            pos,
+            direction,
+        });
+    }
+
+    for poi in &points_of_interest {
+        reserved_areas.reserve_circle(poi.pos, RESERVED_RADIUS);
+    }
+    points_of_interest.append(&mut generate_bridges(&mut bitmap, &mut reserved_areas));
+    bitmap.set_points_of_interest(points_of_interest);
+}
+
+pub struct PoiGenerationPlugin;
+
+impl Plugin for PoiGenerationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::AssetLoading),
+            generate_points_of_interest.after(generate_path),
+        );
+    }
+}
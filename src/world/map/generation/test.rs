@@ -0,0 +1,49 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use super::{BitMap, ChunkRngStream};
+
+#[test]
+fn chunk_seeds_are_stable() {
+    let (a, b) = (BitMap::with_seed(42.0), BitMap::with_seed(42.0));
+    let chunk = IVec2::new(-3, 5);
+
+    assert_eq!(
+        a.chunk_seed(chunk, ChunkRngStream::Tiles),
+        b.chunk_seed(chunk, ChunkRngStream::Tiles)
+    );
+    let mut rng_a = a.chunk_rng(chunk, ChunkRngStream::FloraVariants);
+    let mut rng_b = b.chunk_rng(chunk, ChunkRngStream::FloraVariants);
+    for _ in 0..10 {
+        assert_eq!(rng_a.gen::<u64>(), rng_b.gen::<u64>());
+    }
+}
+
+#[test]
+fn chunk_seeds_differ() {
+    let bitmap = BitMap::with_seed(42.0);
+    // Mirrored chunks used to share their flora.
+    let chunks = [
+        IVec2::new(0, 0),
+        IVec2::new(1, 0),
+        IVec2::new(0, 1),
+        IVec2::new(-1, 0),
+        IVec2::new(0, -1),
+        IVec2::new(1, -1),
+    ];
+    let streams = [
+        ChunkRngStream::Tiles,
+        ChunkRngStream::FloraPositions,
+        ChunkRngStream::FloraVariants,
+    ];
+
+    let mut seeds: Vec<u64> = chunks
+        .iter()
+        .flat_map(|chunk| streams.map(|stream| bitmap.chunk_seed(*chunk, stream)))
+        .collect();
+    seeds.push(BitMap::with_seed(43.0).chunk_seed(IVec2::ZERO, ChunkRngStream::Tiles));
+    let count = seeds.len();
+    seeds.sort();
+    seeds.dedup();
+    assert_eq!(seeds.len(), count);
+}
@@ -1,15 +1,19 @@
+pub mod chunk_manager;
+pub mod flora;
 pub mod generation;
+pub mod navigation;
+pub mod reserved_areas;
 
-mod chunk_manager;
+mod bridge;
 mod collision;
-mod flora;
+mod poi;
 mod poisson_sampling;
 
 use bevy::prelude::*;
 
 // Values based on the used tileset, don't change!
-const TILE_SIZE: f32 = 16.0;
-const CHUNK_SIZE: u32 = 16;
+pub const TILE_SIZE: f32 = 16.0;
+pub const CHUNK_SIZE: u32 = 16;
 const BACKGROUND_ZINDEX_ABS: f32 = 800.0;
 
 const RENDERED_CHUNKS_RADIUS: u32 = 3;
@@ -22,7 +26,11 @@ impl Plugin for MapPlugin {
             generation::MapGenerationPlugin,
             chunk_manager::ChunkManagerPlugin,
             collision::MapCollisionPlugin,
+            bridge::BridgePlugin,
             flora::FloraPlugin,
+            poi::PoiPlugin,
+            navigation::NavigationPlugin,
+            reserved_areas::ReservedAreasPlugin,
         ));
     }
 }
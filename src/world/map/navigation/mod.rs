@@ -0,0 +1,251 @@
+mod search;
+
+#[cfg(test)]
+mod test;
+
+use bevy::{prelude::*, transform::TransformSystem, utils::HashMap};
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    player::{input::MouseWorldCoords, Player},
+    utils::DebugActive,
+    GameState,
+};
+
+use super::{
+    chunk_manager::{despawn_chunks, spawn_chunks, ChunkIndex, DespawnedChunk},
+    generation::BitMap,
+    CHUNK_SIZE, TILE_SIZE,
+};
+
+// Upper bound for a single path query, the grid only covers the loaded chunks anyway.
+const MAX_EXPANSIONS: usize = 20_000;
+// Obstacles are inflated by this much so that agents don't get stuck on their corners.
+const OBSTACLE_CLEARANCE: f32 = TILE_SIZE / 2.0;
+// How far (in cells) we look for a walkable cell if the start or goal is blocked.
+const NEAREST_WALKABLE_RADIUS: i32 = 3;
+
+/// Marks a collider that agents need to walk around.
+/// The collider is rasterized into the navigation grid by its bounding box.
+#[derive(Component)]
+pub struct NavObstacle;
+
+struct NavChunk {
+    terrain: Vec<bool>,
+    obstacles: Vec<bool>,
+}
+
+impl NavChunk {
+    fn index(local: IVec2) -> usize {
+        local.x as usize * CHUNK_SIZE as usize + local.y as usize
+    }
+}
+
+/// Walkable grid of all the loaded chunks.
+/// One cell corresponds to exactly one tile.
+/// Cells in chunks that are not loaded are never walkable.
+#[derive(Resource, Default)]
+pub struct NavGrid {
+    chunks: HashMap<IVec2, NavChunk>,
+    obstacles_dirty: bool,
+}
+
+impl NavGrid {
+    fn chunk_and_local(cell: IVec2) -> (IVec2, IVec2) {
+        let size = IVec2::splat(CHUNK_SIZE as i32);
+        (cell.div_euclid(size), cell.rem_euclid(size))
+    }
+
+    pub fn world_to_cell(pos: Vec2) -> IVec2 {
+        (pos / TILE_SIZE).round().as_ivec2()
+    }
+
+    pub fn cell_to_world(cell: IVec2) -> Vec2 {
+        cell.as_vec2() * TILE_SIZE
+    }
+
+    pub fn is_walkable(&self, cell: IVec2) -> bool {
+        let (chunk_pos, local) = Self::chunk_and_local(cell);
+        match self.chunks.get(&chunk_pos) {
+            Some(chunk) => {
+                let i = NavChunk::index(local);
+                chunk.terrain[i] && !chunk.obstacles[i]
+            }
+            None => false,
+        }
+    }
+
+    fn set_obstacle(&mut self, cell: IVec2) {
+        let (chunk_pos, local) = Self::chunk_and_local(cell);
+        if let Some(chunk) = self.chunks.get_mut(&chunk_pos) {
+            chunk.obstacles[NavChunk::index(local)] = true;
+        }
+    }
+
+    fn nearest_walkable(&self, cell: IVec2) -> Option<IVec2> {
+        if self.is_walkable(cell) {
+            return Some(cell);
+        }
+
+        let mut best: Option<IVec2> = None;
+        for x in -NEAREST_WALKABLE_RADIUS..=NEAREST_WALKABLE_RADIUS {
+            for y in -NEAREST_WALKABLE_RADIUS..=NEAREST_WALKABLE_RADIUS {
+                let candidate = cell + IVec2::new(x, y);
+                if !self.is_walkable(candidate) {
+                    continue;
+                }
+                if best.is_none_or(|b| {
+                    (candidate - cell).length_squared() < (b - cell).length_squared()
+                }) {
+                    best = Some(candidate);
+                }
+            }
+        }
+        best
+    }
+
+    /// Find a path between the two world positions.
+    /// The returned waypoints don't include the start position and end
+    /// on the goal (or the closest walkable point next to it).
+    /// Returns `None` if there is no path through the loaded chunks.
+    pub fn find_path(&self, start: Vec2, goal: Vec2) -> Option<Vec<Vec2>> {
+        let goal_cell = Self::world_to_cell(goal);
+        let start = self.nearest_walkable(Self::world_to_cell(start))?;
+        let end = self.nearest_walkable(goal_cell)?;
+
+        let walkable = |cell: IVec2| self.is_walkable(cell);
+        let jump_points = search::jump_point_search(&walkable, start, end, MAX_EXPANSIONS)?;
+        let cells = search::smooth_path(&walkable, &jump_points);
+
+        let mut path: Vec<Vec2> = cells.into_iter().skip(1).map(Self::cell_to_world).collect();
+        if end == goal_cell {
+            match path.last_mut() {
+                Some(last) => *last = goal,
+                None => path.push(goal),
+            }
+        }
+        Some(path)
+    }
+}
+
+fn build_chunk_grids(
+    mut nav_grid: ResMut<NavGrid>,
+    mut bitmap: ResMut<BitMap>,
+    q_chunks: Query<&ChunkIndex, Added<ChunkIndex>>,
+) {
+    for chunk in &q_chunks {
+        let mut terrain = vec![false; (CHUNK_SIZE * CHUNK_SIZE) as usize];
+        for x in 0..CHUNK_SIZE as i32 {
+            for y in 0..CHUNK_SIZE as i32 {
+                let local = IVec2::new(x, y);
+                let v = local + **chunk * CHUNK_SIZE as i32;
+                terrain[NavChunk::index(local)] = bitmap.get_walkable_flag(v);
+            }
+        }
+
+        nav_grid.chunks.insert(
+            **chunk,
+            NavChunk {
+                terrain,
+                obstacles: vec![false; (CHUNK_SIZE * CHUNK_SIZE) as usize],
+            },
+        );
+        nav_grid.obstacles_dirty = true;
+    }
+}
+
+fn remove_chunk_grids(
+    mut nav_grid: ResMut<NavGrid>,
+    mut ev_despawned_chunk: EventReader<DespawnedChunk>,
+) {
+    for ev in ev_despawned_chunk.read() {
+        nav_grid.chunks.remove(&ev.chunk_pos);
+    }
+}
+
+/// Rebuild the obstacles of all chunks whenever an obstacle is added or removed.
+/// This runs after the transforms got propagated, newly spawned
+/// obstacles wouldn't have a valid `GlobalTransform` otherwise.
+fn rasterize_obstacles(
+    mut nav_grid: ResMut<NavGrid>,
+    q_obstacles: Query<(&Collider, &GlobalTransform), With<NavObstacle>>,
+    q_added_obstacles: Query<(), Added<NavObstacle>>,
+    mut removed_obstacles: RemovedComponents<NavObstacle>,
+) {
+    let removed = removed_obstacles.read().count() > 0;
+    if !nav_grid.obstacles_dirty && !removed && q_added_obstacles.is_empty() {
+        return;
+    }
+    nav_grid.obstacles_dirty = false;
+
+    for chunk in nav_grid.chunks.values_mut() {
+        chunk.obstacles.fill(false);
+    }
+
+    for (collider, transform) in &q_obstacles {
+        let aabb = collider.raw.compute_local_aabb();
+        let (scale, _, translation) = transform.to_scale_rotation_translation();
+        let min = translation.truncate() + Vec2::new(aabb.mins.x, aabb.mins.y) * scale.truncate()
+            - OBSTACLE_CLEARANCE;
+        let max = translation.truncate()
+            + Vec2::new(aabb.maxs.x, aabb.maxs.y) * scale.truncate()
+            + OBSTACLE_CLEARANCE;
+
+        let min_cell = (min / TILE_SIZE).ceil().as_ivec2();
+        let max_cell = (max / TILE_SIZE).floor().as_ivec2();
+        for x in min_cell.x..=max_cell.x {
+            for y in min_cell.y..=max_cell.y {
+                nav_grid.set_obstacle(IVec2::new(x, y));
+            }
+        }
+    }
+}
+
+fn draw_debug_path(
+    mut gizmos: Gizmos,
+    debug_active: Res<DebugActive>,
+    nav_grid: Res<NavGrid>,
+    mouse_coords: Res<MouseWorldCoords>,
+    q_player: Query<&Transform, With<Player>>,
+) {
+    if !**debug_active {
+        return;
+    }
+
+    let player_pos = match q_player.get_single() {
+        Ok(r) => r.translation.truncate(),
+        Err(_) => return,
+    };
+
+    if let Some(path) = nav_grid.find_path(player_pos, mouse_coords.0) {
+        gizmos.linestrip_2d(
+            std::iter::once(player_pos).chain(path),
+            Color::srgb(0.2, 0.9, 0.4),
+        );
+    }
+}
+
+pub struct NavigationPlugin;
+
+impl Plugin for NavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NavGrid>()
+            .add_systems(
+                Update,
+                (
+                    build_chunk_grids
+                        .before(spawn_chunks)
+                        .before(despawn_chunks),
+                    remove_chunk_grids.after(despawn_chunks),
+                    draw_debug_path,
+                )
+                    .run_if(in_state(GameState::Gaming)),
+            )
+            .add_systems(
+                PostUpdate,
+                rasterize_obstacles
+                    .after(TransformSystem::TransformPropagate)
+                    .run_if(in_state(GameState::Gaming)),
+            );
+    }
+}
@@ -0,0 +1,304 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::f32::consts::SQRT_2;
+
+use bevy::{prelude::*, utils::HashMap};
+
+// Jumps are cut off after this many cells, the end of the jump is then
+// treated like a jump point. This keeps the search fast on big open areas.
+const MAX_JUMP_DISTANCE: i32 = 64;
+
+// Tolerance when checking whether a line passes exactly through the corner of a cell.
+const CORNER_EPSILON: f32 = 1e-4;
+
+const DIRECTIONS: [IVec2; 8] = [
+    IVec2::new(1, 0),
+    IVec2::new(-1, 0),
+    IVec2::new(0, 1),
+    IVec2::new(0, -1),
+    IVec2::new(1, 1),
+    IVec2::new(1, -1),
+    IVec2::new(-1, 1),
+    IVec2::new(-1, -1),
+];
+
+#[derive(PartialEq)]
+struct OpenNode {
+    cell: IVec2,
+    f_score: f32,
+}
+
+impl Eq for OpenNode {}
+
+impl Ord for OpenNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed, we want a min heap.
+        other
+            .f_score
+            .partial_cmp(&self.f_score)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for OpenNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The exact distance between two cells when moving in 8 directions.
+pub fn octile_distance(a: IVec2, b: IVec2) -> f32 {
+    let d = (a - b).abs();
+    let (min, max) = (d.x.min(d.y) as f32, d.x.max(d.y) as f32);
+    max - min + SQRT_2 * min
+}
+
+/// Diagonal moves are only allowed if both orthogonal cells are free,
+/// otherwise agents would clip through the corners of obstacles.
+fn can_move(walkable: &impl Fn(IVec2) -> bool, cell: IVec2, direction: IVec2) -> bool {
+    if !walkable(cell + direction) {
+        return false;
+    }
+    if direction.x != 0 && direction.y != 0 {
+        return walkable(cell + IVec2::new(direction.x, 0))
+            && walkable(cell + IVec2::new(0, direction.y));
+    }
+    true
+}
+
+/// All the cells that can be reached from the given cell in one move.
+pub fn neighbors(walkable: &impl Fn(IVec2) -> bool, cell: IVec2) -> Vec<IVec2> {
+    DIRECTIONS
+        .iter()
+        .filter(|direction| can_move(walkable, cell, **direction))
+        .map(|direction| cell + *direction)
+        .collect()
+}
+
+/// Generic A*. The successors of a cell are given by `successors`,
+/// which also receives the parent of the cell (if any).
+/// Returns the cells from start to goal (both inclusive).
+pub fn a_star(
+    start: IVec2,
+    goal: IVec2,
+    max_expansions: usize,
+    mut successors: impl FnMut(IVec2, Option<IVec2>) -> Vec<IVec2>,
+) -> Option<Vec<IVec2>> {
+    let mut open = BinaryHeap::new();
+    let mut g_scores = HashMap::new();
+    let mut came_from: HashMap<IVec2, IVec2> = HashMap::new();
+
+    g_scores.insert(start, 0.0);
+    open.push(OpenNode {
+        cell: start,
+        f_score: octile_distance(start, goal),
+    });
+
+    let mut expansions = 0;
+    while let Some(OpenNode { cell, f_score }) = open.pop() {
+        if cell == goal {
+            let mut path = vec![cell];
+            let mut current = cell;
+            while let Some(parent) = came_from.get(&current) {
+                path.push(*parent);
+                current = *parent;
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        let g_score = g_scores[&cell];
+        // Stale entry, we already found a shorter way to this cell.
+        if f_score > g_score + octile_distance(cell, goal) {
+            continue;
+        }
+
+        expansions += 1;
+        if expansions > max_expansions {
+            return None;
+        }
+
+        for next in successors(cell, came_from.get(&cell).copied()) {
+            let tentative = g_score + octile_distance(cell, next);
+            if g_scores.get(&next).is_some_and(|g| *g <= tentative) {
+                continue;
+            }
+
+            g_scores.insert(next, tentative);
+            came_from.insert(next, cell);
+            open.push(OpenNode {
+                cell: next,
+                f_score: tentative + octile_distance(next, goal),
+            });
+        }
+    }
+    None
+}
+
+/// Only the neighbors that can't be reached more cheaply through the parent.
+fn pruned_neighbors(
+    walkable: &impl Fn(IVec2) -> bool,
+    cell: IVec2,
+    parent: Option<IVec2>,
+) -> Vec<IVec2> {
+    let Some(parent) = parent else {
+        return neighbors(walkable, cell);
+    };
+
+    let d = (cell - parent).signum();
+    let mut result = Vec::new();
+    if d.x != 0 && d.y != 0 {
+        let horizontal = walkable(cell + IVec2::new(d.x, 0));
+        let vertical = walkable(cell + IVec2::new(0, d.y));
+        if vertical {
+            result.push(cell + IVec2::new(0, d.y));
+        }
+        if horizontal {
+            result.push(cell + IVec2::new(d.x, 0));
+        }
+        if horizontal && vertical {
+            result.push(cell + d);
+        }
+        return result;
+    }
+
+    // Perpendicular to the direction of travel.
+    let side = IVec2::new(d.y, d.x);
+    let next = walkable(cell + d);
+    let left = walkable(cell + side);
+    let right = walkable(cell - side);
+    if next {
+        result.push(cell + d);
+        if left {
+            result.push(cell + d + side);
+        }
+        if right {
+            result.push(cell + d - side);
+        }
+    }
+    if left {
+        result.push(cell + side);
+    }
+    if right {
+        result.push(cell - side);
+    }
+    result
+}
+
+/// Move from the given cell into the given direction until we hit something
+/// interesting (the goal or a cell with forced neighbors).
+fn jump(
+    walkable: &impl Fn(IVec2) -> bool,
+    mut cell: IVec2,
+    direction: IVec2,
+    goal: IVec2,
+) -> Option<IVec2> {
+    for _ in 0..MAX_JUMP_DISTANCE {
+        if !can_move(walkable, cell, direction) {
+            return None;
+        }
+        cell += direction;
+
+        if cell == goal {
+            return Some(cell);
+        }
+
+        if direction.x != 0 && direction.y != 0 {
+            if jump(walkable, cell, IVec2::new(direction.x, 0), goal).is_some()
+                || jump(walkable, cell, IVec2::new(0, direction.y), goal).is_some()
+            {
+                return Some(cell);
+            }
+        } else {
+            let side = IVec2::new(direction.y, direction.x);
+            let forced = (walkable(cell + side) && !walkable(cell - direction + side))
+                || (walkable(cell - side) && !walkable(cell - direction - side));
+            if forced {
+                return Some(cell);
+            }
+        }
+    }
+    Some(cell)
+}
+
+/// Jump point search, returns the jump points from start to goal (both inclusive).
+/// Consecutive jump points are always connected by a straight or diagonal line.
+pub fn jump_point_search(
+    walkable: &impl Fn(IVec2) -> bool,
+    start: IVec2,
+    goal: IVec2,
+    max_expansions: usize,
+) -> Option<Vec<IVec2>> {
+    a_star(start, goal, max_expansions, |cell, parent| {
+        pruned_neighbors(walkable, cell, parent)
+            .into_iter()
+            .filter_map(|next| jump(walkable, cell, next - cell, goal))
+            .collect()
+    })
+}
+
+/// Whether an agent can walk in a straight line between the two cells.
+/// Touching the corner of a blocked cell counts as blocked.
+pub fn line_of_sight(walkable: &impl Fn(IVec2) -> bool, a: IVec2, b: IVec2) -> bool {
+    let delta = (b - a).as_vec2();
+    let step = (b - a).signum();
+    let t_delta = Vec2::new(
+        if delta.x == 0.0 {
+            f32::INFINITY
+        } else {
+            1.0 / delta.x.abs()
+        },
+        if delta.y == 0.0 {
+            f32::INFINITY
+        } else {
+            1.0 / delta.y.abs()
+        },
+    );
+    // We start in the center of the cell, so the first border is half a cell away.
+    let mut t_max = t_delta * 0.5;
+
+    let mut cell = a;
+    let max_steps = (b - a).abs().element_sum();
+    for _ in 0..max_steps {
+        if cell == b {
+            break;
+        }
+        if !walkable(cell) {
+            return false;
+        }
+
+        if (t_max.x - t_max.y).abs() < CORNER_EPSILON {
+            if !can_move(walkable, cell, step) {
+                return false;
+            }
+            cell += step;
+            t_max += t_delta;
+        } else if t_max.x < t_max.y {
+            cell.x += step.x;
+            t_max.x += t_delta.x;
+        } else {
+            cell.y += step.y;
+            t_max.y += t_delta.y;
+        }
+    }
+    cell == b && walkable(cell)
+}
+
+/// Remove all the points that are not necessary, i.e. the agent
+/// can walk in a straight line to the next point after it.
+pub fn smooth_path(walkable: &impl Fn(IVec2) -> bool, path: &[IVec2]) -> Vec<IVec2> {
+    if path.len() <= 2 {
+        return path.to_vec();
+    }
+
+    let mut smoothed = vec![path[0]];
+    let mut anchor = path[0];
+    for i in 1..path.len() - 1 {
+        if !line_of_sight(walkable, anchor, path[i + 1]) {
+            anchor = path[i];
+            smoothed.push(anchor);
+        }
+    }
+    smoothed.push(path[path.len() - 1]);
+    smoothed
+}
@@ -0,0 +1,201 @@
+use bevy::{prelude::*, utils::HashSet};
+
+use super::search::{
+    a_star, jump_point_search, line_of_sight, neighbors, octile_distance, smooth_path,
+};
+
+const MAX_EXPANSIONS: usize = 10_000;
+
+// `#` is blocked, everything else is walkable.
+// The first row is the top of the grid (highest y).
+const OPEN_MAP: [&str; 5] = [
+    "..........",
+    "..........",
+    "..........",
+    "..........",
+    "..........",
+];
+
+const WALL_MAP: [&str; 7] = [
+    "..........",
+    "....#.....",
+    "....#.....",
+    "....#.....",
+    "....#.....",
+    "....#.....",
+    "....#.....",
+];
+
+const MAZE_MAP: [&str; 9] = [
+    "...........",
+    ".#.#######.",
+    ".#.......#.",
+    ".#.#####.#.",
+    ".#.#...#.#.",
+    ".#.#.#.#.#.",
+    ".#...#...#.",
+    ".#########.",
+    "...........",
+];
+
+const CORNER_MAP: [&str; 3] = ["..#", ".#.", "..."];
+
+struct Grid {
+    walkable: HashSet<IVec2>,
+}
+
+impl Grid {
+    fn new(rows: &[&str]) -> Self {
+        let mut walkable = HashSet::new();
+        for (i, row) in rows.iter().enumerate() {
+            let y = (rows.len() - 1 - i) as i32;
+            for (x, c) in row.chars().enumerate() {
+                if c != '#' {
+                    walkable.insert(IVec2::new(x as i32, y));
+                }
+            }
+        }
+        Self { walkable }
+    }
+
+    fn is_walkable(&self, cell: IVec2) -> bool {
+        self.walkable.contains(&cell)
+    }
+}
+
+fn path_length(path: &[IVec2]) -> f32 {
+    path.windows(2).map(|w| octile_distance(w[0], w[1])).sum()
+}
+
+/// Plain A* over all neighbors, used as the reference for jump point search.
+fn reference_path(grid: &Grid, start: IVec2, goal: IVec2) -> Option<Vec<IVec2>> {
+    let walkable = |cell: IVec2| grid.is_walkable(cell);
+    a_star(start, goal, MAX_EXPANSIONS, |cell, _| {
+        neighbors(&walkable, cell)
+    })
+}
+
+/// Every step between two consecutive points must be walkable without cutting corners.
+fn assert_path_valid(grid: &Grid, path: &[IVec2]) {
+    let walkable = |cell: IVec2| grid.is_walkable(cell);
+    for w in path.windows(2) {
+        assert!(
+            line_of_sight(&walkable, w[0], w[1]),
+            "Path segment {} -> {} is blocked",
+            w[0],
+            w[1]
+        );
+    }
+}
+
+fn assert_same_cost_as_reference(rows: &[&str], start: IVec2, goal: IVec2) {
+    let grid = Grid::new(rows);
+    let walkable = |cell: IVec2| grid.is_walkable(cell);
+
+    let reference = reference_path(&grid, start, goal).expect("Reference path must exist");
+    let jps = jump_point_search(&walkable, start, goal, MAX_EXPANSIONS)
+        .expect("Jump point search must find a path");
+
+    assert_eq!(jps.first(), Some(&start));
+    assert_eq!(jps.last(), Some(&goal));
+    assert_path_valid(&grid, &jps);
+    assert!(
+        (path_length(&jps) - path_length(&reference)).abs() < 1e-3,
+        "JPS path is not optimal: {} vs {}",
+        path_length(&jps),
+        path_length(&reference)
+    );
+}
+
+#[test]
+fn jps_open_map() {
+    assert_same_cost_as_reference(&OPEN_MAP, IVec2::new(0, 0), IVec2::new(9, 4));
+    assert_same_cost_as_reference(&OPEN_MAP, IVec2::new(9, 0), IVec2::new(0, 3));
+}
+
+#[test]
+fn jps_around_wall() {
+    assert_same_cost_as_reference(&WALL_MAP, IVec2::new(0, 0), IVec2::new(9, 0));
+    assert_same_cost_as_reference(&WALL_MAP, IVec2::new(2, 3), IVec2::new(7, 1));
+}
+
+#[test]
+fn jps_maze() {
+    assert_same_cost_as_reference(&MAZE_MAP, IVec2::new(0, 0), IVec2::new(4, 4));
+    assert_same_cost_as_reference(&MAZE_MAP, IVec2::new(2, 6), IVec2::new(10, 8));
+}
+
+#[test]
+fn no_path_to_enclosed_cell() {
+    let rows = [".....", ".###.", ".#.#.", ".###.", "....."];
+    let grid = Grid::new(&rows);
+    let walkable = |cell: IVec2| grid.is_walkable(cell);
+
+    assert!(jump_point_search(
+        &walkable,
+        IVec2::new(0, 0),
+        IVec2::new(2, 2),
+        MAX_EXPANSIONS
+    )
+    .is_none());
+    assert!(reference_path(&grid, IVec2::new(0, 0), IVec2::new(2, 2)).is_none());
+}
+
+#[test]
+fn no_corner_cutting() {
+    let grid = Grid::new(&CORNER_MAP);
+    let walkable = |cell: IVec2| grid.is_walkable(cell);
+
+    // The diagonal is free, but both orthogonal neighbors are blocked.
+    assert!(!line_of_sight(
+        &walkable,
+        IVec2::new(0, 0),
+        IVec2::new(1, 1)
+    ));
+    assert!(!neighbors(&walkable, IVec2::new(1, 0)).contains(&IVec2::new(2, 1)));
+
+    let path = jump_point_search(
+        &walkable,
+        IVec2::new(0, 0),
+        IVec2::new(2, 1),
+        MAX_EXPANSIONS,
+    )
+    .expect("Path along the border must exist");
+    assert_path_valid(&grid, &path);
+}
+
+#[test]
+fn smoothing_keeps_path_valid() {
+    let grid = Grid::new(&MAZE_MAP);
+    let walkable = |cell: IVec2| grid.is_walkable(cell);
+
+    let path = jump_point_search(
+        &walkable,
+        IVec2::new(0, 0),
+        IVec2::new(4, 4),
+        MAX_EXPANSIONS,
+    )
+    .expect("Path through maze must exist");
+    let smoothed = smooth_path(&walkable, &path);
+
+    assert!(smoothed.len() <= path.len());
+    assert_eq!(smoothed.first(), path.first());
+    assert_eq!(smoothed.last(), path.last());
+    assert_path_valid(&grid, &smoothed);
+}
+
+#[test]
+fn smoothing_open_map_is_straight_line() {
+    let grid = Grid::new(&OPEN_MAP);
+    let walkable = |cell: IVec2| grid.is_walkable(cell);
+
+    let path = jump_point_search(
+        &walkable,
+        IVec2::new(0, 0),
+        IVec2::new(9, 2),
+        MAX_EXPANSIONS,
+    )
+    .expect("Path on open map must exist");
+    let smoothed = smooth_path(&walkable, &path);
+    assert_eq!(smoothed, vec![IVec2::new(0, 0), IVec2::new(9, 2)]);
+}
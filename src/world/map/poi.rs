@@ -0,0 +1,318 @@
+use std::f32::consts::PI;
+
+use bevy::{prelude::*, sprite::Anchor};
+use bevy_particle_systems::{
+    CircleSegment, ColorOverTime, Curve, CurvePoint, EmitterShape, JitteredValue, ParticleSystem,
+    ParticleSystemBundle, Playing,
+};
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    player::interaction::{InteractAction, Interactable, Seat},
+    world::camera::YSort,
+    GameAssets, GameState,
+};
+
+use super::{
+    chunk_manager::{DespawnedChunk, SpawnedChunk},
+    generation::{
+        poi::{PointOfInterest, PointOfInterestKind},
+        BitMap,
+    },
+    navigation::NavObstacle,
+    CHUNK_SIZE, TILE_SIZE,
+};
+
+const RUINS_STONES: usize = 5;
+const RUINS_RADIUS: f32 = 20.0;
+const CAMPFIRE_STONES: usize = 6;
+const CAMPFIRE_RADIUS: f32 = 8.0;
+const CAMPFIRE_SEAT_DISTANCE: f32 = 22.0;
+const INSPECT_DISTANCE: f32 = 40.0;
+const SEAT_RADIUS: f32 = 16.0;
+
+const STONE_COLOR: Color = Color::srgb(0.55, 0.55, 0.6);
+pub const WOOD_COLOR: Color = Color::srgb(0.45, 0.3, 0.2);
+pub const DARK_WOOD_COLOR: Color = Color::srgb(0.3, 0.2, 0.12);
+
+#[derive(Component)]
+pub struct Poi {
+    chunk_pos: IVec2,
+}
+
+fn chunk_pos_of(pos: Vec2) -> IVec2 {
+    (pos / TILE_SIZE)
+        .floor()
+        .as_ivec2()
+        .div_euclid(IVec2::splat(CHUNK_SIZE as i32))
+}
+
+fn pixel_sprite(
+    assets: &Res<GameAssets>,
+    color: Color,
+    size: Vec2,
+    anchor: Anchor,
+    transform: Transform,
+) -> SpriteBundle {
+    SpriteBundle {
+        texture: assets.white_pixel.clone(),
+        sprite: Sprite {
+            color,
+            custom_size: Some(size),
+            anchor,
+            ..default()
+        },
+        transform,
+        ..default()
+    }
+}
+
+fn spawn_stone(
+    commands: &mut Commands,
+    assets: &Res<GameAssets>,
+    offset: Vec2,
+    index: usize,
+    scale: f32,
+) -> Entity {
+    let collider = commands
+        .spawn((
+            NavObstacle,
+            Collider::cuboid(8.0, 8.0),
+            TransformBundle::default(),
+        ))
+        .id();
+
+    commands
+        .spawn((
+            YSort(-8.0),
+            SpriteBundle {
+                texture: assets.rocks_texture.clone(),
+                transform: Transform::from_translation(offset.extend(0.0))
+                    .with_scale(Vec3::splat(scale)),
+                ..default()
+            },
+            TextureAtlas {
+                layout: assets.rocks_layout.clone(),
+                index,
+            },
+        ))
+        .push_children(&[collider])
+        .id()
+}
+
+fn spawn_ruins(commands: &mut Commands, assets: &Res<GameAssets>) -> Vec<Entity> {
+    (0..RUINS_STONES)
+        .map(|i| {
+            let angle = i as f32 / RUINS_STONES as f32 * 2.0 * PI;
+            let offset = Vec2::from_angle(angle) * RUINS_RADIUS;
+            spawn_stone(commands, assets, offset, i % 3, 1.0 + (i % 2) as f32 * 0.5)
+        })
+        .collect()
+}
+
+fn spawn_shrine(commands: &mut Commands, assets: &Res<GameAssets>) -> Vec<Entity> {
+    let collider = commands
+        .spawn((
+            NavObstacle,
+            Collider::cuboid(6.0, 4.0),
+            TransformBundle::from_transform(Transform::from_translation(Vec3::new(0.0, 4.0, 0.0))),
+        ))
+        .id();
+
+    let pillar = commands
+        .spawn((
+            YSort(0.0),
+            pixel_sprite(
+                assets,
+                STONE_COLOR,
+                Vec2::new(10.0, 28.0),
+                Anchor::BottomCenter,
+                Transform::default(),
+            ),
+        ))
+        .push_children(&[collider])
+        .id();
+
+    let left = spawn_stone(commands, assets, Vec2::new(-12.0, -2.0), 0, 0.75);
+    let right = spawn_stone(commands, assets, Vec2::new(12.0, -2.0), 2, 0.75);
+    vec![pillar, left, right]
+}
+
+fn spawn_campfire(commands: &mut Commands, assets: &Res<GameAssets>) -> Vec<Entity> {
+    let mut entities: Vec<Entity> = (0..CAMPFIRE_STONES)
+        .map(|i| {
+            let angle = i as f32 / CAMPFIRE_STONES as f32 * 2.0 * PI;
+            let offset = Vec2::from_angle(angle) * CAMPFIRE_RADIUS;
+            spawn_stone(commands, assets, offset, i % 3, 0.5)
+        })
+        .collect();
+
+    let embers = commands
+        .spawn((
+            Playing,
+            ParticleSystemBundle {
+                particle_system: ParticleSystem {
+                    max_particles: 50,
+                    texture: assets.white_pixel.clone().into(),
+                    spawn_rate_per_second: 15.0.into(),
+                    initial_speed: JitteredValue::jittered(20.0, -5.0..5.0),
+                    lifetime: JitteredValue::jittered(0.8, -0.3..0.3),
+                    color: ColorOverTime::Gradient(Curve::new(vec![
+                        CurvePoint::new(Color::srgb(1.0, 0.85, 0.3), 0.0),
+                        CurvePoint::new(Color::srgb(1.0, 0.4, 0.1), 0.5),
+                        CurvePoint::new(Color::srgba(0.5, 0.1, 0.1, 0.0), 1.0),
+                    ])),
+                    emitter_shape: EmitterShape::CircleSegment(CircleSegment {
+                        opening_angle: PI / 4.0,
+                        direction_angle: PI / 2.0,
+                        radius: 3.0.into(),
+                    }),
+                    scale: 2.0.into(),
+                    z_value_override: Some(100.0.into()),
+                    looping: true,
+                    despawn_particles_with_system: true,
+                    ..ParticleSystem::default()
+                },
+                ..ParticleSystemBundle::default()
+            },
+        ))
+        .id();
+    entities.push(embers);
+
+    // A log to sit on, behind the fire so that the player faces it.
+    let seat = commands
+        .spawn((
+            Seat,
+            Interactable::new(
+                SEAT_RADIUS,
+                "Sit",
+                InteractAction::Toggle {
+                    toggled_prompt: "Stand up",
+                },
+            ),
+            YSort(0.0),
+            pixel_sprite(
+                assets,
+                WOOD_COLOR,
+                Vec2::new(16.0, 4.0),
+                Anchor::Center,
+                Transform::from_translation(Vec3::new(0.0, CAMPFIRE_SEAT_DISTANCE, 0.0)),
+            ),
+        ))
+        .id();
+    entities.push(seat);
+    entities
+}
+
+fn spawn_signpost(
+    commands: &mut Commands,
+    assets: &Res<GameAssets>,
+    direction: Vec2,
+) -> Vec<Entity> {
+    // We can only tilt the board slightly, it would look weird otherwise.
+    let side = if direction.x < 0.0 { -1.0 } else { 1.0 };
+    let tilt = direction.y.clamp(-0.5, 0.5) * PI / 4.0 * side;
+
+    let board = commands
+        .spawn(pixel_sprite(
+            assets,
+            WOOD_COLOR,
+            Vec2::new(16.0, 6.0),
+            Anchor::Center,
+            Transform::from_translation(Vec3::new(side * 5.0, 16.0, 0.1))
+                .with_rotation(Quat::from_rotation_z(tilt)),
+        ))
+        .id();
+
+    let collider = commands
+        .spawn((
+            NavObstacle,
+            Collider::cuboid(2.0, 2.0),
+            TransformBundle::from_transform(Transform::from_translation(Vec3::new(0.0, 2.0, 0.0))),
+        ))
+        .id();
+
+    let post = commands
+        .spawn((
+            YSort(0.0),
+            pixel_sprite(
+                assets,
+                DARK_WOOD_COLOR,
+                Vec2::new(3.0, 20.0),
+                Anchor::BottomCenter,
+                Transform::default(),
+            ),
+        ))
+        .push_children(&[board, collider])
+        .id();
+    vec![post]
+}
+
+fn spawn_poi(
+    commands: &mut Commands,
+    assets: &Res<GameAssets>,
+    chunk_pos: IVec2,
+    poi: &PointOfInterest,
+) {
+    let children = match poi.kind {
+        PointOfInterestKind::Ruins => spawn_ruins(commands, assets),
+        PointOfInterestKind::Shrine => spawn_shrine(commands, assets),
+        PointOfInterestKind::Campfire => spawn_campfire(commands, assets),
+        // The bridge itself is part of the terrain, see `bridge.rs`.
+        PointOfInterestKind::Bridge => Vec::new(),
+        PointOfInterestKind::Signpost => spawn_signpost(commands, assets, poi.direction),
+    };
+
+    let mut entity = commands.spawn((
+        Poi { chunk_pos },
+        SpatialBundle::from_transform(Transform::from_translation(poi.pos.extend(0.0))),
+    ));
+    entity.push_children(&children);
+    if let Some(node) = poi.kind.inspect_node() {
+        entity.insert(Interactable::new(
+            INSPECT_DISTANCE,
+            "Inspect",
+            InteractAction::RunNode(node),
+        ));
+    }
+}
+
+fn spawn_poi_chunks(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    bitmap: Res<BitMap>,
+    mut ev_spawned_chunk: EventReader<SpawnedChunk>,
+) {
+    for ev in ev_spawned_chunk.read() {
+        for poi in bitmap.points_of_interest() {
+            if chunk_pos_of(poi.pos) == ev.pos {
+                spawn_poi(&mut commands, &assets, ev.pos, poi);
+            }
+        }
+    }
+}
+
+fn despawn_poi_chunks(
+    mut commands: Commands,
+    q_pois: Query<(Entity, &Poi)>,
+    mut ev_despawned_chunk: EventReader<DespawnedChunk>,
+) {
+    for ev in ev_despawned_chunk.read() {
+        for (entity, poi) in &q_pois {
+            if poi.chunk_pos == ev.chunk_pos {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+    }
+}
+
+pub struct PoiPlugin;
+
+impl Plugin for PoiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (spawn_poi_chunks, despawn_poi_chunks).run_if(in_state(GameState::Gaming)),
+        );
+    }
+}
@@ -53,12 +53,15 @@ pub fn generate_poisson_points(
     points
 }
 
+/// Candidates for which `is_reserved` returns `true` are rejected,
+/// the positions are relative to the region like the returned points.
 pub fn generate_poisson_points_variable_radii(
     min_radius: f32,
     max_radius: f32,
     region_size: Vec2,
     rejection_iter: usize,
     seed: u64,
+    is_reserved: impl Fn(Vec2) -> bool,
 ) -> Vec<Vec3> {
     let cell_size = max_radius / f32::sqrt(2.0);
     let grid_size_x = (region_size.x / cell_size).floor() as usize + 1;
@@ -83,7 +86,9 @@ pub fn generate_poisson_points_variable_radii(
             let candidate =
                 (spawn_center + dir * rng.gen_range(radius..2.0 * radius)).extend(radius);
 
-            if !is_valid_variable_radii(candidate, region_size, cell_size, &points, &grid) {
+            if !is_valid_variable_radii(candidate, region_size, cell_size, &points, &grid)
+                || is_reserved(candidate.xy())
+            {
                 continue;
             }
 
@@ -0,0 +1,99 @@
+#[cfg(test)]
+mod test;
+
+use bevy::prelude::*;
+
+use crate::{
+    utils::{debug_layer_active, DebugLayer},
+    GameState,
+};
+
+const DEBUG_COLOR: Color = Color::srgb(0.95, 0.35, 0.25);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReservedShape {
+    Circle {
+        center: Vec2,
+        radius: f32,
+    },
+    /// The corners of the polygon in order, it's closed automatically.
+    Polygon(Vec<Vec2>),
+}
+
+impl ReservedShape {
+    fn contains(&self, pos: Vec2) -> bool {
+        match self {
+            ReservedShape::Circle { center, radius } => {
+                center.distance_squared(pos) <= radius.powi(2)
+            }
+            // Count how often a ray to the right crosses the edges.
+            ReservedShape::Polygon(points) => {
+                let mut inside = false;
+                for (i, a) in points.iter().enumerate() {
+                    let b = points[(i + 1) % points.len()];
+                    if (a.y > pos.y) != (b.y > pos.y)
+                        && pos.x < a.x + (pos.y - a.y) / (b.y - a.y) * (b.x - a.x)
+                    {
+                        inside = !inside;
+                    }
+                }
+                inside
+            }
+        }
+    }
+}
+
+/// Areas of the world that have to stay free, e.g. around NPCs.
+/// The map generation doesn't place anything (like flora) in them,
+/// so the areas have to be registered before the chunks around them spawn.
+/// All positions are in world units.
+#[derive(Resource, Default)]
+pub struct ReservedAreas(Vec<ReservedShape>);
+
+impl ReservedAreas {
+    pub fn reserve_circle(&mut self, center: Vec2, radius: f32) {
+        self.0.push(ReservedShape::Circle { center, radius });
+    }
+
+    pub fn reserve_polygon(&mut self, points: Vec<Vec2>) {
+        if points.len() < 3 {
+            error!("trying to reserve a polygon with less than 3 points");
+            return;
+        }
+        self.0.push(ReservedShape::Polygon(points));
+    }
+
+    pub fn contains(&self, pos: Vec2) -> bool {
+        self.0.iter().any(|shape| shape.contains(pos))
+    }
+
+    pub fn shapes(&self) -> &[ReservedShape] {
+        &self.0
+    }
+}
+
+fn draw_reserved_areas(mut gizmos: Gizmos, reserved_areas: Res<ReservedAreas>) {
+    for shape in reserved_areas.shapes() {
+        match shape {
+            ReservedShape::Circle { center, radius } => {
+                gizmos.circle_2d(*center, *radius, DEBUG_COLOR);
+            }
+            ReservedShape::Polygon(points) => {
+                gizmos.linestrip_2d(points.iter().chain(points.first()).copied(), DEBUG_COLOR);
+            }
+        }
+    }
+}
+
+pub struct ReservedAreasPlugin;
+
+impl Plugin for ReservedAreasPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReservedAreas>().add_systems(
+            Update,
+            draw_reserved_areas.run_if(
+                in_state(GameState::Gaming).and_then(debug_layer_active(DebugLayer::ReservedAreas)),
+            ),
+        );
+    }
+}
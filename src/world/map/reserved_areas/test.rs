@@ -0,0 +1,39 @@
+use bevy::prelude::*;
+
+use super::ReservedAreas;
+
+#[test]
+fn circles() {
+    let mut reserved_areas = ReservedAreas::default();
+    assert!(!reserved_areas.contains(Vec2::ZERO));
+
+    reserved_areas.reserve_circle(Vec2::new(10.0, 0.0), 5.0);
+    assert!(reserved_areas.contains(Vec2::new(10.0, 0.0)));
+    assert!(reserved_areas.contains(Vec2::new(15.0, 0.0)));
+    assert!(!reserved_areas.contains(Vec2::new(15.1, 0.0)));
+    assert!(!reserved_areas.contains(Vec2::ZERO));
+}
+
+#[test]
+fn polygons() {
+    let mut reserved_areas = ReservedAreas::default();
+    // An L shape, the corner at (5, 5) is cut out.
+    reserved_areas.reserve_polygon(vec![
+        Vec2::new(0.0, 0.0),
+        Vec2::new(10.0, 0.0),
+        Vec2::new(10.0, 5.0),
+        Vec2::new(5.0, 5.0),
+        Vec2::new(5.0, 10.0),
+        Vec2::new(0.0, 10.0),
+    ]);
+
+    assert!(reserved_areas.contains(Vec2::new(2.0, 2.0)));
+    assert!(reserved_areas.contains(Vec2::new(8.0, 2.0)));
+    assert!(reserved_areas.contains(Vec2::new(2.0, 8.0)));
+    assert!(!reserved_areas.contains(Vec2::new(8.0, 8.0)));
+    assert!(!reserved_areas.contains(Vec2::new(-1.0, 2.0)));
+
+    // Degenerate polygons are ignored.
+    reserved_areas.reserve_polygon(vec![Vec2::ZERO, Vec2::new(20.0, 20.0)]);
+    assert_eq!(reserved_areas.shapes().len(), 1);
+}
@@ -1,7 +1,11 @@
 pub mod camera;
+pub mod camera_director;
 pub mod camera_shake;
 pub mod ending;
+pub mod exploration;
 pub mod map;
+pub mod photo_mode;
+pub mod viewport;
 
 pub use camera::MainCamera;
 // pub use camera_shake::CameraShake;
@@ -18,8 +22,12 @@ impl Plugin for WorldPlugin {
         app.add_plugins((
             camera::CameraPlugin,
             camera_shake::CameraShakePlugin,
+            camera_director::CameraDirectorPlugin,
             map::MapPlugin,
             ending::EndingPlugin,
+            exploration::ExplorationPlugin,
+            viewport::ViewportPlugin,
+            photo_mode::PhotoModePlugin,
         ))
         .add_systems(OnExit(GameState::AssetLoading), configure_physics);
     }
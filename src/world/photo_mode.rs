@@ -0,0 +1,340 @@
+use std::path::PathBuf;
+
+use bevy::{
+    input::InputSystem,
+    prelude::*,
+    render::view::{screenshot::ScreenshotManager, VisibilitySystems},
+    transform::TransformSystem,
+    window::PrimaryWindow,
+};
+
+use super::{
+    camera::PROJECTION_SCALE,
+    camera_shake::{update_camera, CameraShake},
+    viewport::ViewportMode,
+    MainCamera,
+};
+use crate::{
+    player::input::{PlayerInput, PlayerInputOverrideSystemSet},
+    GameState,
+};
+
+// In world units per second at the default zoom.
+const PAN_SPEED: f32 = 150.0;
+// Each scroll step zooms by this factor.
+const ZOOM_STEP: f32 = 1.25;
+// Way beyond what `zoom_camera` allows, both in and out.
+const MIN_ZOOM: f32 = 0.25;
+const MAX_ZOOM: f32 = 20.0;
+// How far the camera may be panned away from where photo mode was entered.
+const MAX_PAN_DISTANCE: f32 = 20.0 * PROJECTION_SCALE;
+const PICTURES_SUBDIR: &str = "Lost Oppai";
+
+/// A tint over the whole screen.
+/// It's part of the UI, so it also ends up in the captures.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum PhotoFilter {
+    #[default]
+    None,
+    Warm,
+    Cool,
+    Dusk,
+    Faded,
+}
+
+impl PhotoFilter {
+    fn next(self) -> Self {
+        match self {
+            PhotoFilter::None => PhotoFilter::Warm,
+            PhotoFilter::Warm => PhotoFilter::Cool,
+            PhotoFilter::Cool => PhotoFilter::Dusk,
+            PhotoFilter::Dusk => PhotoFilter::Faded,
+            PhotoFilter::Faded => PhotoFilter::None,
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            PhotoFilter::None => Color::NONE,
+            PhotoFilter::Warm => Color::srgba(1.0, 0.6, 0.2, 0.18),
+            PhotoFilter::Cool => Color::srgba(0.2, 0.5, 1.0, 0.18),
+            PhotoFilter::Dusk => Color::srgba(0.25, 0.1, 0.35, 0.35),
+            PhotoFilter::Faded => Color::srgba(0.95, 0.9, 0.8, 0.3),
+        }
+    }
+}
+
+/// The camera is detached from the player and can be moved freely,
+/// all of the UI is hidden. Toggled with `P`, `C` cycles the filters.
+/// Screenshots (`F12`) work both in and out of photo mode.
+#[derive(Resource, Default)]
+pub struct PhotoMode {
+    pub active: bool,
+    pub filter: PhotoFilter,
+    origin: Vec2,
+    pos: Vec2,
+    previous_zoom: f32,
+    pan: Vec2,
+    zoom: f32,
+}
+
+/// Hidden together with the UI while in photo mode, for things in the world like hints.
+#[derive(Component)]
+pub struct HideInPhotoMode;
+
+/// The visibility the entity had before photo mode hid it.
+#[derive(Component)]
+struct HiddenByPhotoMode(Visibility);
+
+#[derive(Component)]
+struct PhotoFilterOverlay;
+
+pub fn photo_mode_active(photo_mode: Res<PhotoMode>) -> bool {
+    photo_mode.active
+}
+
+fn spawn_filter_overlay(mut commands: Commands) {
+    commands.spawn((
+        PhotoFilterOverlay,
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            background_color: Color::NONE.into(),
+            ..default()
+        },
+    ));
+}
+
+fn toggle_photo_mode(
+    player_input: Res<PlayerInput>,
+    mut photo_mode: ResMut<PhotoMode>,
+    mut q_camera: Query<(&Transform, &mut OrthographicProjection), With<MainCamera>>,
+) {
+    if !player_input.toggle_photo_mode {
+        return;
+    }
+    let (transform, mut projection) = match q_camera.get_single_mut() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    if photo_mode.active {
+        projection.scale = photo_mode.previous_zoom;
+        photo_mode.active = false;
+    } else {
+        photo_mode.active = true;
+        photo_mode.origin = transform.translation.truncate();
+        photo_mode.pos = photo_mode.origin;
+        photo_mode.previous_zoom = projection.scale;
+    }
+}
+
+fn exit_photo_mode(
+    mut photo_mode: ResMut<PhotoMode>,
+    mut q_projection: Query<&mut OrthographicProjection, With<MainCamera>>,
+) {
+    if !photo_mode.active {
+        return;
+    }
+
+    photo_mode.active = false;
+    if let Ok(mut projection) = q_projection.get_single_mut() {
+        projection.scale = photo_mode.previous_zoom;
+    }
+}
+
+/// The rest of the game shouldn't react to the input while in photo mode,
+/// e.g. the player would walk around or the volume would change when zooming.
+fn consume_input(mut player_input: ResMut<PlayerInput>, mut photo_mode: ResMut<PhotoMode>) {
+    let input = std::mem::take(&mut *player_input);
+    photo_mode.pan = input.move_direction;
+    photo_mode.zoom = input.scroll;
+
+    player_input.toggle_photo_mode = input.toggle_photo_mode;
+    player_input.cycle_photo_filter = input.cycle_photo_filter;
+    player_input.toggle_fullscreen = input.toggle_fullscreen;
+    player_input.cycle_viewport_mode = input.cycle_viewport_mode;
+}
+
+fn cycle_filter(player_input: Res<PlayerInput>, mut photo_mode: ResMut<PhotoMode>) {
+    if player_input.cycle_photo_filter {
+        photo_mode.filter = photo_mode.filter.next();
+    }
+}
+
+fn move_camera(
+    time: Res<Time>,
+    mut photo_mode: ResMut<PhotoMode>,
+    mut shake: ResMut<CameraShake>,
+    viewport_mode: Res<ViewportMode>,
+    mut q_projection: Query<&mut OrthographicProjection, With<MainCamera>>,
+) {
+    let mut projection = match q_projection.get_single_mut() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    if photo_mode.zoom != 0.0 && !viewport_mode.is_pixel_perfect() {
+        projection.scale =
+            (projection.scale * ZOOM_STEP.powf(photo_mode.zoom)).clamp(MIN_ZOOM, MAX_ZOOM);
+    }
+
+    let delta = photo_mode.pan * PAN_SPEED * projection.scale * time.delta_seconds();
+    let offset = (photo_mode.pos + delta - photo_mode.origin).clamp_length_max(MAX_PAN_DISTANCE);
+    photo_mode.pos = photo_mode.origin + offset;
+    shake.update_target(photo_mode.pos);
+}
+
+fn update_filter_overlay(
+    photo_mode: Res<PhotoMode>,
+    mut q_overlay: Query<&mut BackgroundColor, With<PhotoFilterOverlay>>,
+) {
+    let mut background = match q_overlay.get_single_mut() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    let color = if photo_mode.active {
+        photo_mode.filter.color()
+    } else {
+        Color::NONE
+    };
+    if background.0 != color {
+        background.0 = color;
+    }
+}
+
+/// This runs after everything else that may show the UI, so photo mode always wins.
+fn hide_ui(
+    mut commands: Commands,
+    mut q_ui_roots: Query<
+        (Entity, &mut Visibility),
+        (With<Node>, Without<Parent>, Without<PhotoFilterOverlay>),
+    >,
+    mut q_hidden: Query<(Entity, &mut Visibility), (With<HideInPhotoMode>, Without<Node>)>,
+) {
+    for (entity, mut visibility) in q_ui_roots.iter_mut().chain(q_hidden.iter_mut()) {
+        if *visibility != Visibility::Hidden {
+            // Hints may get despawned in the same frame.
+            commands
+                .entity(entity)
+                .try_insert(HiddenByPhotoMode(*visibility));
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
+
+fn restore_ui(
+    mut commands: Commands,
+    mut q_hidden: Query<(Entity, &mut Visibility, &HiddenByPhotoMode)>,
+) {
+    for (entity, mut visibility, hidden) in &mut q_hidden {
+        *visibility = hidden.0;
+        commands.entity(entity).remove::<HiddenByPhotoMode>();
+    }
+}
+
+/// The platform's pictures directory, falls back to the working directory.
+fn pictures_dir() -> PathBuf {
+    let pictures = std::env::var_os("XDG_PICTURES_DIR")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("USERPROFILE")
+                .or_else(|| std::env::var_os("HOME"))
+                .map(|home| PathBuf::from(home).join("Pictures"))
+        });
+
+    let Some(dir) = pictures.map(|p| p.join(PICTURES_SUBDIR)) else {
+        return PathBuf::from(".");
+    };
+    match std::fs::create_dir_all(&dir) {
+        Ok(()) => dir,
+        Err(err) => {
+            error!("failed to create {}, {}", dir.display(), err);
+            PathBuf::from(".")
+        }
+    }
+}
+
+/// On wasm, bevy downloads the screenshot with this file name instead.
+fn screenshot_path(counter: u32) -> PathBuf {
+    let file_name = format!(
+        "lost-oppai-{}-{}.png",
+        chrono::Local::now().format("%Y-%m-%d_%H-%M-%S"),
+        counter
+    );
+
+    if cfg!(target_arch = "wasm32") {
+        PathBuf::from(file_name)
+    } else {
+        pictures_dir().join(file_name)
+    }
+}
+
+fn take_screenshot(
+    keys: Res<ButtonInput<KeyCode>>,
+    main_window: Query<Entity, With<PrimaryWindow>>,
+    mut screenshot_manager: ResMut<ScreenshotManager>,
+    mut counter: Local<u32>,
+) {
+    if !keys.just_pressed(KeyCode::F12) {
+        return;
+    }
+    let window = match main_window.get_single() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    let path = screenshot_path(*counter);
+    *counter += 1;
+    match screenshot_manager.save_screenshot_to_disk(window, path) {
+        Ok(()) => {}
+        Err(err) => error!("failed to take screenshot, {}", err),
+    }
+}
+
+pub struct PhotoModePlugin;
+
+impl Plugin for PhotoModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PhotoMode>()
+            .add_systems(OnEnter(GameState::Gaming), spawn_filter_overlay)
+            .add_systems(OnExit(GameState::Gaming), exit_photo_mode)
+            .add_systems(
+                PreUpdate,
+                consume_input
+                    .after(InputSystem)
+                    .after(PlayerInputOverrideSystemSet)
+                    .run_if(photo_mode_active),
+            )
+            .add_systems(
+                Update,
+                (
+                    toggle_photo_mode.run_if(in_state(GameState::Gaming)),
+                    cycle_filter.run_if(photo_mode_active),
+                    update_filter_overlay,
+                    take_screenshot,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    move_camera
+                        .run_if(photo_mode_active)
+                        .before(TransformSystem::TransformPropagate)
+                        .before(update_camera),
+                    hide_ui
+                        .run_if(photo_mode_active)
+                        .before(VisibilitySystems::VisibilityPropagate),
+                    restore_ui
+                        .run_if(not(photo_mode_active))
+                        .before(VisibilitySystems::VisibilityPropagate),
+                ),
+            );
+    }
+}
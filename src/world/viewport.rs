@@ -0,0 +1,134 @@
+use bevy::{
+    prelude::*,
+    render::camera::{ScalingMode, Viewport},
+    window::{PrimaryWindow, WindowResized},
+};
+
+use crate::player::input::PlayerInput;
+
+use super::camera::{MainCamera, PROJECTION_SCALE};
+
+const ASPECT_RATIO: f32 = 16.0 / 9.0;
+
+/// How the game view gets fitted into the window.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Debug)]
+pub enum ViewportMode {
+    /// Use the whole window. Wider (or taller) windows than 16:9
+    /// reveal more of the world instead of stretching it.
+    #[default]
+    Ultrawide,
+    /// Keep the view at 16:9 and fill the rest of the window with black bars.
+    Letterbox,
+    /// Like `Letterbox`, but only scale by whole numbers
+    /// so that every texel covers the same amount of pixels.
+    /// Zooming and camera shake are disabled, they would break the integer scaling.
+    PixelPerfect,
+}
+
+impl ViewportMode {
+    pub fn is_pixel_perfect(self) -> bool {
+        self == ViewportMode::PixelPerfect
+    }
+
+    fn next(self) -> Self {
+        match self {
+            ViewportMode::Ultrawide => ViewportMode::Letterbox,
+            ViewportMode::Letterbox => ViewportMode::PixelPerfect,
+            ViewportMode::PixelPerfect => ViewportMode::Ultrawide,
+        }
+    }
+}
+
+/// The largest 16:9 rectangle that fits into the window, centered.
+fn letterbox_viewport(window_size: UVec2) -> Viewport {
+    let window = window_size.as_vec2();
+    let size = if window.x / window.y > ASPECT_RATIO {
+        Vec2::new(window.y * ASPECT_RATIO, window.y)
+    } else {
+        Vec2::new(window.x, window.x / ASPECT_RATIO)
+    };
+    centered_viewport(window_size, size.as_uvec2())
+}
+
+/// Returns the viewport together with the amount of pixels per world unit.
+fn pixel_perfect_viewport(window_size: UVec2) -> (Viewport, f32) {
+    let window = window_size.as_vec2();
+    let max_height = window.y.min(window.x / ASPECT_RATIO);
+    let scale = (max_height / PROJECTION_SCALE).floor().max(1.0);
+    let size = Vec2::new(PROJECTION_SCALE * ASPECT_RATIO, PROJECTION_SCALE) * scale;
+    (centered_viewport(window_size, size.as_uvec2()), scale)
+}
+
+fn centered_viewport(window_size: UVec2, size: UVec2) -> Viewport {
+    let size = size.min(window_size);
+    Viewport {
+        physical_position: (window_size - size) / 2,
+        physical_size: size.max(UVec2::ONE),
+        ..default()
+    }
+}
+
+fn cycle_viewport_mode(player_input: Res<PlayerInput>, mut viewport_mode: ResMut<ViewportMode>) {
+    if !player_input.cycle_viewport_mode {
+        return;
+    }
+
+    *viewport_mode = viewport_mode.next();
+    info!("viewport mode: {:?}", *viewport_mode);
+}
+
+fn update_viewport(
+    viewport_mode: Res<ViewportMode>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut q_camera: Query<(&mut Camera, &mut OrthographicProjection), With<MainCamera>>,
+    mut ev_window_resized: EventReader<WindowResized>,
+) {
+    if !viewport_mode.is_changed() && ev_window_resized.is_empty() {
+        return;
+    }
+    ev_window_resized.clear();
+
+    let window = match q_window.get_single() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    let (mut camera, mut projection) = match q_camera.get_single_mut() {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+
+    let window_size = UVec2::new(window.physical_width(), window.physical_height());
+    if window_size.x == 0 || window_size.y == 0 {
+        return;
+    }
+
+    match *viewport_mode {
+        ViewportMode::Ultrawide => {
+            camera.viewport = None;
+            projection.scaling_mode = ScalingMode::AutoMin {
+                min_width: PROJECTION_SCALE * ASPECT_RATIO,
+                min_height: PROJECTION_SCALE,
+            };
+        }
+        ViewportMode::Letterbox => {
+            camera.viewport = Some(letterbox_viewport(window_size));
+            projection.scaling_mode = ScalingMode::FixedVertical(PROJECTION_SCALE);
+        }
+        ViewportMode::PixelPerfect => {
+            let (viewport, scale) = pixel_perfect_viewport(window_size);
+            camera.viewport = Some(viewport);
+            // The projection works with logical pixels.
+            projection.scaling_mode = ScalingMode::WindowSize(scale / window.scale_factor());
+            projection.scale = 1.0;
+        }
+    }
+}
+
+pub struct ViewportPlugin;
+
+impl Plugin for ViewportPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ViewportMode>()
+            .add_systems(Update, (cycle_viewport_mode, update_viewport).chain());
+    }
+}